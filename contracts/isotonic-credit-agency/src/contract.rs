@@ -1,16 +1,21 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response};
+use cosmwasm_std::{
+    to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Reply, Response, StdResult,
+};
 use cw2::set_contract_version;
-use cw_utils::parse_reply_instantiate_data;
+use cw_storage_plus::Map;
+use cw_utils::must_pay;
+use serde::{Deserialize, Serialize};
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Config, CONFIG, NEXT_REPLY_ID};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use crate::state::{Config, MarketState, CONFIG, DEFAULT_MARKET_ID, MARKETS, NEXT_REPLY_ID};
 
 use isotonic_market::msg::ReceiveMsg::RepayTo as MarketRepayTo;
 
 use either::Either;
+use utils::events::{self, json};
 use utils::token::Token;
 
 // version info for migration info
@@ -26,6 +31,8 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    restricted::validate_liquidation_auction(&msg.liquidation_auction)?;
+
     // TODO: should we validate Tokens?
     let cfg = Config {
         gov_contract: deps.api.addr_validate(&msg.gov_contract)?,
@@ -35,7 +42,12 @@ pub fn instantiate(
         common_token: msg.common_token,
         liquidation_price: msg.liquidation_price,
         borrow_limit_ratio: msg.borrow_limit_ratio,
+        liquidation_auction: msg.liquidation_auction,
+        deleverage_fee: msg.deleverage_fee,
+        param_change_delay: msg.param_change_delay,
+        risk_steward: None,
     };
+    restricted::validate_deleverage_fee(&cfg)?;
     CONFIG.save(deps.storage, &cfg)?;
     NEXT_REPLY_ID.save(deps.storage, &0)?;
 
@@ -55,9 +67,13 @@ pub fn execute(
 
     match msg {
         CreateMarket(market_cfg) => execute::create_market(deps, env, info, market_cfg),
+        CreateMarketFromTemplate(market_cfg) => {
+            execute::create_market_from_template(deps, env, info, market_cfg)
+        }
         Liquidate {
             account,
-            collateral_denom,
+            debt_market,
+            collateral_market,
         } => {
             let account = deps.api.addr_validate(&account)?;
 
@@ -71,7 +87,57 @@ pub fn execute(
                 Token::Native(info.funds[0].denom.clone()),
             );
 
-            execute::liquidate(deps, info.sender, account, coin, collateral_denom)
+            execute::liquidate(
+                deps,
+                env,
+                info.sender,
+                account,
+                coin,
+                debt_market,
+                collateral_market,
+            )
+        }
+        LiquidateWithSwap {
+            account,
+            debt_denom,
+            collateral_denom,
+            repay_amount,
+            min_profit,
+            debt_market_id,
+            collateral_market_id,
+        } => {
+            let account = deps.api.addr_validate(&account)?;
+            execute::liquidate_with_swap(
+                deps,
+                env,
+                info.sender,
+                account,
+                debt_denom.into_coin(repay_amount),
+                debt_market_id.unwrap_or(DEFAULT_MARKET_ID),
+                (
+                    collateral_denom,
+                    collateral_market_id.unwrap_or(DEFAULT_MARKET_ID),
+                ),
+                min_profit,
+            )
+        }
+        SettleLiquidationWithSwap {
+            liquidator,
+            collateral_market,
+            ctokens_before,
+            min_profit,
+        } => execute::settle_liquidation_with_swap(
+            deps,
+            env,
+            info,
+            liquidator,
+            collateral_market,
+            ctokens_before,
+            min_profit,
+        ),
+        UpdateLiquidationAuction { account } => {
+            let account = deps.api.addr_validate(&account)?;
+            execute::update_liquidation_auction(deps, env, info.sender, account)
         }
         EnterMarket { account } => {
             let account = deps.api.addr_validate(&account)?;
@@ -82,41 +148,225 @@ pub fn execute(
             execute::exit_market(deps, info, market)
         }
         RepayWithCollateral {
+            collateral_market,
+            max_collateral,
+            debt_market,
+            amount_to_repay,
+        } => execute::repay_with_collateral(
+            deps,
+            env,
+            info.sender,
+            collateral_market,
+            max_collateral,
+            debt_market,
+            amount_to_repay,
+        ),
+        Deleverage {
+            max_collateral,
+            amount_to_repay,
+            collateral_market_id,
+            debt_market_id,
+        } => execute::deleverage(
+            deps,
+            env,
+            info.sender,
             max_collateral,
+            collateral_market_id.unwrap_or(DEFAULT_MARKET_ID),
             amount_to_repay,
-        } => execute::repay_with_collateral(deps, info.sender, max_collateral, amount_to_repay),
+            debt_market_id.unwrap_or(DEFAULT_MARKET_ID),
+        ),
+        OpenLeveragedPosition {
+            collateral_denom,
+            debt_denom,
+            initial_amount,
+            target_leverage,
+            max_slippage,
+            collateral_market_id,
+            debt_market_id,
+        } => execute::open_leveraged_position(
+            deps,
+            env,
+            info,
+            (
+                collateral_denom,
+                collateral_market_id.unwrap_or(DEFAULT_MARKET_ID),
+            ),
+            (debt_denom, debt_market_id.unwrap_or(DEFAULT_MARKET_ID)),
+            initial_amount,
+            target_leverage,
+            max_slippage,
+        ),
+        CloseLeveragedPosition {
+            collateral_denom,
+            debt_denom,
+            max_slippage,
+            collateral_market_id,
+            debt_market_id,
+        } => execute::close_leveraged_position(
+            deps,
+            env,
+            info.sender,
+            (
+                collateral_denom,
+                collateral_market_id.unwrap_or(DEFAULT_MARKET_ID),
+            ),
+            (debt_denom, debt_market_id.unwrap_or(DEFAULT_MARKET_ID)),
+            max_slippage,
+        ),
+        SettleLeveragedPosition {
+            account,
+            collateral_denom,
+            collateral_market_id,
+            balance_before,
+        } => execute::settle_leveraged_position(
+            deps,
+            env,
+            info,
+            account,
+            (collateral_denom, collateral_market_id),
+            balance_before,
+        ),
+        SwapCollateral {
+            from,
+            to_denom,
+            min_receive,
+            from_market_id,
+            to_market_id,
+        } => execute::swap_collateral(
+            deps,
+            env,
+            info.sender,
+            from,
+            from_market_id.unwrap_or(DEFAULT_MARKET_ID),
+            (to_denom, to_market_id.unwrap_or(DEFAULT_MARKET_ID)),
+            min_receive,
+        ),
+        SettleCollateralSwap {
+            account,
+            to_denom,
+            to_market_id,
+            balance_before,
+        } => execute::settle_collateral_swap(
+            deps,
+            env,
+            info,
+            account,
+            (to_denom, to_market_id),
+            balance_before,
+        ),
+        SwapDebt {
+            from_denom,
+            to_denom,
+            amount,
+            max_new_debt,
+            from_market_id,
+            to_market_id,
+        } => execute::swap_debt(
+            deps,
+            env,
+            info.sender,
+            (from_denom, from_market_id.unwrap_or(DEFAULT_MARKET_ID)),
+            (to_denom, to_market_id.unwrap_or(DEFAULT_MARKET_ID)),
+            amount,
+            max_new_debt,
+        ),
+        SettleDebtSwap {
+            account,
+            from_denom,
+            from_market_id,
+            balance_before,
+        } => execute::settle_debt_swap(
+            deps,
+            env,
+            info,
+            account,
+            (from_denom, from_market_id),
+            balance_before,
+        ),
         Receive(msg) => execute::receive_cw20_message(deps, env, info, msg),
-        AdjustMarketId { new_market_id } => restricted::adjust_market_id(deps, info, new_market_id),
-        AdjustTokenId { new_token_id } => restricted::adjust_token_id(deps, info, new_token_id),
-        AdjustCommonToken { new_common_token } => {
-            restricted::adjust_common_token(deps, info, new_common_token)
+        EmitRewards { markets } => execute::emit_rewards(deps, env, info.sender, markets),
+        ClaimRewards { markets } => execute::claim_rewards(deps, env, info, markets),
+        ProposeParamChange { change } => restricted::propose_param_change(deps, env, info, change),
+        ExecuteParamChange { id } => restricted::execute_param_change(deps, env, info, id),
+        CancelParamChange { id } => restricted::cancel_param_change(deps, info, id),
+        StewardAdjust { market, change } => {
+            restricted::steward_adjust(deps, env, info, market, change)
+        }
+        RemoveMarket { market } => execute::remove_market(deps, info.sender, market),
+        ProposeNewGovernance { new_governance } => {
+            restricted::propose_new_governance(deps, info, new_governance)
+        }
+        AcceptGovernance {} => restricted::accept_governance(deps, info),
+        FundEmission {
+            market,
+            duration,
+            supplier_share,
+        } => {
+            let reward = match CONFIG.load(deps.storage)?.reward_token {
+                Token::Native(denom) => {
+                    let amount = must_pay(&info, &denom)?;
+                    Token::Native(denom).into_coin(amount)
+                }
+                token => return Err(ContractError::InvalidRewardToken(token.denom())),
+            };
+            restricted::fund_emission(
+                deps,
+                env,
+                info.sender,
+                market,
+                duration,
+                supplier_share,
+                reward,
+            )
         }
         MigrateMarket {
             contract,
             migrate_msg,
         } => restricted::migrate_market(deps, info, contract, migrate_msg),
+        MigrateAllMarkets {
+            migrate_msg,
+            start_after,
+            limit,
+        } => restricted::migrate_all_markets(deps, info, migrate_msg, start_after, limit),
     }
 }
 
 mod execute {
     use super::*;
 
-    use cosmwasm_std::{ensure_eq, from_binary, StdError, StdResult, SubMsg, WasmMsg};
-    use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+    use cosmwasm_std::{
+        coins, ensure_eq, from_binary, CosmosMsg, Decimal, Event, StdError, StdResult, Storage,
+        SubMsg, Uint128, WasmMsg,
+    };
+    use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20ReceiveMsg};
     use utils::{
+        amount::token_to_base,
         coin::Coin,
         credit_line::{CreditLineResponse, CreditLineValues},
-        price::{coin_times_price_rate, PriceRate},
+        instantiate2::predict_address,
+        price::PriceRate,
+        wyndex::SimulateSwapOperationsResponse,
     };
 
     use crate::{
-        msg::{MarketConfig, ReceiveMsg},
-        state::{MarketState, ENTERED_MARKETS, MARKETS, REPLY_IDS},
+        msg::{MarketConfig, MarketResponse, ReceiveMsg, TemplateMarketConfig},
+        state::{
+            MarketCode, MarketState, EMISSIONS, ENTERED_MARKETS, ISOLATED_MARKETS,
+            LIQUIDATION_AUCTIONS, MARKETS, MARKET_NONCES, MARKET_TEMPLATES,
+        },
     };
     use isotonic_market::{
-        msg::{ExecuteMsg as MarketExecuteMsg, QueryMsg as MarketQueryMsg},
+        msg::{
+            ExecuteMsg as MarketExecuteMsg, LiquidationSeizeResponse, QueryMsg as MarketQueryMsg,
+            ReceiveMsg as MarketReceiveMsg, TotalDebtResponse, WindDownResponse,
+        },
         state::Config as MarketConfiguration,
     };
+    use isotonic_token::msg::{
+        ExecuteMsg as TokenExecuteMsg, MultiplierResponse, QueryMsg as TokenQueryMsg,
+        TokenInfoResponse,
+    };
+    use wyndex_oracle::{msg::QueryMsg as OracleQueryMsg, state::Config as OracleConfig};
 
     pub fn create_market(
         deps: DepsMut,
@@ -124,8 +374,6 @@ mod execute {
         info: MessageInfo,
         market_cfg: MarketConfig,
     ) -> Result<Response, ContractError> {
-        let market_token = market_cfg.market_token;
-
         let cfg = CONFIG.load(deps.storage)?;
 
         // Only governance contract can instantiate a market.
@@ -135,27 +383,160 @@ mod execute {
             ContractError::Unauthorized {}
         );
 
+        let market_token = market_cfg.market_token.clone();
+        let market_id = market_cfg.market_id;
+        let collateral_ratio = market_cfg.collateral_ratio;
+        let (market, market_instantiate) = instantiate_market_msg(deps, &env, cfg, market_cfg)?;
+        let event = events::CreateMarket {
+            sender: info.sender.clone(),
+            market: market.clone(),
+            market_token,
+            market_id,
+            collateral_ratio,
+            template: None,
+        };
+
+        Ok(Response::new()
+            .add_attribute("action", "create_market")
+            .add_attribute("sender", info.sender)
+            .add_attribute("market", market)
+            .add_event(event.into())
+            .add_message(market_instantiate))
+    }
+
+    /// Handler for `ExecuteMsg::CreateMarketFromTemplate`
+    pub fn create_market_from_template(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        market_cfg: TemplateMarketConfig,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        let template = MARKET_TEMPLATES
+            .may_load(deps.storage, &market_cfg.template)?
+            .ok_or_else(|| ContractError::NoMarketTemplate(market_cfg.template.clone()))?;
+
+        if market_cfg.collateral_ratio < template.min_collateral_ratio
+            || market_cfg.collateral_ratio > template.max_collateral_ratio
+        {
+            return Err(ContractError::CollateralRatioOutOfTemplate(
+                market_cfg.collateral_ratio,
+            ));
+        }
+
+        // Templates are meant for new assets - default markets and assets with markets created
+        // by governance are left to governance
+        if market_cfg.market_id == DEFAULT_MARKET_ID {
+            return Err(ContractError::TemplateMarketDefaultId {});
+        }
+        for market in MARKETS.prefix(&market_cfg.market_token).range(
+            deps.storage,
+            None,
+            None,
+            Order::Ascending,
+        ) {
+            let (_, state) = market?;
+            if !ISOLATED_MARKETS.has(deps.storage, &state.to_addr()) {
+                return Err(ContractError::GovernanceMarketExists(
+                    market_cfg.market_token.denom(),
+                ));
+            }
+        }
+
+        // Market prices come from the oracle, so the pool has to be known to it
+        let pool: StdResult<Addr> = deps.querier.query_wasm_smart(
+            &template.price_oracle,
+            &OracleQueryMsg::PoolAddress {
+                first_asset: market_cfg.market_token.clone().into(),
+                second_asset: cfg.common_token.clone().into(),
+            },
+        );
+        if !matches!(pool, Ok(pool) if pool.as_str() == market_cfg.pair_contract) {
+            return Err(ContractError::OraclePoolNotRegistered {
+                pair: market_cfg.pair_contract,
+                market_token: market_cfg.market_token.denom(),
+            });
+        }
+
+        let (market, market_instantiate) = instantiate_market_msg(
+            deps.branch(),
+            &env,
+            cfg,
+            MarketConfig {
+                name: market_cfg.name,
+                symbol: market_cfg.symbol,
+                decimals: market_cfg.decimals,
+                market_token: market_cfg.market_token.clone(),
+                market_id: market_cfg.market_id,
+                market_cap: Some(template.market_cap),
+                interest_rate: template.interest_rate,
+                interest_charge_period: template.interest_charge_period,
+                collateral_ratio: market_cfg.collateral_ratio,
+                price_oracle: template.price_oracle.to_string(),
+                reserve_factor: template.reserve_factor,
+            },
+        )?;
+        ISOLATED_MARKETS.save(deps.storage, &market, &template.isolated_credit_cap)?;
+
+        let event = events::CreateMarket {
+            sender: info.sender.clone(),
+            market: market.clone(),
+            market_token: market_cfg.market_token,
+            market_id: market_cfg.market_id,
+            collateral_ratio: market_cfg.collateral_ratio,
+            template: Some(market_cfg.template.clone()),
+        };
+
+        Ok(Response::new()
+            .add_attribute("action", "create_market_from_template")
+            .add_attribute("sender", info.sender)
+            .add_attribute("template", market_cfg.template)
+            .add_attribute("market", market)
+            .add_event(event.into())
+            .add_message(market_instantiate))
+    }
+
+    /// Validates the market config, records the market under its predicted address and returns
+    /// the address with the instantiation message. The market is instantiated from
+    /// `isotonic_market_id`, which is the code of this crate's market version.
+    fn instantiate_market_msg(
+        deps: DepsMut,
+        env: &Env,
+        cfg: Config,
+        market_cfg: MarketConfig,
+    ) -> Result<(Addr, WasmMsg), ContractError> {
+        let market_token = market_cfg.market_token;
+        let market_id = market_cfg.market_id;
+
         // Collateral ratio must be lower then liquidation price, otherwise
         // liquidation could decrese debt less then it decreases potential credit.
-        if market_cfg.collateral_ratio >= cfg.liquidation_price {
+        if market_cfg.collateral_ratio >= cfg.min_liquidation_price() {
             // TODO: shouldn't we use also a margin? Collateral ration should be 90% of liquidation price.
             return Err(ContractError::MarketCfgCollateralFailure {});
         }
 
-        if let Some(state) = MARKETS.may_load(deps.storage, &market_token)? {
-            use MarketState::*;
-
-            let err = match state {
-                Instantiating => ContractError::MarketCreating(market_token.denom()),
-                Ready(_) => ContractError::MarketAlreadyExists(market_token.denom()),
-            };
-            return Err(err);
+        if MARKETS.has(deps.storage, (&market_token, market_id)) {
+            return Err(ContractError::MarketAlreadyExists(market_token.denom()));
         }
-        MARKETS.save(deps.storage, &market_token, &MarketState::Instantiating)?;
-
-        let reply_id =
-            NEXT_REPLY_ID.update(deps.storage, |id| -> Result<_, StdError> { Ok(id + 1) })?;
-        REPLY_IDS.save(deps.storage, reply_id, &market_token)?;
+        let salt = query::market_salt(deps.storage, &market_token, market_id)?;
+        let market_addr = predict_address(
+            deps.as_ref(),
+            cfg.isotonic_market_id,
+            &env.contract.address,
+            &salt,
+        )?;
+        let code = MarketCode {
+            code_id: deps
+                .querier
+                .query_wasm_code_info(cfg.isotonic_market_id)?
+                .code_id,
+            version: isotonic_market::contract::CONTRACT_VERSION.to_owned(),
+        };
+        MARKETS.save(
+            deps.storage,
+            (&market_token, market_id),
+            &MarketState::Ready(market_addr.clone(), code),
+        )?;
 
         let market_msg = isotonic_market::msg::InstantiateMsg {
             // Fields required for the isotonic-token instantiation.
@@ -175,19 +556,18 @@ mod execute {
             reserve_factor: market_cfg.reserve_factor,
             gov_contract: cfg.gov_contract.to_string(),
             borrow_limit_ratio: cfg.borrow_limit_ratio,
+            param_change_delay: cfg.param_change_delay,
         };
-        let market_instantiate = WasmMsg::Instantiate {
+        let market_instantiate = WasmMsg::Instantiate2 {
             admin: Some(env.contract.address.to_string()),
             code_id: cfg.isotonic_market_id,
             msg: to_binary(&market_msg)?,
             funds: vec![],
-            label: format!("market_contract_{}", market_token),
+            label: format!("market_contract_{}_{}", market_token, market_id),
+            salt,
         };
 
-        Ok(Response::new()
-            .add_attribute("action", "create_market")
-            .add_attribute("sender", info.sender)
-            .add_submessage(SubMsg::reply_on_success(market_instantiate, reply_id)))
+        Ok((market_addr, market_instantiate))
     }
 
     fn create_repay_to_submessage(
@@ -230,36 +610,55 @@ mod execute {
     /// Liquidate implements the liquidation logic for both native and cw20 tokens.
     pub fn liquidate(
         deps: DepsMut,
+        env: Env,
         sender: Addr,
         // Account to liquidate.
         account: Addr,
         // Native or cw20 tokens sent along with the tx.
         coins: utils::coin::Coin,
-        collateral_denom: Token,
+        debt_market: String,
+        collateral_market: String,
     ) -> Result<Response, ContractError> {
         let cfg = CONFIG.load(deps.storage)?;
 
+        let debt_market = query::market_by_address(deps.as_ref(), &debt_market)?;
+        if debt_market.market_token != coins.denom {
+            return Err(ContractError::MarketTokenMismatch {
+                market: debt_market.market,
+                token: coins.denom.denom(),
+            });
+        }
+        let collateral_market = query::market_by_address(deps.as_ref(), &collateral_market)?;
+
         // assert that given account actually has more debt then credit
-        let total_credit_line = query::total_credit_line(deps.as_ref(), account.to_string())?;
-        let total_credit_line = total_credit_line.validate(&cfg.common_token)?;
-        if total_credit_line.debt <= total_credit_line.credit_line {
+        if !query::can_liquidate(deps.as_ref(), &cfg, &account)?
+            && !query::forced_liquidation(deps.as_ref(), &debt_market.market)?
+        {
             return Err(ContractError::LiquidationNotAllowed {});
         }
 
-        // Count debt and repay it. This requires that market returns error if repaying more then balance.
-        let debt_market = query::market(deps.as_ref(), &coins.denom)?.market;
-
-        let repay_to_msg =
-            create_repay_to_submessage(coins.clone(), debt_market.clone(), account.clone())?;
+        let liquidation_price = query::liquidation_price(deps.as_ref(), &env, &cfg, &account)?;
+        restart_liquidation_auction(deps.storage, &env, &cfg, &account)?;
 
-        // find price rate of collateral denom
-        let price_response: PriceRate = deps.querier.query_wasm_smart(
-            debt_market.to_string(),
-            &MarketQueryMsg::PriceMarketLocalPerCommon {},
+        let plan = query::liquidation_plan(
+            deps.as_ref(),
+            &coins,
+            debt_market.market,
+            collateral_market.market,
         )?;
 
-        // find market with wanted collateral_denom
-        let collateral_market = query::market(deps.as_ref(), &collateral_denom)?.market;
+        let event = events::Liquidate {
+            sender: sender.clone(),
+            account: account.clone(),
+            debt_market: plan.debt_market.clone(),
+            collateral_market: plan.collateral_market.clone(),
+            amount: coins.clone(),
+            amount_common: cfg.common_token.amount(plan.repay_value),
+            liquidation_price,
+        };
+
+        // Count debt and repay it. This requires that market returns error if repaying more then balance.
+        let repay_to_msg = create_repay_to_submessage(coins, plan.debt_market, account.clone())?;
 
         // transfer claimed amount as reward
         let msg = to_binary(&isotonic_market::msg::ExecuteMsg::TransferFrom {
@@ -267,22 +666,203 @@ mod execute {
             destination: sender.to_string(),
             // transfer repaid amount represented as amount of common tokens, which is
             // calculated into collateral_denom's amount later in the market
-            amount: coin_times_price_rate(&coins, &price_response)?.amount,
-            liquidation_price: cfg.liquidation_price,
+            amount: plan.repay_value,
+            liquidation_price,
         })?;
         let transfer_from_msg = SubMsg::new(WasmMsg::Execute {
-            contract_addr: collateral_market.to_string(),
+            contract_addr: plan.collateral_market.to_string(),
             msg,
             funds: vec![],
         });
+        // Partial liquidation could make the account healthy again
+        let recheck_msg = recheck_liquidation_auction(deps.storage, &env, &account)?;
 
         Ok(Response::new()
             .add_attribute("action", "liquidate")
             .add_attribute("liquidator", sender)
             .add_attribute("account", account)
+            .add_attribute("collateral_denom", collateral_market.market_token.denom())
+            .add_event(event.into())
+            .add_submessage(repay_to_msg)
+            .add_submessage(transfer_from_msg)
+            .add_messages(recheck_msg))
+    }
+
+    /// Handler for `ExecuteMsg::LiquidateWithSwap`
+    /// Collateral is seized by the credit agency itself, then sold for the debt token with
+    /// `SwapWithdrawFrom` and the debt is repaid. Whatever is left of the collateral is withdrawn
+    /// and sent to the liquidator in the final `SettleLiquidationWithSwap` step.
+    #[allow(clippy::too_many_arguments)]
+    pub fn liquidate_with_swap(
+        deps: DepsMut,
+        env: Env,
+        sender: Addr,
+        account: Addr,
+        repay: Coin,
+        debt_market_id: u32,
+        (collateral_denom, collateral_market_id): (Token, u32),
+        min_profit: Uint128,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        let debt_market = query::market(deps.as_ref(), &repay.denom, debt_market_id)?.market;
+        let collateral_market =
+            query::market(deps.as_ref(), &collateral_denom, collateral_market_id)?.market;
+
+        if !query::can_liquidate(deps.as_ref(), &cfg, &account)?
+            && !query::forced_liquidation(deps.as_ref(), &debt_market)?
+        {
+            return Err(ContractError::LiquidationNotAllowed {});
+        }
+
+        let liquidation_price = query::liquidation_price(deps.as_ref(), &env, &cfg, &account)?;
+        restart_liquidation_auction(deps.storage, &env, &cfg, &account)?;
+
+        let plan = query::liquidation_plan(deps.as_ref(), &repay, debt_market, collateral_market)?;
+        let this = env.contract.address;
+
+        let seize: LiquidationSeizeResponse = deps.querier.query_wasm_smart(
+            &plan.collateral_market,
+            &MarketQueryMsg::LiquidationSeize {
+                account: account.to_string(),
+                amount: plan.repay_value,
+                liquidation_price,
+            },
+        )?;
+        let collateral_market_cfg: MarketConfiguration = deps
+            .querier
+            .query_wasm_smart(&plan.collateral_market, &MarketQueryMsg::Configuration {})?;
+        let ctokens_before: BalanceResponse = deps.querier.query_wasm_smart(
+            &collateral_market_cfg.ctoken_contract,
+            &TokenQueryMsg::Balance {
+                address: this.to_string(),
+            },
+        )?;
+
+        let transfer_from_msg = WasmMsg::Execute {
+            contract_addr: plan.collateral_market.to_string(),
+            msg: to_binary(&MarketExecuteMsg::TransferFrom {
+                source: account.to_string(),
+                destination: this.to_string(),
+                amount: plan.repay_value,
+                liquidation_price,
+            })?,
+            funds: vec![],
+        };
+
+        // Sell no more than seized to buy the debt token
+        let swap_withdraw_from_msg = WasmMsg::Execute {
+            contract_addr: plan.collateral_market.to_string(),
+            msg: to_binary(&MarketExecuteMsg::SwapWithdrawFrom {
+                account: this.to_string(),
+                buy: repay.clone(),
+                sell_limit: seize.seized.amount,
+            })?,
+            funds: vec![],
+        };
+
+        let event = events::LiquidateWithSwap {
+            sender: sender.clone(),
+            account: account.clone(),
+            debt_market: plan.debt_market.clone(),
+            collateral_market: plan.collateral_market.clone(),
+            amount: repay.clone(),
+            amount_common: cfg.common_token.amount(plan.repay_value),
+            liquidation_price,
+            min_profit,
+        };
+
+        let repay_to_msg = create_repay_to_submessage(repay, plan.debt_market, account.clone())?;
+
+        let settle_msg = WasmMsg::Execute {
+            contract_addr: this.to_string(),
+            msg: to_binary(&ExecuteMsg::SettleLiquidationWithSwap {
+                liquidator: sender.to_string(),
+                collateral_market: plan.collateral_market.to_string(),
+                ctokens_before: ctokens_before.balance,
+                min_profit,
+            })?,
+            funds: vec![],
+        };
+        let recheck_msg = recheck_liquidation_auction(deps.storage, &env, &account)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "liquidate_with_swap")
+            .add_attribute("liquidator", sender)
+            .add_attribute("account", account)
             .add_attribute("collateral_denom", collateral_denom.denom())
+            .add_event(event.into())
+            .add_message(transfer_from_msg)
+            .add_message(swap_withdraw_from_msg)
             .add_submessage(repay_to_msg)
-            .add_submessage(transfer_from_msg))
+            .add_message(settle_msg)
+            .add_messages(recheck_msg))
+    }
+
+    /// Handler for `ExecuteMsg::SettleLiquidationWithSwap`
+    pub fn settle_liquidation_with_swap(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        liquidator: String,
+        collateral_market: String,
+        ctokens_before: Uint128,
+        min_profit: Uint128,
+    ) -> Result<Response, ContractError> {
+        if info.sender != env.contract.address {
+            return Err(ContractError::Unauthorized {});
+        }
+        let liquidator = deps.api.addr_validate(&liquidator)?;
+        let collateral_market_cfg: MarketConfiguration = deps
+            .querier
+            .query_wasm_smart(&collateral_market, &MarketQueryMsg::Configuration {})?;
+        let ctoken = collateral_market_cfg.ctoken_contract;
+
+        let balance: BalanceResponse = deps.querier.query_wasm_smart(
+            &ctoken,
+            &TokenQueryMsg::Balance {
+                address: env.contract.address.to_string(),
+            },
+        )?;
+        let multiplier: MultiplierResponse = deps
+            .querier
+            .query_wasm_smart(&ctoken, &TokenQueryMsg::Multiplier {})?;
+
+        let profit = balance.balance.saturating_sub(ctokens_before);
+        let profit_base = token_to_base(profit, multiplier.multiplier);
+        if profit_base < min_profit {
+            return Err(ContractError::LiquidationProfitTooLow {
+                profit: profit_base,
+                min_profit,
+            });
+        }
+
+        let collateral_denom = collateral_market_cfg.market_token;
+        let event = events::SettleLiquidation {
+            sender: info.sender,
+            recipient: liquidator.clone(),
+            amount: collateral_denom.amount(profit_base),
+        };
+        let mut response = Response::new()
+            .add_attribute("action", "settle_liquidation_with_swap")
+            .add_attribute("liquidator", liquidator.to_string())
+            .add_attribute("profit", profit_base)
+            .add_event(event.into());
+
+        // Collateral is withdrawn to the credit agency first, then passed to the liquidator
+        if !profit_base.is_zero() {
+            response = response
+                .add_message(WasmMsg::Execute {
+                    contract_addr: collateral_market,
+                    msg: to_binary(&MarketExecuteMsg::WithdrawFrom {
+                        account: env.contract.address.to_string(),
+                        amount: profit_base,
+                    })?,
+                    funds: vec![],
+                })
+                .add_message(collateral_denom.send_msg(&liquidator, profit_base)?);
+        }
+
+        Ok(response)
     }
 
     pub fn enter_market(
@@ -298,10 +878,107 @@ mod execute {
             Ok(markets)
         })?;
 
+        // Deposit could make the account healthy again
+        if LIQUIDATION_AUCTIONS.has(deps.storage, &account) {
+            let cfg = CONFIG.load(deps.storage)?;
+            if !query::can_liquidate(deps.as_ref(), &cfg, &account)? {
+                LIQUIDATION_AUCTIONS.remove(deps.storage, &account);
+            }
+        }
+
+        let event = events::EnterMarket {
+            sender: market.clone(),
+            account: account.clone(),
+            market: market.clone(),
+        };
         Ok(Response::new()
             .add_attribute("action", "enter_market")
             .add_attribute("market", market)
-            .add_attribute("account", account))
+            .add_attribute("account", account)
+            .add_event(event.into()))
+    }
+
+    /// Records the start of the liquidation auction of the account, unless it is running already
+    /// or the auction is disabled
+    fn start_liquidation_auction(
+        storage: &mut dyn Storage,
+        env: &Env,
+        cfg: &Config,
+        account: &Addr,
+    ) -> StdResult<()> {
+        if cfg.liquidation_auction.is_some() && !LIQUIDATION_AUCTIONS.has(storage, account) {
+            LIQUIDATION_AUCTIONS.save(storage, account, &env.block.time)?;
+        }
+        Ok(())
+    }
+
+    /// Restarts the liquidation auction of the account from the current block once a liquidation
+    /// used its discount. The rest of the debt is auctioned from the minimal discount again, so
+    /// the start left over from an earlier unhealthy period, which nobody reset when prices
+    /// recovered, gives the grown discount to a single liquidation at most.
+    fn restart_liquidation_auction(
+        storage: &mut dyn Storage,
+        env: &Env,
+        cfg: &Config,
+        account: &Addr,
+    ) -> StdResult<()> {
+        if cfg.liquidation_auction.is_some() {
+            LIQUIDATION_AUCTIONS.save(storage, account, &env.block.time)?;
+        }
+        Ok(())
+    }
+
+    /// Rechecks the running liquidation auction of the account once all messages added before
+    /// are executed, so it is reset if they restored the account health
+    fn recheck_liquidation_auction(
+        storage: &dyn Storage,
+        env: &Env,
+        account: &Addr,
+    ) -> StdResult<Option<WasmMsg>> {
+        if !LIQUIDATION_AUCTIONS.has(storage, account) {
+            return Ok(None);
+        }
+        Ok(Some(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_binary(&ExecuteMsg::UpdateLiquidationAuction {
+                account: account.to_string(),
+            })?,
+            funds: vec![],
+        }))
+    }
+
+    /// Handler for `ExecuteMsg::UpdateLiquidationAuction`
+    pub fn update_liquidation_auction(
+        deps: DepsMut,
+        env: Env,
+        sender: Addr,
+        account: Addr,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        if cfg.liquidation_auction.is_none() {
+            // Fixed liquidation price, there is no auction to track
+            return Ok(Response::new()
+                .add_attribute("action", "update_liquidation_auction")
+                .add_attribute("account", account));
+        }
+
+        let liquidatable = query::can_liquidate(deps.as_ref(), &cfg, &account)?;
+        if liquidatable {
+            start_liquidation_auction(deps.storage, &env, &cfg, &account)?;
+        } else {
+            LIQUIDATION_AUCTIONS.remove(deps.storage, &account);
+        }
+
+        let event = events::LiquidationAuction {
+            sender,
+            account: account.clone(),
+            liquidatable,
+        };
+        Ok(Response::new()
+            .add_attribute("action", "update_liquidation_auction")
+            .add_attribute("account", account)
+            .add_attribute("liquidatable", liquidatable.to_string())
+            .add_event(event.into()))
     }
 
     pub fn exit_market(
@@ -309,7 +986,7 @@ mod execute {
         info: MessageInfo,
         market: Addr,
     ) -> Result<Response, ContractError> {
-        let common_token = CONFIG.load(deps.storage)?.common_token;
+        let cfg = CONFIG.load(deps.storage)?;
         let mut markets = ENTERED_MARKETS
             .may_load(deps.storage, &info.sender)?
             .unwrap_or_default();
@@ -350,8 +1027,8 @@ mod execute {
                         account: info.sender.to_string(),
                     },
                 )?;
-                let price_response = price_response.validate(&common_token)?;
-                Ok(price_response)
+                let price_response = price_response.validate(&cfg.common_token)?;
+                query::isolated_credit_line(deps.as_ref(), &cfg, market, price_response)
             })
             .try_fold(
                 CreditLineValues::zero(),
@@ -369,12 +1046,23 @@ mod execute {
             });
         }
 
-        ENTERED_MARKETS.save(deps.storage, &info.sender, &markets)?;
+        // Accounts without markets are dropped, so they are not listed by `ListAccounts`
+        if markets.is_empty() {
+            ENTERED_MARKETS.remove(deps.storage, &info.sender);
+        } else {
+            ENTERED_MARKETS.save(deps.storage, &info.sender, &markets)?;
+        }
 
+        let event = events::ExitMarket {
+            sender: info.sender.clone(),
+            account: info.sender.clone(),
+            market: market.clone(),
+        };
         Ok(Response::new()
             .add_attribute("action", "exit_market")
             .add_attribute("market", market)
-            .add_attribute("account", info.sender))
+            .add_attribute("account", info.sender)
+            .add_event(event.into()))
     }
 
     /// Allows a user to repay a certain **amount_to_repay** of debt using previously deposited
@@ -384,13 +1072,20 @@ mod execute {
     /// The function checks also that the required repay action didn't put **sender** into an unsafe collateral position.
     pub fn repay_with_collateral(
         deps: DepsMut,
+        env: Env,
         sender: Addr,
-        max_collateral: Coin,
-        amount_to_repay: Coin,
+        collateral_market: String,
+        max_collateral: Uint128,
+        debt_market: String,
+        amount_to_repay: Uint128,
     ) -> Result<Response, ContractError> {
-        // query collateral and debt market addresses
-        let collateral_market = query::market(deps.as_ref(), &max_collateral.denom)?.market;
-        let debt_market = query::market(deps.as_ref(), &amount_to_repay.denom)?.market;
+        // query collateral and debt market tokens
+        let collateral_market = query::market_by_address(deps.as_ref(), &collateral_market)?;
+        let max_collateral = collateral_market.market_token.amount(max_collateral);
+        let collateral_market = collateral_market.market;
+        let debt_market = query::market_by_address(deps.as_ref(), &debt_market)?;
+        let amount_to_repay = debt_market.market_token.amount(amount_to_repay);
+        let debt_market = debt_market.market;
 
         // check if `sender` is in both debt and collateral markets
         let markets = ENTERED_MARKETS
@@ -422,10 +1117,11 @@ mod execute {
         )?;
         let collateral_per_common_rate = collateral_per_common_rate.rate_sell_per_buy;
 
+        let collateral = max_collateral;
         let max_collateral = cfg
             .common_token
             .clone()
-            .into_coin(max_collateral.amount * collateral_per_common_rate);
+            .into_coin(collateral.amount * collateral_per_common_rate);
 
         // Express user debt in terms of common token
         let debt_per_common_rate: PriceRate = deps.querier.query_wasm_smart(
@@ -440,8 +1136,8 @@ mod execute {
 
         let simulated_credit_line = tcr
             .credit_line
-            .checked_sub(max_collateral * collateral_market_cfg.collateral_ratio)?;
-        let simulated_debt = tcr.debt.checked_sub(amount_to_repay_common)?;
+            .checked_sub(max_collateral.clone() * collateral_market_cfg.collateral_ratio)?;
+        let simulated_debt = tcr.debt.checked_sub(amount_to_repay_common.clone())?;
         if simulated_debt > simulated_credit_line {
             return Err(ContractError::RepayingLoanUsingCollateralFailed {});
         }
@@ -449,10 +1145,8 @@ mod execute {
         // Create the swap message to be sent to the collateral's market.
         let msg = to_binary(&MarketExecuteMsg::SwapWithdrawFrom {
             account: sender.to_string(),
-            // sell_limit: max_collateral.amount,
             buy: amount_to_repay.clone(),
-            referral_address: None,
-            referral_commission: None,
+            sell_limit: collateral.amount,
         })?;
         let swap_withdraw_from_msg = SubMsg::new(WasmMsg::Execute {
             contract_addr: collateral_market.to_string(),
@@ -460,47 +1154,1031 @@ mod execute {
             funds: vec![],
         });
 
+        let event = events::RepayWithCollateral {
+            sender: sender.clone(),
+            account: sender.clone(),
+            collateral_market,
+            debt_market: debt_market.clone(),
+            amount: amount_to_repay.clone(),
+            amount_common: amount_to_repay_common,
+            collateral,
+            collateral_common: max_collateral,
+        };
+
         let repay_to_msg =
-            create_repay_to_submessage(amount_to_repay, debt_market, sender).unwrap();
+            create_repay_to_submessage(amount_to_repay, debt_market, sender.clone()).unwrap();
+        let recheck_msg = recheck_liquidation_auction(deps.storage, &env, &sender)?;
 
         Ok(Response::new()
+            .add_attribute("action", "repay_with_collateral")
+            .add_attribute("account", sender)
+            .add_event(event.into())
             .add_submessage(swap_withdraw_from_msg)
-            .add_submessage(repay_to_msg))
+            .add_submessage(repay_to_msg)
+            .add_messages(recheck_msg))
     }
 
-    pub fn receive_cw20_message(
+    /// Handler for `ExecuteMsg::Deleverage`
+    /// Contrary to `repay_with_collateral`, the account may be liquidatable already. Health factor
+    /// is checked assuming the whole `max_collateral` is sold, so the actual swap can only end
+    /// up better. The fee is transferred in collateral cTokens to the governance contract.
+    pub fn deleverage(
         deps: DepsMut,
-        _env: Env,
-        info: MessageInfo,
-        msg: Cw20ReceiveMsg,
+        env: Env,
+        sender: Addr,
+        max_collateral: Coin,
+        collateral_market_id: u32,
+        amount_to_repay: Coin,
+        debt_market_id: u32,
     ) -> Result<Response, ContractError> {
-        match from_binary(&msg.msg)? {
-            ReceiveMsg::Liquidate {
-                account,
-                collateral_denom,
-            } => {
-                let sender = deps.api.addr_validate(&msg.sender)?;
-                let account = deps.api.addr_validate(&account)?;
-
-                let coin =
-                    utils::coin::Coin::new(msg.amount.u128(), Token::Cw20(info.sender.to_string()));
+        let cfg = CONFIG.load(deps.storage)?;
+        let collateral_market =
+            query::market(deps.as_ref(), &max_collateral.denom, collateral_market_id)?.market;
+        let debt_market =
+            query::market(deps.as_ref(), &amount_to_repay.denom, debt_market_id)?.market;
 
-                execute::liquidate(deps, sender, account, coin, collateral_denom)
+        let markets = ENTERED_MARKETS
+            .may_load(deps.storage, &sender)?
+            .unwrap_or_default();
+        for market in [&collateral_market, &debt_market] {
+            if !markets.contains(market) {
+                return Err(ContractError::NotOnMarket {
+                    address: sender,
+                    market: market.clone(),
+                });
             }
         }
-    }
-}
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
-    use QueryMsg::*;
+        let collateral_market_cfg: MarketConfiguration = deps
+            .querier
+            .query_wasm_smart(&collateral_market, &MarketQueryMsg::Configuration {})?;
+        let collateral_price: PriceRate = deps.querier.query_wasm_smart(
+            &collateral_market,
+            &MarketQueryMsg::PriceMarketLocalPerCommon {},
+        )?;
+        let debt_price: PriceRate = deps
+            .querier
+            .query_wasm_smart(&debt_market, &MarketQueryMsg::PriceMarketLocalPerCommon {})?;
+
+        let repay_value = amount_to_repay.amount * debt_price.rate_sell_per_buy;
+        let fee_value = repay_value * cfg.deleverage_fee;
+        let sold_value = max_collateral.amount * collateral_price.rate_sell_per_buy + fee_value;
+        let lost_credit_line = sold_value * collateral_market_cfg.collateral_ratio;
+
+        let before = query::total_credit_line(deps.as_ref(), sender.to_string())?
+            .validate(&cfg.common_token)?;
+        let after = CreditLineValues {
+            collateral: before.collateral.saturating_sub(sold_value),
+            credit_line: before.credit_line.saturating_sub(lost_credit_line),
+            borrow_limit: before
+                .borrow_limit
+                .saturating_sub(lost_credit_line * cfg.borrow_limit_ratio),
+            debt: before.debt.saturating_sub(repay_value),
+        };
 
-    let res = match msg {
-        Configuration {} => to_binary(&CONFIG.load(deps.storage)?)?,
-        Market { market_token } => to_binary(&query::market(deps, &market_token)?)?,
-        ListMarkets { start_after, limit } => {
+        let improved = match (query::health_factor(&before), query::health_factor(&after)) {
+            (_, None) => true,
+            (Some(before), Some(after)) => after > before,
+            (None, Some(_)) => false,
+        };
+        if !improved {
+            return Err(ContractError::DeleverageNotImproving {});
+        }
+
+        let swap_withdraw_from_msg = SubMsg::new(WasmMsg::Execute {
+            contract_addr: collateral_market.to_string(),
+            msg: to_binary(&MarketExecuteMsg::SwapWithdrawFrom {
+                account: sender.to_string(),
+                buy: amount_to_repay.clone(),
+                sell_limit: max_collateral.amount,
+            })?,
+            funds: vec![],
+        });
+        let event = events::Deleverage {
+            sender: sender.clone(),
+            account: sender.clone(),
+            collateral_market: collateral_market.clone(),
+            debt_market: debt_market.clone(),
+            amount: amount_to_repay.clone(),
+            amount_common: cfg.common_token.amount(repay_value),
+            collateral: max_collateral,
+            fee_common: cfg.common_token.amount(fee_value),
+        };
+        let repay_to_msg =
+            create_repay_to_submessage(amount_to_repay, debt_market, sender.clone())?;
+
+        let mut response = Response::new()
+            .add_attribute("action", "deleverage")
+            .add_attribute("account", sender.clone())
+            .add_attribute("fee", fee_value)
+            .add_event(event.into())
+            .add_submessage(swap_withdraw_from_msg)
+            .add_submessage(repay_to_msg);
+
+        if !fee_value.is_zero() {
+            response = response.add_submessage(SubMsg::new(WasmMsg::Execute {
+                contract_addr: collateral_market.to_string(),
+                msg: to_binary(&MarketExecuteMsg::TransferFrom {
+                    source: sender.to_string(),
+                    destination: cfg.gov_contract.to_string(),
+                    amount: fee_value,
+                    liquidation_price: Decimal::one(),
+                })?,
+                funds: vec![],
+            }));
+        }
+
+        Ok(response.add_messages(recheck_liquidation_auction(deps.storage, &env, &sender)?))
+    }
+
+    /// Handler for `ExecuteMsg::OpenLeveragedPosition`
+    /// The debt is flash-borrowed with `BorrowFor`, which skips the credit line check. The borrow
+    /// limit is verified at the end, in `settle_leveraged_position`, when all collateral is
+    /// known.
+    #[allow(clippy::too_many_arguments)]
+    pub fn open_leveraged_position(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        (collateral_denom, collateral_market_id): (Token, u32),
+        (debt_denom, debt_market_id): (Token, u32),
+        initial_amount: Uint128,
+        target_leverage: Decimal,
+        max_slippage: Decimal,
+    ) -> Result<Response, ContractError> {
+        if target_leverage <= Decimal::one() || max_slippage >= Decimal::one() {
+            return Err(ContractError::InvalidLeverage {});
+        }
+        let denom = match (&collateral_denom, &debt_denom) {
+            (Token::Native(denom), Token::Native(_)) => denom,
+            _ => return Err(ContractError::Cw20TokensNotSupported),
+        };
+        let received = must_pay(&info, denom)?;
+        if received != initial_amount {
+            return Err(ContractError::InvalidInitialCollateral {
+                expected: initial_amount,
+                received,
+            });
+        }
+
+        let cfg = CONFIG.load(deps.storage)?;
+        let collateral_market =
+            query::market(deps.as_ref(), &collateral_denom, collateral_market_id)?.market;
+        let debt_market = query::market(deps.as_ref(), &debt_denom, debt_market_id)?.market;
+
+        let collateral_price: PriceRate = deps.querier.query_wasm_smart(
+            &collateral_market,
+            &MarketQueryMsg::PriceMarketLocalPerCommon {},
+        )?;
+        let debt_price: PriceRate = deps
+            .querier
+            .query_wasm_smart(&debt_market, &MarketQueryMsg::PriceMarketLocalPerCommon {})?;
+
+        // Collateral to buy with borrowed tokens, and debt worth it at the oracle price
+        let bought = initial_amount * (target_leverage - Decimal::one());
+        let borrow_amount = query::divide(
+            bought * collateral_price.rate_sell_per_buy,
+            debt_price.rate_sell_per_buy,
+        )
+        .unwrap_or_default();
+
+        // Initial collateral is on the balance already, it is deposited in the settle step too
+        let balance = deps
+            .querier
+            .query_balance(&env.contract.address, denom)?
+            .amount;
+        let balance_before = balance.saturating_sub(initial_amount);
+
+        let event = events::OpenLeveragedPosition {
+            sender: info.sender.clone(),
+            account: info.sender.clone(),
+            collateral_market: collateral_market.clone(),
+            debt_market: debt_market.clone(),
+            amount: collateral_denom.amount(initial_amount),
+            borrowed: debt_denom.amount(borrow_amount),
+            leverage: target_leverage,
+        };
+        let mut response = Response::new()
+            .add_attribute("action", "open_leveraged_position")
+            .add_attribute("account", info.sender.to_string())
+            .add_attribute("borrowed", borrow_amount)
+            .add_event(event.into())
+            .add_message(WasmMsg::Execute {
+                contract_addr: debt_market.to_string(),
+                msg: to_binary(&MarketExecuteMsg::BorrowFor {
+                    account: info.sender.to_string(),
+                    amount: borrow_amount,
+                })?,
+                funds: vec![],
+            });
+
+        if collateral_denom != debt_denom {
+            let collateral_market_cfg: MarketConfiguration = deps
+                .querier
+                .query_wasm_smart(&collateral_market, &MarketQueryMsg::Configuration {})?;
+            let oracle_cfg: OracleConfig = deps.querier.query_wasm_smart(
+                collateral_market_cfg.price_oracle,
+                &OracleQueryMsg::Config {},
+            )?;
+
+            let swap_msg: CosmosMsg = debt_denom.swap_msg(
+                oracle_cfg.multi_hop,
+                debt_denom.swap_operations(&collateral_denom, &cfg.common_token),
+                Some(bought * (Decimal::one() - max_slippage)),
+                borrow_amount,
+            )?;
+            response = response.add_message(swap_msg);
+        }
+
+        Ok(response.add_message(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_binary(&ExecuteMsg::SettleLeveragedPosition {
+                account: info.sender.to_string(),
+                collateral_denom,
+                collateral_market_id,
+                balance_before,
+            })?,
+            funds: vec![],
+        }))
+    }
+
+    /// Handler for `ExecuteMsg::SettleLeveragedPosition`
+    /// The deposit is executed after this handler returns, so its credit line is added to the
+    /// account's one manually.
+    pub fn settle_leveraged_position(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        account: String,
+        (collateral_denom, collateral_market_id): (Token, u32),
+        balance_before: Uint128,
+    ) -> Result<Response, ContractError> {
+        if info.sender != env.contract.address {
+            return Err(ContractError::Unauthorized {});
+        }
+        let account = deps.api.addr_validate(&account)?;
+        let cfg = CONFIG.load(deps.storage)?;
+        let collateral_market =
+            query::market(deps.as_ref(), &collateral_denom, collateral_market_id)?.market;
+
+        let balance = deps
+            .querier
+            .query_balance(&env.contract.address, collateral_denom.denom())?
+            .amount;
+        let deposit = balance.saturating_sub(balance_before);
+
+        let (value, total) =
+            credit_line_after_deposit(deps.as_ref(), &cfg, &account, &collateral_market, deposit)?;
+        if total.debt > total.borrow_limit {
+            return Err(ContractError::LeveragedPositionOverBorrowLimit {
+                debt: total.debt,
+                borrow_limit: total.borrow_limit,
+            });
+        }
+
+        let event = events::SettleDeposit {
+            sender: info.sender,
+            account: account.clone(),
+            market: collateral_market.clone(),
+            amount: collateral_denom.amount(deposit),
+            amount_common: cfg.common_token.amount(value),
+        };
+        Ok(Response::new()
+            .add_attribute("action", "settle_leveraged_position")
+            .add_attribute("account", account.to_string())
+            .add_attribute("deposit", deposit)
+            .add_event(event.into())
+            .add_message(WasmMsg::Execute {
+                contract_addr: collateral_market.to_string(),
+                msg: to_binary(&MarketExecuteMsg::DepositTo {
+                    account: account.to_string(),
+                })?,
+                funds: coins(deposit.u128(), collateral_denom.denom()),
+            }))
+    }
+
+    /// Value of `deposit` in the common token and the credit line of the account once it is
+    /// executed on the collateral market. Used by the settle steps, which check the account
+    /// before their deposit message is executed. The deposit is valued the way
+    /// `query::total_credit_line` values the market - with its current, wind-down adjusted
+    /// collateral ratio and the isolation cap.
+    fn credit_line_after_deposit(
+        deps: Deps,
+        cfg: &Config,
+        account: &Addr,
+        collateral_market: &Addr,
+        deposit: Uint128,
+    ) -> Result<(Uint128, CreditLineValues), ContractError> {
+        let collateral_market_cfg: MarketConfiguration = deps
+            .querier
+            .query_wasm_smart(collateral_market, &MarketQueryMsg::Configuration {})?;
+        let wind_down: WindDownResponse = deps
+            .querier
+            .query_wasm_smart(collateral_market, &MarketQueryMsg::WindDown {})?;
+        let collateral_price: PriceRate = deps.querier.query_wasm_smart(
+            collateral_market,
+            &MarketQueryMsg::PriceMarketLocalPerCommon {},
+        )?;
+        let collateral = deposit * collateral_price.rate_sell_per_buy;
+        let credit_line = collateral * wind_down.collateral_ratio;
+
+        let markets = ENTERED_MARKETS
+            .may_load(deps.storage, account)?
+            .unwrap_or_default();
+        let total = markets
+            .iter()
+            .filter(|market| *market != collateral_market)
+            .map(|market| {
+                let values = query::market_credit_line(deps, cfg, market, account.as_str())?;
+                query::isolated_credit_line(deps, cfg, market, values)
+            })
+            .collect::<Result<Vec<CreditLineValues>, ContractError>>()?
+            .iter()
+            .sum::<CreditLineValues>();
+
+        let market_values =
+            query::market_credit_line(deps, cfg, collateral_market, account.as_str())?
+                + CreditLineValues {
+                    collateral,
+                    credit_line,
+                    borrow_limit: credit_line * collateral_market_cfg.borrow_limit_ratio,
+                    debt: Uint128::zero(),
+                };
+        let total =
+            total + query::isolated_credit_line(deps, cfg, collateral_market, market_values)?;
+        Ok((collateral, total))
+    }
+
+    /// Handler for `ExecuteMsg::SwapCollateral`
+    /// The collateral is withdrawn with `WithdrawFrom`, which skips the credit line check. The
+    /// account is verified in `settle_collateral_swap` instead, once the bought amount is known.
+    pub fn swap_collateral(
+        deps: DepsMut,
+        env: Env,
+        sender: Addr,
+        from: Coin,
+        from_market_id: u32,
+        (to_denom, to_market_id): (Token, u32),
+        min_receive: Uint128,
+    ) -> Result<Response, ContractError> {
+        if from.denom == to_denom {
+            return Err(ContractError::SwapSameDenom(to_denom.denom()));
+        }
+        let denom = match (&from.denom, &to_denom) {
+            (Token::Native(_), Token::Native(denom)) => denom,
+            _ => return Err(ContractError::Cw20TokensNotSupported),
+        };
+
+        let cfg = CONFIG.load(deps.storage)?;
+        let source_market = query::market(deps.as_ref(), &from.denom, from_market_id)?.market;
+        // Fail early if there is no target market
+        query::market(deps.as_ref(), &to_denom, to_market_id)?;
+
+        let source_market_cfg: MarketConfiguration = deps
+            .querier
+            .query_wasm_smart(&source_market, &MarketQueryMsg::Configuration {})?;
+        let oracle_cfg: OracleConfig = deps
+            .querier
+            .query_wasm_smart(source_market_cfg.price_oracle, &OracleQueryMsg::Config {})?;
+
+        let balance_before = deps
+            .querier
+            .query_balance(&env.contract.address, denom)?
+            .amount;
+
+        let withdraw_from_msg = WasmMsg::Execute {
+            contract_addr: source_market.to_string(),
+            msg: to_binary(&MarketExecuteMsg::WithdrawFrom {
+                account: sender.to_string(),
+                amount: from.amount,
+            })?,
+            funds: vec![],
+        };
+        let swap_msg: CosmosMsg = from.denom.swap_msg(
+            oracle_cfg.multi_hop,
+            from.denom.swap_operations(&to_denom, &cfg.common_token),
+            Some(min_receive),
+            from.amount,
+        )?;
+        let settle_msg = WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_binary(&ExecuteMsg::SettleCollateralSwap {
+                account: sender.to_string(),
+                to_denom: to_denom.clone(),
+                to_market_id,
+                balance_before,
+            })?,
+            funds: vec![],
+        };
+
+        let event = events::SwapCollateral {
+            sender: sender.clone(),
+            account: sender.clone(),
+            amount: from.clone(),
+            to_denom: to_denom.clone(),
+            min_receive,
+        };
+        Ok(Response::new()
+            .add_attribute("action", "swap_collateral")
+            .add_attribute("account", sender)
+            .add_attribute("from", from.denom.denom())
+            .add_attribute("amount", from.amount)
+            .add_attribute("to", to_denom.denom())
+            .add_event(event.into())
+            .add_message(withdraw_from_msg)
+            .add_message(swap_msg)
+            .add_message(settle_msg))
+    }
+
+    /// Handler for `ExecuteMsg::SettleCollateralSwap`
+    pub fn settle_collateral_swap(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        account: String,
+        (to_denom, to_market_id): (Token, u32),
+        balance_before: Uint128,
+    ) -> Result<Response, ContractError> {
+        if info.sender != env.contract.address {
+            return Err(ContractError::Unauthorized {});
+        }
+        let account = deps.api.addr_validate(&account)?;
+        let cfg = CONFIG.load(deps.storage)?;
+        let target_market = query::market(deps.as_ref(), &to_denom, to_market_id)?.market;
+
+        let balance = deps
+            .querier
+            .query_balance(&env.contract.address, to_denom.denom())?
+            .amount;
+        let deposit = balance.saturating_sub(balance_before);
+
+        let (value, total) =
+            credit_line_after_deposit(deps.as_ref(), &cfg, &account, &target_market, deposit)?;
+        // Same limit as for a plain withdrawal of the sold collateral
+        if total.debt > total.borrow_limit {
+            return Err(ContractError::CollateralSwapOverBorrowLimit {
+                debt: total.debt,
+                borrow_limit: total.borrow_limit,
+            });
+        }
+
+        let event = events::SettleDeposit {
+            sender: info.sender,
+            account: account.clone(),
+            market: target_market.clone(),
+            amount: to_denom.amount(deposit),
+            amount_common: cfg.common_token.amount(value),
+        };
+        Ok(Response::new()
+            .add_attribute("action", "settle_collateral_swap")
+            .add_attribute("account", account.to_string())
+            .add_attribute("deposit", deposit)
+            .add_event(event.into())
+            .add_message(WasmMsg::Execute {
+                contract_addr: target_market.to_string(),
+                msg: to_binary(&MarketExecuteMsg::DepositTo {
+                    account: account.to_string(),
+                })?,
+                funds: coins(deposit.u128(), to_denom.denom()),
+            }))
+    }
+
+    /// Handler for `ExecuteMsg::SwapDebt`
+    /// New debt is borrowed with `BorrowFor`, which skips the credit line check. The account is
+    /// verified in `settle_debt_swap` instead, once the old debt is repaid.
+    pub fn swap_debt(
+        deps: DepsMut,
+        env: Env,
+        sender: Addr,
+        (from_denom, from_market_id): (Token, u32),
+        (to_denom, to_market_id): (Token, u32),
+        amount: Uint128,
+        max_new_debt: Uint128,
+    ) -> Result<Response, ContractError> {
+        if from_denom == to_denom {
+            return Err(ContractError::SwapSameDenom(to_denom.denom()));
+        }
+        if !(from_denom.is_native() && to_denom.is_native()) {
+            return Err(ContractError::Cw20TokensNotSupported);
+        }
+
+        let cfg = CONFIG.load(deps.storage)?;
+        let source_market = query::market(deps.as_ref(), &from_denom, from_market_id)?.market;
+        let target_market = query::market(deps.as_ref(), &to_denom, to_market_id)?.market;
+
+        let debt: Coin = deps.querier.query_wasm_smart(
+            &source_market,
+            &MarketQueryMsg::RepayableDebt {
+                account: sender.to_string(),
+            },
+        )?;
+        let amount = std::cmp::min(amount, debt.amount);
+        if amount.is_zero() {
+            return Err(ContractError::NoDebt(from_denom.denom()));
+        }
+
+        let target_market_cfg: MarketConfiguration = deps
+            .querier
+            .query_wasm_smart(&target_market, &MarketQueryMsg::Configuration {})?;
+        let operations = to_denom.swap_operations(&from_denom, &cfg.common_token);
+
+        // New debt needed to buy the repaid amount
+        let estimate: SimulateSwapOperationsResponse = deps.querier.query_wasm_smart(
+            &target_market_cfg.price_oracle,
+            &OracleQueryMsg::SimulateReverseSwapOperations {
+                ask_amount: amount,
+                operations: operations.clone(),
+            },
+        )?;
+        let estimate = estimate.amount;
+        if estimate > max_new_debt {
+            return Err(ContractError::NewDebtOverLimit {
+                estimate,
+                max_new_debt,
+            });
+        }
+
+        let oracle_cfg: OracleConfig = deps
+            .querier
+            .query_wasm_smart(target_market_cfg.price_oracle, &OracleQueryMsg::Config {})?;
+
+        let borrow_for_msg = SubMsg::new(WasmMsg::Execute {
+            contract_addr: target_market.to_string(),
+            msg: to_binary(&MarketExecuteMsg::BorrowFor {
+                account: sender.to_string(),
+                amount: estimate,
+            })?,
+            funds: vec![],
+        });
+        let balance_before = deps
+            .querier
+            .query_balance(&env.contract.address, from_denom.denom())?
+            .amount;
+
+        let swap_msg: CosmosMsg =
+            to_denom.swap_msg(oracle_cfg.multi_hop, operations, Some(amount), estimate)?;
+        let settle_msg = SubMsg::new(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_binary(&ExecuteMsg::SettleDebtSwap {
+                account: sender.to_string(),
+                from_denom: from_denom.clone(),
+                from_market_id,
+                balance_before,
+            })?,
+            funds: vec![],
+        });
+
+        let event = events::SwapDebt {
+            sender: sender.clone(),
+            account: sender.clone(),
+            amount: from_denom.amount(amount),
+            borrowed: to_denom.amount(estimate),
+        };
+        Ok(Response::new()
+            .add_attribute("action", "swap_debt")
+            .add_attribute("account", sender)
+            .add_attribute("repaid", amount)
+            .add_attribute("borrowed", estimate)
+            .add_event(event.into())
+            .add_submessage(borrow_for_msg)
+            .add_message(swap_msg)
+            .add_submessage(settle_msg))
+    }
+
+    /// Handler for `ExecuteMsg::SettleDebtSwap`
+    /// The swap may buy more than the estimated amount, so the whole balance bought is repaid, up
+    /// to the debt, and the remainder is sent back to the account.
+    pub fn settle_debt_swap(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        account: String,
+        (from_denom, from_market_id): (Token, u32),
+        balance_before: Uint128,
+    ) -> Result<Response, ContractError> {
+        if info.sender != env.contract.address {
+            return Err(ContractError::Unauthorized {});
+        }
+        let account = deps.api.addr_validate(&account)?;
+        let cfg = CONFIG.load(deps.storage)?;
+        let source_market = query::market(deps.as_ref(), &from_denom, from_market_id)?.market;
+
+        let balance = deps
+            .querier
+            .query_balance(&env.contract.address, from_denom.denom())?
+            .amount;
+        let bought = balance.saturating_sub(balance_before);
+
+        let debt: Coin = deps.querier.query_wasm_smart(
+            &source_market,
+            &MarketQueryMsg::RepayableDebt {
+                account: account.to_string(),
+            },
+        )?;
+        let repaid = std::cmp::min(bought, debt.amount);
+        let remainder = bought - repaid;
+
+        // Repayment is sent along with this response, so it is accounted for up front
+        let price: PriceRate = deps.querier.query_wasm_smart(
+            &source_market,
+            &MarketQueryMsg::PriceMarketLocalPerCommon {},
+        )?;
+        let total = query::total_credit_line(deps.as_ref(), account.to_string())?
+            .validate(&cfg.common_token)?;
+        let debt_after = total.debt.saturating_sub(repaid * price.rate_sell_per_buy);
+        if debt_after > total.borrow_limit {
+            return Err(ContractError::DebtSwapOverBorrowLimit {
+                debt: debt_after,
+                borrow_limit: total.borrow_limit,
+            });
+        }
+
+        let event = events::SettleDebtSwap {
+            sender: info.sender,
+            account: account.clone(),
+            debt: cfg.common_token.amount(debt_after),
+            borrow_limit: cfg.common_token.amount(total.borrow_limit),
+        };
+        let mut response = Response::new()
+            .add_attribute("action", "settle_debt_swap")
+            .add_attribute("account", account.to_string())
+            .add_attribute("repaid", repaid)
+            .add_event(event.into());
+        if !repaid.is_zero() {
+            response = response.add_submessage(create_repay_to_submessage(
+                from_denom.amount(repaid),
+                source_market,
+                account.clone(),
+            )?);
+        }
+        if !remainder.is_zero() {
+            response = response.add_message(from_denom.send_msg(&account, remainder)?);
+        }
+        Ok(response)
+    }
+
+    /// Handler for `ExecuteMsg::CloseLeveragedPosition`
+    pub fn close_leveraged_position(
+        deps: DepsMut,
+        env: Env,
+        sender: Addr,
+        (collateral_denom, collateral_market_id): (Token, u32),
+        (debt_denom, debt_market_id): (Token, u32),
+        max_slippage: Decimal,
+    ) -> Result<Response, ContractError> {
+        if max_slippage >= Decimal::one() {
+            return Err(ContractError::InvalidLeverage {});
+        }
+        let cfg = CONFIG.load(deps.storage)?;
+        let collateral_market =
+            query::market(deps.as_ref(), &collateral_denom, collateral_market_id)?.market;
+        let debt_market = query::market(deps.as_ref(), &debt_denom, debt_market_id)?.market;
+
+        let debt: Coin = deps.querier.query_wasm_smart(
+            &debt_market,
+            &MarketQueryMsg::RepayableDebt {
+                account: sender.to_string(),
+            },
+        )?;
+        if debt.amount.is_zero() {
+            return Err(ContractError::NoDebt(debt_denom.denom()));
+        }
+
+        let collateral_price: PriceRate = deps.querier.query_wasm_smart(
+            &collateral_market,
+            &MarketQueryMsg::PriceMarketLocalPerCommon {},
+        )?;
+        let debt_price: PriceRate = deps
+            .querier
+            .query_wasm_smart(&debt_market, &MarketQueryMsg::PriceMarketLocalPerCommon {})?;
+
+        // Collateral worth the debt at the oracle price
+        let collateral_amount = query::divide(
+            debt.amount * debt_price.rate_sell_per_buy,
+            collateral_price.rate_sell_per_buy,
+        )
+        .unwrap_or_default();
+
+        let swap_withdraw_from_msg = SubMsg::new(WasmMsg::Execute {
+            contract_addr: collateral_market.to_string(),
+            msg: to_binary(&MarketExecuteMsg::SwapWithdrawFrom {
+                account: sender.to_string(),
+                buy: debt.clone(),
+                sell_limit: collateral_amount * (Decimal::one() + max_slippage),
+            })?,
+            funds: vec![],
+        });
+        let event = events::CloseLeveragedPosition {
+            sender: sender.clone(),
+            account: sender.clone(),
+            collateral_market: collateral_market.clone(),
+            debt_market: debt_market.clone(),
+            amount: debt.clone(),
+            amount_common: cfg
+                .common_token
+                .amount(debt.amount * debt_price.rate_sell_per_buy),
+        };
+        let repay_to_msg = create_repay_to_submessage(debt.clone(), debt_market, sender.clone())?;
+        let recheck_msg = recheck_liquidation_auction(deps.storage, &env, &sender)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "close_leveraged_position")
+            .add_attribute("account", sender)
+            .add_attribute("repaid", debt.amount)
+            .add_event(event.into())
+            .add_submessage(swap_withdraw_from_msg)
+            .add_submessage(repay_to_msg)
+            .add_messages(recheck_msg))
+    }
+
+    /// Handler for `ExecuteMsg::RemoveMarket`
+    pub fn remove_market(
+        deps: DepsMut,
+        sender: Addr,
+        market: String,
+    ) -> Result<Response, ContractError> {
+        let MarketResponse {
+            market_token,
+            market_id,
+            market,
+        } = query::market_by_address(deps.as_ref(), &market)?;
+        let wind_down: WindDownResponse = deps
+            .querier
+            .query_wasm_smart(&market, &MarketQueryMsg::WindDown {})?;
+        if wind_down.wind_down.is_none() {
+            return Err(ContractError::MarketNotWindingDown(market_token.denom()));
+        }
+        if !wind_down.empty {
+            return Err(ContractError::MarketNotEmpty(market_token.denom()));
+        }
+
+        MARKETS.remove(deps.storage, (&market_token, market_id));
+        MARKET_NONCES.update(
+            deps.storage,
+            (&market_token, market_id),
+            |nonce| -> StdResult<_> { Ok(nonce.unwrap_or_default() + 1) },
+        )?;
+        ISOLATED_MARKETS.remove(deps.storage, &market);
+        let event = events::RemoveMarket {
+            sender,
+            market: market.clone(),
+            market_token: market_token.clone(),
+            market_id,
+        };
+        Ok(Response::new()
+            .add_attribute("action", "remove_market")
+            .add_attribute("market_token", market_token.denom())
+            .add_attribute("market_id", market_id.to_string())
+            .add_attribute("market", market)
+            .add_event(event.into()))
+    }
+
+    /// Handler for `ExecuteMsg::EmitRewards`
+    pub fn emit_rewards(
+        deps: DepsMut,
+        env: Env,
+        sender: Addr,
+        markets: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        let markets = validate_markets(deps.as_ref(), markets)?;
+
+        let mut messages = vec![];
+        let mut emitted = vec![];
+        for market in &markets {
+            let (msgs, event) = emit_market_rewards(deps.branch(), &env, &sender, market)?;
+            messages.extend(msgs);
+            emitted.extend(event);
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "emit_rewards")
+            .add_events(emitted)
+            .add_submessages(messages))
+    }
+
+    /// Handler for `ExecuteMsg::ClaimRewards`
+    pub fn claim_rewards(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        markets: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        let markets = validate_markets(deps.as_ref(), markets)?;
+
+        // Emit first, so rewards accrued up to now are included in the claim
+        let mut messages = vec![];
+        let mut emitted = vec![];
+        for market in &markets {
+            let (msgs, event) = emit_market_rewards(deps.branch(), &env, &info.sender, market)?;
+            messages.extend(msgs);
+            emitted.extend(event);
+        }
+
+        let msg = to_binary(&MarketExecuteMsg::ClaimRewards {
+            account: info.sender.to_string(),
+        })?;
+        messages.extend(markets.iter().map(|market| {
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: market.to_string(),
+                msg: msg.clone(),
+                funds: vec![],
+            })
+        }));
+
+        let event = events::ClaimRewards {
+            sender: info.sender.clone(),
+            account: info.sender.clone(),
+            markets,
+            rewards: vec![],
+        };
+        Ok(Response::new()
+            .add_attribute("action", "claim_rewards")
+            .add_attribute("account", info.sender)
+            .add_events(emitted)
+            .add_event(event.into())
+            .add_submessages(messages))
+    }
+
+    fn validate_markets(deps: Deps, markets: Vec<String>) -> Result<Vec<Addr>, ContractError> {
+        markets
+            .into_iter()
+            .map(|market| {
+                let addr = deps.api.addr_validate(&market)?;
+                if !restricted::find_market(deps, &addr) {
+                    return Err(ContractError::MarketSearchError { market });
+                }
+                Ok(addr)
+            })
+            .collect()
+    }
+
+    /// Releases rewards accrued on the market since the last emission and creates messages
+    /// distributing them between cToken holders and borrowers. If there is noone on one
+    /// of the sides, its share goes back to the emission budget. The event is only returned
+    /// when the market has an emission.
+    pub fn emit_market_rewards(
+        deps: DepsMut,
+        env: &Env,
+        sender: &Addr,
+        market: &Addr,
+    ) -> Result<(Vec<SubMsg>, Option<Event>), ContractError> {
+        let mut emission = match EMISSIONS.may_load(deps.storage, market)? {
+            Some(emission) => emission,
+            None => return Ok((vec![], None)),
+        };
+
+        let reward_token = CONFIG.load(deps.storage)?.reward_token;
+
+        let released = emission.release(env.block.time);
+        let mut supplier_rewards = released * emission.supplier_share;
+        let mut borrower_rewards = released - supplier_rewards;
+
+        let market_cfg: MarketConfiguration = deps
+            .querier
+            .query_wasm_smart(market, &MarketQueryMsg::Configuration {})?;
+
+        if !supplier_rewards.is_zero() {
+            let ctoken_info: TokenInfoResponse = deps
+                .querier
+                .query_wasm_smart(&market_cfg.ctoken_contract, &TokenQueryMsg::TokenInfo {})?;
+            if ctoken_info.total_supply.is_zero() {
+                emission.remaining += supplier_rewards;
+                supplier_rewards = Uint128::zero();
+            }
+        }
+
+        if !borrower_rewards.is_zero() {
+            let total_debt: TotalDebtResponse = deps
+                .querier
+                .query_wasm_smart(market, &MarketQueryMsg::TotalDebt {})?;
+            if total_debt.total.is_zero() {
+                emission.remaining += borrower_rewards;
+                borrower_rewards = Uint128::zero();
+            }
+        }
+
+        EMISSIONS.save(deps.storage, market, &emission)?;
+
+        let mut messages = vec![];
+        if !supplier_rewards.is_zero() {
+            // cToken distributes everything it received since the last distribution, so rewards
+            // are transferred first - the same way for native and cw20 tokens
+            let transfer: CosmosMsg =
+                reward_token.send_msg(&market_cfg.ctoken_contract, supplier_rewards)?;
+            messages.push(SubMsg::new(transfer));
+            messages.push(SubMsg::new(WasmMsg::Execute {
+                contract_addr: market_cfg.ctoken_contract.to_string(),
+                msg: to_binary(&TokenExecuteMsg::Distribute { sender: None })?,
+                funds: vec![],
+            }));
+        }
+        if !borrower_rewards.is_zero() {
+            let msg = match &reward_token {
+                Token::Native(denom) => WasmMsg::Execute {
+                    contract_addr: market.to_string(),
+                    msg: to_binary(&MarketExecuteMsg::DistributeDebtRewards {})?,
+                    funds: coins(borrower_rewards.u128(), denom),
+                },
+                Token::Cw20(address) => WasmMsg::Execute {
+                    contract_addr: address.to_owned(),
+                    msg: to_binary(&Cw20ExecuteMsg::Send {
+                        contract: market.to_string(),
+                        amount: borrower_rewards,
+                        msg: to_binary(&MarketReceiveMsg::DistributeDebtRewards {})?,
+                    })?,
+                    funds: vec![],
+                },
+            };
+            messages.push(SubMsg::new(msg));
+        }
+
+        let event = events::EmitRewards {
+            sender: sender.clone(),
+            market: market.clone(),
+            supplier_rewards: reward_token.amount(supplier_rewards),
+            borrower_rewards: reward_token.amount(borrower_rewards),
+        };
+        Ok((messages, Some(event.into())))
+    }
+
+    pub fn receive_cw20_message(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Cw20ReceiveMsg,
+    ) -> Result<Response, ContractError> {
+        match from_binary(&msg.msg)? {
+            ReceiveMsg::Liquidate {
+                account,
+                debt_market,
+                collateral_market,
+            } => {
+                let sender = deps.api.addr_validate(&msg.sender)?;
+                let account = deps.api.addr_validate(&account)?;
+
+                let coin =
+                    utils::coin::Coin::new(msg.amount.u128(), Token::Cw20(info.sender.to_string()));
+
+                execute::liquidate(
+                    deps,
+                    env,
+                    sender,
+                    account,
+                    coin,
+                    debt_market,
+                    collateral_market,
+                )
+            }
+            ReceiveMsg::FundEmission {
+                market,
+                duration,
+                supplier_share,
+            } => {
+                let sender = deps.api.addr_validate(&msg.sender)?;
+                let reward = Token::Cw20(info.sender.to_string());
+                restricted::fund_emission(
+                    deps,
+                    env,
+                    sender,
+                    market,
+                    duration,
+                    supplier_share,
+                    reward.into_coin(msg.amount),
+                )
+            }
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    use QueryMsg::*;
+
+    let res = match msg {
+        Configuration {} => to_binary(&CONFIG.load(deps.storage)?)?,
+        Market {
+            market_token,
+            market_id,
+        } => to_binary(&query::market(
+            deps,
+            &market_token,
+            market_id.unwrap_or(DEFAULT_MARKET_ID),
+        )?)?,
+        PredictMarketAddress {
+            market_token,
+            market_id,
+        } => to_binary(&query::predict_market_address(
+            deps,
+            env,
+            market_token,
+            market_id.unwrap_or(DEFAULT_MARKET_ID),
+        )?)?,
+        ListMarkets { start_after, limit } => {
             to_binary(&query::list_markets(deps, start_after, limit)?)?
         }
+        ListOutdatedMarkets { start_after, limit } => {
+            to_binary(&query::list_outdated_markets(deps, start_after, limit)?)?
+        }
         TotalCreditLine { account } => to_binary(&query::total_credit_line(deps, account)?)?,
         ListEnteredMarkets {
             account,
@@ -508,86 +2186,240 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             limit,
         } => to_binary(&query::entered_markets(deps, account, start_after, limit)?)?,
         IsOnMarket { account, market } => to_binary(&query::is_on_market(deps, account, market)?)?,
+        ListAccounts { start_after, limit } => {
+            to_binary(&query::list_accounts(deps, start_after, limit)?)?
+        }
+        ListLiquidatable { start_after, limit } => {
+            to_binary(&query::list_liquidatable(deps, start_after, limit)?)?
+        }
         Liquidation { account } => to_binary(&query::liquidation(deps, account)?)?,
+        LiquidationQuote {
+            account,
+            debt_denom,
+            repay_amount,
+            collateral_denom,
+            debt_market_id,
+            collateral_market_id,
+        } => to_binary(&query::liquidation_quote(
+            deps,
+            env,
+            account,
+            (debt_denom, debt_market_id.unwrap_or(DEFAULT_MARKET_ID)),
+            repay_amount,
+            (
+                collateral_denom,
+                collateral_market_id.unwrap_or(DEFAULT_MARKET_ID),
+            ),
+        )?)?,
+        LiquidationAuction { account } => {
+            to_binary(&query::liquidation_auction(deps, env, account)?)?
+        }
+        AccountHealth { account } => to_binary(&query::account_health(deps, account)?)?,
+        SimulateActions { account, actions } => {
+            to_binary(&query::simulate_actions(deps, account, actions)?)?
+        }
+        Emission { market } => to_binary(&query::emission(deps, env, market)?)?,
+        PendingParamChanges { start_after, limit } => {
+            to_binary(&query::pending_param_changes(deps, start_after, limit)?)?
+        }
+        StewardLog { start_after, limit } => {
+            to_binary(&query::steward_log(deps, start_after, limit)?)?
+        }
+        PendingGovernance {} => to_binary(&query::pending_governance(deps)?)?,
+        MarketTemplates { start_after, limit } => {
+            to_binary(&query::market_templates(deps, start_after, limit)?)?
+        }
+        IsolatedMarket { market } => to_binary(&query::isolated_market(deps, market)?)?,
     };
 
     Ok(res)
 }
 
 mod query {
-    use cosmwasm_std::{Order, StdResult};
+    use cosmwasm_std::{Decimal, Fraction, Order, StdResult, Storage, Uint128};
     use cw_storage_plus::Bound;
-    use isotonic_market::msg::{QueryMsg as MarketQueryMsg, TokensBalanceResponse};
+    use isotonic_market::{
+        msg::{
+            LiquidationSeizeResponse, QueryMsg as MarketQueryMsg, TokensBalanceResponse,
+            WindDownResponse,
+        },
+        state::{Config as MarketConfiguration, CTOKEN_SALT_SEED},
+    };
     use utils::{
         coin::Coin,
         credit_line::{CreditLineResponse, CreditLineValues},
+        instantiate2::{predict_address, token_salt},
+        price::{coin_times_price_rate, PriceRate},
     };
 
     use crate::{
         msg::{
-            IsOnMarketResponse, LiquidationResponse, ListEnteredMarketsResponse,
-            ListMarketsResponse, MarketResponse,
+            AccountHealthResponse, Action, EmissionResponse, IsOnMarketResponse,
+            IsolatedMarketResponse, LiquidatableAccount, LiquidationAuctionResponse,
+            LiquidationQuoteResponse, LiquidationResponse, ListAccountsResponse,
+            ListEnteredMarketsResponse, ListLiquidatableResponse, ListMarketsResponse,
+            ListOutdatedMarketsResponse, MarketHealth, MarketResponse, MarketTemplateEntry,
+            MarketTemplatesResponse, OutdatedMarketResponse, PendingGovernanceResponse,
+            PendingParamChangeResponse, PendingParamChangesResponse, PredictMarketAddressResponse,
+            SimulateActionsResponse, SimulatedAction, StewardLogEntry, StewardLogResponse,
+        },
+        state::{
+            MarketState, EMISSIONS, ENTERED_MARKETS, ISOLATED_MARKETS, LIQUIDATION_AUCTIONS,
+            MARKETS, MARKET_NONCES, MARKET_TEMPLATES, PARAM_CHANGES, PENDING_GOVERNANCE,
+            STEWARD_LOG,
         },
-        state::{ENTERED_MARKETS, MARKETS},
     };
 
     use super::*;
 
-    /// Returns the address of the market associated to the given `market_token`. Returns an error
-    /// if the market does not exists or is being created.
-    pub fn market(deps: Deps, market_token: &Token) -> Result<MarketResponse, ContractError> {
+    /// Returns the address of the market associated to the given `market_token` and `market_id`.
+    /// Returns an error if the market does not exists.
+    pub fn market(
+        deps: Deps,
+        market_token: &Token,
+        market_id: u32,
+    ) -> Result<MarketResponse, ContractError> {
         let state = MARKETS
-            .may_load(deps.storage, market_token)?
+            .may_load(deps.storage, (market_token, market_id))?
             .ok_or_else(|| ContractError::NoMarket(market_token.denom()))?;
 
-        let addr = state
-            .to_addr()
-            .ok_or_else(|| ContractError::MarketCreating(market_token.denom()))?;
-
         Ok(MarketResponse {
             market_token: market_token.to_owned(),
-            market: addr,
+            market_id,
+            market: state.to_addr(),
         })
     }
 
+    /// Salt of the next market of `market_token` and `market_id`
+    pub fn market_salt(
+        storage: &dyn Storage,
+        market_token: &Token,
+        market_id: u32,
+    ) -> StdResult<Binary> {
+        let nonce = MARKET_NONCES
+            .may_load(storage, (market_token, market_id))?
+            .unwrap_or_default();
+        let seed = [market_id.to_be_bytes(), nonce.to_be_bytes()].concat();
+        Ok(token_salt(market_token, &seed))
+    }
+
+    /// Handler for `QueryMsg::PredictMarketAddress`
+    pub fn predict_market_address(
+        deps: Deps,
+        env: Env,
+        market_token: Token,
+        market_id: u32,
+    ) -> Result<PredictMarketAddressResponse, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        let salt = market_salt(deps.storage, &market_token, market_id)?;
+        let market = predict_address(deps, cfg.isotonic_market_id, &env.contract.address, &salt)?;
+        let ctoken = predict_address(
+            deps,
+            cfg.isotonic_token_id,
+            &market,
+            &token_salt(&market_token, CTOKEN_SALT_SEED),
+        )?;
+
+        Ok(PredictMarketAddressResponse { market, ctoken })
+    }
+
+    /// Finds the token and id of the ready market with the given address
+    pub fn market_by_address(deps: Deps, market: &str) -> Result<MarketResponse, ContractError> {
+        MARKETS
+            .range(deps.storage, None, None, Order::Ascending)
+            .find_map(|m| match m {
+                Ok(((market_token, market_id), MarketState::Ready(addr, _))) if addr == market => {
+                    Some(Ok(MarketResponse {
+                        market_token,
+                        market_id,
+                        market: addr,
+                    }))
+                }
+                Ok(_) => None,
+                Err(err) => Some(Err(err.into())),
+            })
+            .unwrap_or_else(|| {
+                Err(ContractError::MarketSearchError {
+                    market: market.to_owned(),
+                })
+            })
+    }
+
     // settings for pagination
     const MAX_LIMIT: u32 = 30;
     const DEFAULT_LIMIT: u32 = 10;
 
     pub fn list_markets(
         deps: Deps,
-        start_after: Option<Token>,
+        start_after: Option<(Token, u32)>,
         limit: Option<u32>,
     ) -> Result<ListMarketsResponse, ContractError> {
         let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-        let start = start_after.as_ref().map(Bound::exclusive);
+        let start = start_after
+            .as_ref()
+            .map(|(token, id)| Bound::exclusive((token, *id)));
 
         let markets: StdResult<Vec<_>> = MARKETS
             .range(deps.storage, start, None, Order::Ascending)
             .map(|m| {
-                let (market_token, market) = m?;
+                let ((market_token, market_id), market) = m?;
 
-                let result = market.to_addr().map(|addr| MarketResponse {
+                Ok(MarketResponse {
                     market_token,
-                    market: addr,
-                });
-
-                Ok(result)
+                    market_id,
+                    market: market.to_addr(),
+                })
             })
-            .filter_map(|m| m.transpose())
             .take(limit)
             .collect();
 
         Ok(ListMarketsResponse { markets: markets? })
     }
 
+    /// Handler for `QueryMsg::ListOutdatedMarkets`
+    pub fn list_outdated_markets(
+        deps: Deps,
+        start_after: Option<(Token, u32)>,
+        limit: Option<u32>,
+    ) -> Result<ListOutdatedMarketsResponse, ContractError> {
+        let current_code_id = CONFIG.load(deps.storage)?.isotonic_market_id;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after
+            .as_ref()
+            .map(|(token, id)| Bound::exclusive((token, *id)));
+
+        let markets = MARKETS
+            .range(deps.storage, start, None, Order::Ascending)
+            .filter_map(|m| match m {
+                Ok(((market_token, market_id), MarketState::Ready(market, code)))
+                    if code.code_id != current_code_id =>
+                {
+                    Some(Ok(OutdatedMarketResponse {
+                        market_token,
+                        market_id,
+                        market,
+                        code,
+                    }))
+                }
+                Ok(_) => None,
+                Err(err) => Some(Err(err)),
+            })
+            .take(limit)
+            .collect::<StdResult<_>>()?;
+
+        Ok(ListOutdatedMarketsResponse {
+            current_code_id,
+            markets,
+        })
+    }
+
     /// Handler for `QueryMsg::TotalCreditLine`
     /// Computes the sum of `CreditLineValues` for all markets the `address` is participating to.
     pub fn total_credit_line(
         deps: Deps,
         account: String,
     ) -> Result<CreditLineResponse, ContractError> {
-        let common_token = CONFIG.load(deps.storage)?.common_token;
+        let cfg = CONFIG.load(deps.storage)?;
         let markets = ENTERED_MARKETS
             .may_load(deps.storage, &Addr::unchecked(&account))?
             .unwrap_or_default();
@@ -595,19 +2427,72 @@ mod query {
         let total_credit_line: CreditLineValues = markets
             .into_iter()
             .map(|market| {
-                let price_response: CreditLineResponse = deps.querier.query_wasm_smart(
-                    market,
-                    &MarketQueryMsg::CreditLine {
-                        account: account.clone(),
-                    },
-                )?;
-                let price_response = price_response.validate(&common_token.clone())?;
-                Ok(price_response)
+                let values = market_credit_line(deps, &cfg, &market, &account)?;
+                isolated_credit_line(deps, &cfg, &market, values)
             })
             .collect::<Result<Vec<CreditLineValues>, ContractError>>()?
             .iter()
             .sum();
-        Ok(total_credit_line.make_response(common_token))
+        Ok(total_credit_line.make_response(cfg.common_token))
+    }
+
+    /// Credit line of the account given by a single market, before the isolation cap
+    pub fn market_credit_line(
+        deps: Deps,
+        cfg: &Config,
+        market: &Addr,
+        account: &str,
+    ) -> Result<CreditLineValues, ContractError> {
+        let response: CreditLineResponse = deps.querier.query_wasm_smart(
+            market,
+            &MarketQueryMsg::CreditLine {
+                account: account.to_owned(),
+            },
+        )?;
+        Ok(response.validate(&cfg.common_token)?)
+    }
+
+    /// Limits the credit line given by the market to its cap while the market is in isolation
+    pub fn isolated_credit_line(
+        deps: Deps,
+        cfg: &Config,
+        market: &Addr,
+        mut values: CreditLineValues,
+    ) -> Result<CreditLineValues, ContractError> {
+        if let Some(cap) = ISOLATED_MARKETS.may_load(deps.storage, market)? {
+            values.credit_line = values.credit_line.min(cap);
+            values.borrow_limit = values.borrow_limit.min(cap * cfg.borrow_limit_ratio);
+        }
+        Ok(values)
+    }
+
+    /// Handler for `QueryMsg::MarketTemplates`
+    pub fn market_templates(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<MarketTemplatesResponse, ContractError> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.as_deref().map(Bound::exclusive);
+
+        let templates = MARKET_TEMPLATES
+            .range(deps.storage, start, None, Order::Ascending)
+            .map(|t| t.map(|(name, template)| MarketTemplateEntry { name, template }))
+            .take(limit)
+            .collect::<StdResult<_>>()?;
+
+        Ok(MarketTemplatesResponse { templates })
+    }
+
+    /// Handler for `QueryMsg::IsolatedMarket`
+    pub fn isolated_market(
+        deps: Deps,
+        market: String,
+    ) -> Result<IsolatedMarketResponse, ContractError> {
+        let market = deps.api.addr_validate(&market)?;
+        Ok(IsolatedMarketResponse {
+            credit_cap: ISOLATED_MARKETS.may_load(deps.storage, &market)?,
+        })
     }
 
     pub fn entered_markets(
@@ -653,94 +2538,798 @@ mod query {
         })
     }
 
+    /// Accounts with at least one entered market, in ascending order
+    fn accounts(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: usize,
+    ) -> Result<Vec<Addr>, ContractError> {
+        let start_after = start_after.map(Addr::unchecked);
+        let start = start_after.as_ref().map(Bound::exclusive);
+
+        let accounts = ENTERED_MARKETS
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<_>>()?;
+        Ok(accounts)
+    }
+
+    /// Handler for `QueryMsg::ListAccounts`
+    pub fn list_accounts(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<ListAccountsResponse, ContractError> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        Ok(ListAccountsResponse {
+            accounts: accounts(deps, start_after, limit)?,
+        })
+    }
+
+    // every checked account queries all its markets
+    const MAX_LIQUIDATABLE_LIMIT: u32 = 10;
+    const DEFAULT_LIQUIDATABLE_LIMIT: u32 = 5;
+
+    /// Handler for `QueryMsg::ListLiquidatable`
+    pub fn list_liquidatable(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<ListLiquidatableResponse, ContractError> {
+        let limit = limit
+            .unwrap_or(DEFAULT_LIQUIDATABLE_LIMIT)
+            .min(MAX_LIQUIDATABLE_LIMIT) as usize;
+        let checked = accounts(deps, start_after, limit)?;
+        let last_checked = if checked.len() < limit {
+            None
+        } else {
+            checked.last().cloned()
+        };
+
+        let accounts = checked
+            .into_iter()
+            .map(|account| -> Result<_, ContractError> {
+                let credit_line = total_credit_line(deps, account.to_string())?;
+                Ok((account, credit_line))
+            })
+            .filter(|entry| {
+                !matches!(entry, Ok((_, credit_line)) if credit_line.debt <= credit_line.credit_line)
+            })
+            .map(|entry| {
+                entry.map(|(account, credit_line)| LiquidatableAccount {
+                    account,
+                    credit_line,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(ListLiquidatableResponse {
+            accounts,
+            last_checked,
+        })
+    }
+
     pub fn liquidation(deps: Deps, account: String) -> Result<LiquidationResponse, ContractError> {
         let account_addr = deps.api.addr_validate(&account)?;
 
-        // check whether the given account actually has more debt then credit
-        let total_credit_line: CreditLineResponse = total_credit_line(deps, account.clone())?;
-        let can_liquidate = total_credit_line.debt > total_credit_line.credit_line;
+        // check whether the given account actually has more debt then credit
+        let can_liquidate = can_liquidate(deps, &CONFIG.load(deps.storage)?, &account_addr)?;
+
+        let markets = ENTERED_MARKETS
+            .may_load(deps.storage, &account_addr)?
+            .unwrap_or_default();
+
+        let market_data: Result<Vec<_>, _> = markets
+            .into_iter()
+            .map(|market| -> Result<(Addr, Coin, Coin), ContractError> {
+                let token_balances: TokensBalanceResponse = deps.querier.query_wasm_smart(
+                    &market,
+                    &MarketQueryMsg::TokensBalance {
+                        account: account.clone(),
+                    },
+                )?;
+
+                Ok((market, token_balances.collateral, token_balances.debt))
+            })
+            .collect();
+        let market_data = market_data?;
+
+        let collateral: Vec<_> = market_data
+            .iter()
+            .filter(|(_, collateral, _)| !collateral.amount.is_zero())
+            .cloned()
+            .map(|(market, collateral, _)| (market, collateral))
+            .collect();
+        let debt: Vec<_> = market_data
+            .into_iter()
+            .filter(|(_, _, debt)| !debt.amount.is_zero())
+            .map(|(market, _, debt)| (market, debt))
+            .collect();
+
+        Ok(LiquidationResponse {
+            can_liquidate,
+            debt,
+            collateral,
+        })
+    }
+
+    /// Debt in a wound down market with forced liquidation can be liquidated regardless of the
+    /// account health
+    pub fn forced_liquidation(deps: Deps, debt_market: &Addr) -> Result<bool, ContractError> {
+        let wind_down: WindDownResponse = deps
+            .querier
+            .query_wasm_smart(debt_market, &MarketQueryMsg::WindDown {})?;
+        Ok(wind_down.force_liquidation)
+    }
+
+    /// Account can be liquidated when its debt exceeds its credit line
+    pub fn can_liquidate(deps: Deps, cfg: &Config, account: &Addr) -> Result<bool, ContractError> {
+        let total_credit_line = total_credit_line(deps, account.to_string())?;
+        let total_credit_line = total_credit_line.validate(&cfg.common_token)?;
+        Ok(total_credit_line.debt > total_credit_line.credit_line)
+    }
+
+    /// Part of the liquidation calculated by the credit agency, shared by `ExecuteMsg::Liquidate`
+    /// and `QueryMsg::LiquidationQuote`
+    pub struct LiquidationPlan {
+        pub debt_market: Addr,
+        pub collateral_market: Addr,
+        /// Value of repaid debt in common tokens, which the collateral market converts into
+        /// seized collateral
+        pub repay_value: Uint128,
+        pub debt_price: PriceRate,
+    }
+
+    pub fn liquidation_plan(
+        deps: Deps,
+        repay: &Coin,
+        debt_market: Addr,
+        collateral_market: Addr,
+    ) -> Result<LiquidationPlan, ContractError> {
+        let debt_price: PriceRate = deps
+            .querier
+            .query_wasm_smart(&debt_market, &MarketQueryMsg::PriceMarketLocalPerCommon {})?;
+        let repay_value = coin_times_price_rate(repay, &debt_price)?.amount;
+
+        Ok(LiquidationPlan {
+            debt_market,
+            collateral_market,
+            repay_value,
+            debt_price,
+        })
+    }
+
+    /// Price for collateral applied when liquidating the account now. With the liquidation auction
+    /// enabled, the auction which didn't start yet is treated as starting now.
+    pub fn liquidation_price(
+        deps: Deps,
+        env: &Env,
+        cfg: &Config,
+        account: &Addr,
+    ) -> StdResult<Decimal> {
+        match &cfg.liquidation_auction {
+            Some(auction) => {
+                let start = LIQUIDATION_AUCTIONS
+                    .may_load(deps.storage, account)?
+                    .unwrap_or(env.block.time);
+                Ok(auction.liquidation_price(start, env.block.time))
+            }
+            None => Ok(cfg.liquidation_price),
+        }
+    }
+
+    /// Handler for `QueryMsg::LiquidationAuction`
+    pub fn liquidation_auction(
+        deps: Deps,
+        env: Env,
+        account: String,
+    ) -> Result<LiquidationAuctionResponse, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        let account = deps.api.addr_validate(&account)?;
+
+        Ok(LiquidationAuctionResponse {
+            start: LIQUIDATION_AUCTIONS.may_load(deps.storage, &account)?,
+            liquidation_price: liquidation_price(deps, &env, &cfg, &account)?,
+        })
+    }
+
+    /// Handler for `QueryMsg::LiquidationQuote`
+    pub fn liquidation_quote(
+        deps: Deps,
+        env: Env,
+        account: String,
+        (debt_denom, debt_market_id): (Token, u32),
+        repay_amount: Uint128,
+        (collateral_denom, collateral_market_id): (Token, u32),
+    ) -> Result<LiquidationQuoteResponse, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        let account = deps.api.addr_validate(&account)?;
+        let repay = debt_denom.amount(repay_amount);
+
+        let debt_market = market(deps, &debt_denom, debt_market_id)?.market;
+        let collateral_market = market(deps, &collateral_denom, collateral_market_id)?.market;
+
+        let can_liquidate =
+            can_liquidate(deps, &cfg, &account)? || forced_liquidation(deps, &debt_market)?;
+        let liquidation_price = liquidation_price(deps, &env, &cfg, &account)?;
+        let plan = liquidation_plan(deps, &repay, debt_market, collateral_market)?;
+
+        let debt: Coin = deps.querier.query_wasm_smart(
+            &plan.debt_market,
+            &MarketQueryMsg::RepayableDebt {
+                account: account.to_string(),
+            },
+        )?;
+        let seize: LiquidationSeizeResponse = deps.querier.query_wasm_smart(
+            &plan.collateral_market,
+            &MarketQueryMsg::LiquidationSeize {
+                account: account.to_string(),
+                amount: plan.repay_value,
+                liquidation_price,
+            },
+        )?;
+        let collateral_price: PriceRate = deps.querier.query_wasm_smart(
+            &plan.collateral_market,
+            &MarketQueryMsg::PriceMarketLocalPerCommon {},
+        )?;
+
+        // Largest repayment not seizing more than the whole collateral. Every step rounds down,
+        // so it is never too high, but may be a bit lower than the exact one.
+        let collateral_value =
+            seize.collateral.amount * (collateral_price.rate_sell_per_buy * liquidation_price);
+        let max_repay = divide(collateral_value, plan.debt_price.rate_sell_per_buy)
+            .map_or(debt.amount, |amount| amount.min(debt.amount));
+
+        let success = can_liquidate
+            && repay.amount <= debt.amount
+            && seize.seized_ctokens <= seize.ctoken_balance;
+
+        Ok(LiquidationQuoteResponse {
+            can_liquidate,
+            success,
+            repay_value: cfg.common_token.amount(plan.repay_value),
+            seized: seize.seized,
+            seized_ctokens: seize.seized_ctokens,
+            max_repay: debt.denom.amount(max_repay),
+        })
+    }
+
+    /// Handler for `QueryMsg::AccountHealth`
+    pub fn account_health(
+        deps: Deps,
+        account: String,
+    ) -> Result<AccountHealthResponse, ContractError> {
+        let account_addr = deps.api.addr_validate(&account)?;
+        let ca_cfg = CONFIG.load(deps.storage)?;
+        let common_token = ca_cfg.common_token.clone();
+        let markets = ENTERED_MARKETS
+            .may_load(deps.storage, &account_addr)?
+            .unwrap_or_default();
+
+        let markets = markets
+            .into_iter()
+            .map(|market| -> Result<_, ContractError> {
+                let cfg: MarketConfiguration = deps
+                    .querier
+                    .query_wasm_smart(&market, &MarketQueryMsg::Configuration {})?;
+                let price: PriceRate = deps
+                    .querier
+                    .query_wasm_smart(&market, &MarketQueryMsg::PriceMarketLocalPerCommon {})?;
+                let balances: TokensBalanceResponse = deps.querier.query_wasm_smart(
+                    &market,
+                    &MarketQueryMsg::TokensBalance {
+                        account: account.clone(),
+                    },
+                )?;
+                let credit_line: CreditLineResponse = deps.querier.query_wasm_smart(
+                    &market,
+                    &MarketQueryMsg::CreditLine {
+                        account: account.clone(),
+                    },
+                )?;
+                let values = isolated_credit_line(
+                    deps,
+                    &ca_cfg,
+                    &market,
+                    credit_line.validate(&common_token)?,
+                )?;
+                let credit_line = values.clone().make_response(common_token.clone());
+
+                let health = MarketHealth {
+                    market,
+                    market_token: cfg.market_token,
+                    collateral_ratio: cfg.collateral_ratio,
+                    price: price.rate_sell_per_buy,
+                    collateral: balances.collateral,
+                    debt: balances.debt,
+                    credit_line,
+                    liquidation_price_drop: None,
+                    liquidation_price: None,
+                };
+                Ok((health, values))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let total: CreditLineValues = markets.iter().map(|(_, values)| values).sum();
+        let health_factor = health_factor(&total);
+
+        let markets = markets
+            .into_iter()
+            .map(|(mut health, values)| {
+                health.liquidation_price_drop = liquidation_price_drop(&total, &values);
+                health.liquidation_price = health
+                    .liquidation_price_drop
+                    .map(|drop| health.price * (Decimal::one() - drop));
+                health
+            })
+            .collect();
+
+        Ok(AccountHealthResponse {
+            health_factor,
+            total: total.make_response(common_token),
+            markets,
+        })
+    }
+
+    /// `credit_line / debt`, `None` if there is no debt
+    pub fn health_factor(total: &CreditLineValues) -> Option<Decimal> {
+        if total.debt.is_zero() {
+            None
+        } else {
+            Some(Decimal::checked_from_ratio(total.credit_line, total.debt).unwrap_or(Decimal::MAX))
+        }
+    }
+
+    /// Account can be liquidated when its debt exceeds its credit line. Price drop `d` of the
+    /// market token lowers the credit line by `d * market.credit_line` and the debt by
+    /// `d * market.debt`, so the threshold is
+    /// `d = (credit_line - debt) / (market.credit_line - market.debt)`.
+    fn liquidation_price_drop(
+        total: &CreditLineValues,
+        market: &CreditLineValues,
+    ) -> Option<Decimal> {
+        if total.debt > total.credit_line {
+            return Some(Decimal::zero());
+        }
+        if market.credit_line <= market.debt {
+            // Market token going down lowers debt at least as much as credit line
+            return None;
+        }
+
+        let margin = total.credit_line - total.debt;
+        let market_margin = market.credit_line - market.debt;
+        if margin >= market_margin {
+            // Price would have to drop to zero
+            return None;
+        }
+        Some(Decimal::from_ratio(margin, market_margin))
+    }
+
+    /// Account position on a single market, modified in memory by `simulate_actions`
+    struct SimulatedPosition {
+        market: Addr,
+        cfg: MarketConfiguration,
+        price: PriceRate,
+        /// Collateral in market token
+        collateral: Uint128,
+        /// Debt in market token
+        debt: Uint128,
+        /// Credit line cap if the market is in isolation
+        isolated_credit_cap: Option<Uint128>,
+        /// Current collateral ratio, lowered while the market winds down
+        collateral_ratio: Decimal,
+        winding_down: bool,
+    }
+
+    impl SimulatedPosition {
+        fn load(deps: Deps, market: &Addr, account: &str) -> Result<Self, ContractError> {
+            let cfg: MarketConfiguration = deps
+                .querier
+                .query_wasm_smart(market, &MarketQueryMsg::Configuration {})?;
+            let price: PriceRate = deps
+                .querier
+                .query_wasm_smart(market, &MarketQueryMsg::PriceMarketLocalPerCommon {})?;
+            // Balances include the interest not charged yet
+            let balances: TokensBalanceResponse = deps.querier.query_wasm_smart(
+                market,
+                &MarketQueryMsg::TokensBalance {
+                    account: account.to_owned(),
+                },
+            )?;
+            let wind_down: WindDownResponse = deps
+                .querier
+                .query_wasm_smart(market, &MarketQueryMsg::WindDown {})?;
+
+            Ok(Self {
+                market: market.clone(),
+                cfg,
+                price,
+                collateral: balances.collateral.amount,
+                debt: balances.debt.amount,
+                isolated_credit_cap: ISOLATED_MARKETS.may_load(deps.storage, market)?,
+                collateral_ratio: wind_down.collateral_ratio,
+                winding_down: wind_down.wind_down.is_some(),
+            })
+        }
+
+        /// Calculated the same way as `QueryMsg::CreditLine` of the market, limited by the
+        /// isolation cap
+        fn credit_line(&self) -> Result<CreditLineValues, ContractError> {
+            if self.collateral.is_zero() && self.debt.is_zero() {
+                return Ok(CreditLineValues::zero());
+            }
+
+            let token = &self.cfg.market_token;
+            let collateral =
+                coin_times_price_rate(&token.amount(self.collateral), &self.price)?.amount;
+            let debt = coin_times_price_rate(&token.amount(self.debt), &self.price)?.amount;
+            let mut credit_line = collateral * self.collateral_ratio;
+            let mut borrow_limit = credit_line * self.cfg.borrow_limit_ratio;
+            if let Some(cap) = self.isolated_credit_cap {
+                credit_line = credit_line.min(cap);
+                borrow_limit = borrow_limit.min(cap * self.cfg.borrow_limit_ratio);
+            }
+            Ok(CreditLineValues::new(
+                collateral,
+                credit_line,
+                borrow_limit,
+                debt,
+            ))
+        }
+
+        /// Mirrors `can_borrow` of the market, `total` being the credit line of all markets
+        fn borrowable(&self, total: &CreditLineValues) -> Uint128 {
+            let available = total.borrow_limit.saturating_sub(total.debt);
+            divide(available, self.price.rate_sell_per_buy).unwrap_or_default()
+        }
+
+        /// Mirrors `transferable_amount` of the market, in market token
+        fn transferable(&self, total: &CreditLineValues) -> Uint128 {
+            // Collateral of the wound down market doesn't back any debt anymore
+            if total.debt.is_zero() || (self.winding_down && self.collateral_ratio.is_zero()) {
+                return self.collateral;
+            }
+            divide(self.borrowable(total), self.collateral_ratio).unwrap_or_default()
+        }
+    }
+
+    /// `None` when dividing by zero, in which case the market fails the transaction
+    pub fn divide(top: Uint128, bottom: Decimal) -> Option<Uint128> {
+        (top * bottom.denominator())
+            .checked_div(bottom.numerator())
+            .ok()
+    }
+
+    fn simulated_total(positions: &[SimulatedPosition]) -> Result<CreditLineValues, ContractError> {
+        Ok(positions
+            .iter()
+            .map(SimulatedPosition::credit_line)
+            .collect::<Result<Vec<_>, _>>()?
+            .iter()
+            .sum())
+    }
+
+    /// Returns index of position on the `market_id` market of `token`, the default one if not
+    /// set, loading it if not yet present
+    fn simulated_position(
+        deps: Deps,
+        positions: &mut Vec<SimulatedPosition>,
+        account: &str,
+        token: &Token,
+        market_id: Option<u32>,
+    ) -> Result<usize, ContractError> {
+        let market = market(deps, token, market_id.unwrap_or(DEFAULT_MARKET_ID))?.market;
+        if let Some(idx) = positions.iter().position(|p| p.market == market) {
+            return Ok(idx);
+        }
+
+        positions.push(SimulatedPosition::load(deps, &market, account)?);
+        Ok(positions.len() - 1)
+    }
+
+    /// Applies single action to positions, returns if it would be accepted. Rejected actions
+    /// leave positions unchanged.
+    fn simulate_action(
+        deps: Deps,
+        positions: &mut Vec<SimulatedPosition>,
+        account: &str,
+        action: &Action,
+    ) -> Result<bool, ContractError> {
+        let accepted = match action {
+            Action::Deposit { amount, market_id } => {
+                let idx = simulated_position(deps, positions, account, &amount.denom, *market_id)?;
+                positions[idx].collateral += amount.amount;
+                true
+            }
+            Action::Withdraw { amount, market_id } => {
+                let idx = simulated_position(deps, positions, account, &amount.denom, *market_id)?;
+                let total = simulated_total(positions)?;
+                let position = &mut positions[idx];
+                let accepted = amount.amount <= position.collateral
+                    && amount.amount <= position.transferable(&total);
+                if accepted {
+                    position.collateral -= amount.amount;
+                }
+                accepted
+            }
+            Action::Borrow { amount, market_id } => {
+                let idx = simulated_position(deps, positions, account, &amount.denom, *market_id)?;
+                let total = simulated_total(positions)?;
+                let position = &mut positions[idx];
+                let accepted = amount.amount <= position.borrowable(&total);
+                if accepted {
+                    position.debt += amount.amount;
+                }
+                accepted
+            }
+            Action::Repay { amount, market_id } => {
+                // Excess is sent back by the market
+                let idx = simulated_position(deps, positions, account, &amount.denom, *market_id)?;
+                let position = &mut positions[idx];
+                position.debt = position.debt.saturating_sub(amount.amount);
+                true
+            }
+            Action::SwapCollateral {
+                sell,
+                buy,
+                sell_market_id,
+                buy_market_id,
+            } => {
+                let sell_idx =
+                    simulated_position(deps, positions, account, &sell.denom, *sell_market_id)?;
+                let buy_idx =
+                    simulated_position(deps, positions, account, &buy.denom, *buy_market_id)?;
+                // Bought collateral is credited before the sold one is checked
+                positions[buy_idx].collateral += buy.amount;
+                let total = simulated_total(positions)?;
+                let position = &mut positions[sell_idx];
+                let accepted = sell.amount <= position.collateral
+                    && sell.amount <= position.transferable(&total);
+                if accepted {
+                    position.collateral -= sell.amount;
+                } else {
+                    positions[buy_idx].collateral -= buy.amount;
+                }
+                accepted
+            }
+        };
+
+        Ok(accepted)
+    }
+
+    /// Handler for `QueryMsg::SimulateActions`
+    pub fn simulate_actions(
+        deps: Deps,
+        account: String,
+        actions: Vec<Action>,
+    ) -> Result<SimulateActionsResponse, ContractError> {
+        let account_addr = deps.api.addr_validate(&account)?;
+        let ca_cfg = CONFIG.load(deps.storage)?;
+        let common_token = ca_cfg.common_token.clone();
+
+        let mut positions = ENTERED_MARKETS
+            .may_load(deps.storage, &account_addr)?
+            .unwrap_or_default()
+            .iter()
+            .map(|market| SimulatedPosition::load(deps, market, &account))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let actions = actions
+            .into_iter()
+            .map(|action| -> Result<_, ContractError> {
+                let accepted = simulate_action(deps, &mut positions, &account, &action)?;
+                let total = simulated_total(&positions)?;
+                Ok(SimulatedAction {
+                    action,
+                    accepted,
+                    health_factor: health_factor(&total),
+                    total: total.make_response(common_token.clone()),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let total = simulated_total(&positions)?;
+        Ok(SimulateActionsResponse {
+            health_factor: health_factor(&total),
+            total: total.make_response(common_token),
+            actions,
+        })
+    }
+
+    /// Handler for `QueryMsg::Emission`
+    pub fn emission(
+        deps: Deps,
+        env: Env,
+        market: String,
+    ) -> Result<EmissionResponse, ContractError> {
+        let market = deps.api.addr_validate(&market)?;
+        let mut emission = EMISSIONS.may_load(deps.storage, &market)?.ok_or_else(|| {
+            ContractError::MarketSearchError {
+                market: market.to_string(),
+            }
+        })?;
+
+        let pending = emission.release(env.block.time);
 
-        let markets = ENTERED_MARKETS
-            .may_load(deps.storage, &account_addr)?
-            .unwrap_or_default();
+        Ok(EmissionResponse {
+            remaining: emission.remaining + pending,
+            pending,
+            end: emission.end,
+            supplier_share: emission.supplier_share,
+        })
+    }
 
-        let market_data: Result<Vec<_>, _> = markets
+    /// Handler for `QueryMsg::PendingParamChanges`
+    pub fn pending_param_changes(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<PendingParamChangesResponse, ContractError> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let changes = PARAM_CHANGES
+            .range(deps.storage, start_after, limit)?
             .into_iter()
-            .map(|market| -> Result<(Addr, Coin, Coin), ContractError> {
-                let token_balances: TokensBalanceResponse = deps.querier.query_wasm_smart(
-                    &market,
-                    &MarketQueryMsg::TokensBalance {
-                        account: account.clone(),
-                    },
-                )?;
-
-                Ok((market, token_balances.collateral, token_balances.debt))
+            .map(|(id, pending)| PendingParamChangeResponse {
+                id,
+                change: pending.change,
+                executable_at: pending.executable_at,
             })
             .collect();
-        let market_data = market_data?;
+        Ok(PendingParamChangesResponse { changes })
+    }
 
-        let collateral: Vec<_> = market_data
-            .iter()
-            .filter(|(_, collateral, _)| !collateral.amount.is_zero())
-            .cloned()
-            .map(|(market, collateral, _)| (market, collateral))
-            .collect();
-        let debt: Vec<_> = market_data
-            .into_iter()
-            .filter(|(_, _, debt)| !debt.amount.is_zero())
-            .map(|(market, _, debt)| (market, debt))
-            .collect();
+    /// Handler for `QueryMsg::StewardLog`
+    pub fn steward_log(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<StewardLogResponse, ContractError> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let actions = STEWARD_LOG
+            .range(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .map(|entry| entry.map(|(id, action)| StewardLogEntry { id, action }))
+            .collect::<StdResult<_>>()?;
+        Ok(StewardLogResponse { actions })
+    }
 
-        Ok(LiquidationResponse {
-            can_liquidate,
-            debt,
-            collateral,
-        })
+    /// Handler for `QueryMsg::PendingGovernance`
+    pub fn pending_governance(deps: Deps) -> Result<PendingGovernanceResponse, ContractError> {
+        let new_governance = PENDING_GOVERNANCE
+            .may_load(deps.storage)?
+            .map(|pending| pending.new_governance);
+        Ok(PendingGovernanceResponse { new_governance })
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
-    reply::handle_market_instantiation_response(deps, env, msg)
+    reply::handle_market_reply(deps, env, msg)
+}
+
+/// Market state as stored before market ids were introduced
+#[derive(Serialize, Deserialize, Clone, Debug)]
+enum LegacyMarketState {
+    Instantiating,
+    Ready(Addr),
+}
+
+/// Markets created before market ids were introduced, moved to `MARKETS` on migration
+const LEGACY_MARKETS: Map<&Token, LegacyMarketState> = Map::new("market");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // Markets created before market ids were introduced become the default markets of their
+    // tokens. Markets still instantiating never got a contract, they are dropped so they can be
+    // created again.
+    let legacy_markets = LEGACY_MARKETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut migrated = 0;
+    for (market_token, state) in &legacy_markets {
+        if let LegacyMarketState::Ready(addr) = state {
+            let code = reply::market_code(deps.as_ref(), addr)?;
+            MARKETS.save(
+                deps.storage,
+                (market_token, DEFAULT_MARKET_ID),
+                &MarketState::Ready(addr.clone(), code),
+            )?;
+            migrated += 1;
+        }
+        LEGACY_MARKETS.remove(deps.storage, market_token);
+    }
+
+    Ok(Response::new()
+        .add_attribute("migrated_markets", migrated.to_string())
+        .add_attribute(
+            "dropped_markets",
+            (legacy_markets.len() - migrated).to_string(),
+        ))
 }
 
 mod reply {
     use super::*;
 
-    use crate::state::{MarketState, MARKETS, REPLY_IDS};
+    use cosmwasm_std::StdResult;
 
-    pub fn handle_market_instantiation_response(
+    use crate::state::{MarketCode, MarketState, MARKETS, REPLY_IDS};
+
+    /// Replies to market migrations, recording the code the market runs after it
+    pub fn handle_market_reply(
         deps: DepsMut,
         _env: Env,
         msg: Reply,
     ) -> Result<Response, ContractError> {
-        let id = msg.id;
-        let res =
-            parse_reply_instantiate_data(msg).map_err(|err| ContractError::ReplyParseFailure {
-                id,
-                err: err.to_string(),
-            })?;
+        let (market_token, market_id) = REPLY_IDS.load(deps.storage, msg.id)?;
+        let market_addr = MARKETS
+            .load(deps.storage, (&market_token, market_id))?
+            .to_addr();
+        handle_market_code_response(deps, market_token, market_id, market_addr)
+    }
 
-        let market_token = REPLY_IDS.load(deps.storage, id)?;
-        let market_addr = deps.api.addr_validate(&res.contract_address)?;
+    pub fn handle_market_code_response(
+        deps: DepsMut,
+        market_token: Token,
+        market_id: u32,
+        market_addr: Addr,
+    ) -> Result<Response, ContractError> {
+        let code = market_code(deps.as_ref(), &market_addr)?;
+        let response = Response::new()
+            .add_attribute(
+                format!("market_{}_{}_code_id", market_token, market_id),
+                code.code_id.to_string(),
+            )
+            .add_attribute(
+                format!("market_{}_{}_version", market_token, market_id),
+                &code.version,
+            );
 
         MARKETS.save(
             deps.storage,
-            &market_token,
-            &MarketState::Ready(market_addr.clone()),
+            (&market_token, market_id),
+            &MarketState::Ready(market_addr, code),
         )?;
 
-        Ok(Response::new().add_attribute(format!("market_{}", market_token), market_addr))
+        Ok(response)
+    }
+
+    pub fn market_code(deps: Deps, market_addr: &Addr) -> StdResult<MarketCode> {
+        let code_id = deps.querier.query_wasm_contract_info(market_addr)?.code_id;
+        let version = cw2::query_contract_info(&deps.querier, market_addr)?.version;
+        Ok(MarketCode { code_id, version })
     }
 }
 
 mod restricted {
     use super::*;
-    use crate::state::{MarketState, MARKETS};
+    use crate::msg::{ParamChange, StewardChange};
+    use crate::state::{
+        Emission, LiquidationAuction, MarketState, MarketTemplate, PendingGovernance, RiskSteward,
+        StewardAction, StewardBounds, EMISSIONS, ISOLATED_MARKETS, MARKETS, MARKET_TEMPLATES,
+        NEXT_STEWARD_ACTION_ID, PARAM_CHANGES, PENDING_GOVERNANCE, REPLY_IDS, STEWARD_LOG,
+    };
+
+    use cosmwasm_std::{Decimal, Order, StdResult, Storage, SubMsg, Uint128, WasmMsg};
+    use cw_storage_plus::Bound;
+    use isotonic_market::msg::ParamChange as MarketParamChange;
+    use utils::coin::Coin;
+    use utils::price::PriceRate;
 
-    use cosmwasm_std::{Order, SubMsg, WasmMsg};
+    use isotonic_market::msg::{
+        ExecuteMsg as MarketExecuteMsg, MigrateMsg as MarketMigrateMsg, QueryMsg as MarketQueryMsg,
+    };
+    use isotonic_market::state::Config as MarketConfiguration;
 
-    use isotonic_market::msg::{ExecuteMsg as MarketExecuteMsg, MigrateMsg as MarketMigrateMsg};
+    // settings for pagination of `MigrateAllMarkets`, every market is a separate migration
+    const MAX_MIGRATE_LIMIT: u32 = 10;
+    const DEFAULT_MIGRATE_LIMIT: u32 = 5;
 
     pub fn ensure_governance(cfg: &Config, info: &MessageInfo) -> Result<(), ContractError> {
         if cfg.gov_contract != info.sender {
@@ -749,68 +3338,551 @@ mod restricted {
         Ok(())
     }
 
-    pub fn adjust_market_id(
+    pub fn ensure_risk_steward<'a>(
+        cfg: &'a Config,
+        info: &MessageInfo,
+    ) -> Result<&'a StewardBounds, ContractError> {
+        match &cfg.risk_steward {
+            Some(steward) if steward.address == info.sender => Ok(&steward.bounds),
+            _ => Err(ContractError::Unauthorized {}),
+        }
+    }
+
+    /// Handler for `ExecuteMsg::ProposeParamChange`
+    pub fn propose_param_change(
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
-        new_market_id: u64,
+        change: ParamChange,
     ) -> Result<Response, ContractError> {
-        let mut cfg = CONFIG.load(deps.storage)?;
+        let cfg = CONFIG.load(deps.storage)?;
         ensure_governance(&cfg, &info)?;
-        cfg.isotonic_market_id = new_market_id;
-        CONFIG.save(deps.storage, &cfg)?;
-        Ok(Response::new())
+        validate_param_change(&cfg, &change)?;
+        let event = events::ParamChange {
+            sender: info.sender,
+            status: "applied",
+            id: None,
+            executable_at: None,
+            change: Some(json(&change)?),
+        };
+
+        if cfg.param_change_delay == 0 || is_risk_reducing(&cfg, &change) {
+            let response = apply_param_change(deps, change)?;
+            return Ok(response
+                .add_attribute("action", "apply_param_change")
+                .add_event(event.into()));
+        }
+
+        let executable_at = env.block.time.plus_seconds(cfg.param_change_delay);
+        let id = PARAM_CHANGES.propose(deps.storage, change, executable_at)?;
+        let event = events::ParamChange {
+            status: "proposed",
+            id: Some(id),
+            executable_at: Some(executable_at),
+            ..event
+        };
+        Ok(Response::new()
+            .add_attribute("action", "propose_param_change")
+            .add_attribute("id", id.to_string())
+            .add_attribute("executable_at", executable_at.seconds().to_string())
+            .add_event(event.into()))
     }
 
-    pub fn adjust_token_id(
+    /// Handler for `ExecuteMsg::ExecuteParamChange`
+    pub fn execute_param_change(
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
-        new_token_id: u64,
+        id: u64,
     ) -> Result<Response, ContractError> {
-        let mut cfg = CONFIG.load(deps.storage)?;
+        let cfg = CONFIG.load(deps.storage)?;
         ensure_governance(&cfg, &info)?;
-        cfg.isotonic_token_id = new_token_id;
-        CONFIG.save(deps.storage, &cfg)?;
-        Ok(Response::new())
+        let change = PARAM_CHANGES.take_ready(deps.storage, &env.block, id)?;
+        let event = events::ParamChange {
+            sender: info.sender,
+            status: "applied",
+            id: Some(id),
+            executable_at: None,
+            change: Some(json(&change)?),
+        };
+        let response = apply_param_change(deps, change)?;
+        Ok(response
+            .add_attribute("action", "execute_param_change")
+            .add_attribute("id", id.to_string())
+            .add_event(event.into()))
     }
 
-    pub fn adjust_common_token(
+    /// Handler for `ExecuteMsg::CancelParamChange`
+    pub fn cancel_param_change(
         deps: DepsMut,
         info: MessageInfo,
-        new_common_token: Token,
+        id: u64,
     ) -> Result<Response, ContractError> {
-        let mut cfg = CONFIG.load(deps.storage)?;
+        let cfg = CONFIG.load(deps.storage)?;
         ensure_governance(&cfg, &info)?;
-        cfg.common_token = new_common_token.clone();
+        PARAM_CHANGES.cancel(deps.storage, id)?;
+        let event = events::ParamChange {
+            sender: info.sender,
+            status: "cancelled",
+            id: Some(id),
+            executable_at: None,
+            change: None,
+        };
+        Ok(Response::new()
+            .add_attribute("action", "cancel_param_change")
+            .add_attribute("id", id.to_string())
+            .add_event(event.into()))
+    }
+
+    fn validate_param_change(cfg: &Config, change: &ParamChange) -> Result<(), ContractError> {
+        match change {
+            ParamChange::DeleverageFee { fee } => validate_deleverage_fee(&Config {
+                deleverage_fee: *fee,
+                ..cfg.clone()
+            }),
+            ParamChange::LiquidationAuction { auction } => {
+                validate_liquidation_auction(auction)?;
+                validate_deleverage_fee(&Config {
+                    liquidation_auction: auction.clone(),
+                    ..cfg.clone()
+                })
+            }
+            ParamChange::RiskSteward {
+                steward: Some(steward),
+            } => validate_steward_bounds(&steward.bounds),
+            ParamChange::MarketTemplate {
+                template: Some(template),
+                ..
+            } => validate_market_template(cfg, template),
+            _ => Ok(()),
+        }
+    }
+
+    /// Changes which only make the credit agency safer don't have to wait for the delay
+    fn is_risk_reducing(cfg: &Config, change: &ParamChange) -> bool {
+        match change {
+            ParamChange::ParamChangeDelay { new_delay } => *new_delay >= cfg.param_change_delay,
+            ParamChange::RiskSteward { steward: None } => true,
+            ParamChange::MarketTemplate { template: None, .. } => true,
+            _ => false,
+        }
+    }
+
+    fn apply_param_change(deps: DepsMut, change: ParamChange) -> Result<Response, ContractError> {
+        let mut cfg = CONFIG.load(deps.storage)?;
+        let mut response = Response::new();
+        match change {
+            ParamChange::MarketId { new_market_id } => cfg.isotonic_market_id = new_market_id,
+            ParamChange::TokenId { new_token_id } => cfg.isotonic_token_id = new_token_id,
+            ParamChange::CommonToken { new_common_token } => {
+                cfg.common_token = new_common_token.clone();
+                response = response.add_submessages(adjust_markets_common_token(
+                    deps.as_ref(),
+                    new_common_token,
+                )?);
+            }
+            ParamChange::DeleverageFee { fee } => {
+                cfg.deleverage_fee = fee;
+                validate_deleverage_fee(&cfg)?;
+            }
+            ParamChange::LiquidationAuction { auction } => {
+                validate_liquidation_auction(&auction)?;
+                cfg.liquidation_auction = auction;
+                validate_deleverage_fee(&cfg)?;
+                validate_markets_collateral_ratio(deps.as_ref(), &cfg)?;
+            }
+            ParamChange::ParamChangeDelay { new_delay } => cfg.param_change_delay = new_delay,
+            ParamChange::RiskSteward { steward } => {
+                cfg.risk_steward = match steward {
+                    Some(steward) => {
+                        validate_steward_bounds(&steward.bounds)?;
+                        Some(RiskSteward {
+                            address: deps.api.addr_validate(steward.address.as_str())?,
+                            bounds: steward.bounds,
+                        })
+                    }
+                    None => None,
+                };
+            }
+            ParamChange::WindDownMarket {
+                market,
+                duration,
+                force_liquidation,
+            } => {
+                let market = query::market_by_address(deps.as_ref(), &market)?.market;
+                response = response.add_message(WasmMsg::Execute {
+                    contract_addr: market.to_string(),
+                    msg: to_binary(&MarketExecuteMsg::WindDown {
+                        duration,
+                        force_liquidation,
+                    })?,
+                    funds: vec![],
+                });
+            }
+            ParamChange::MarketTemplate { name, template } => match template {
+                Some(template) => {
+                    validate_market_template(&cfg, &template)?;
+                    let template = MarketTemplate {
+                        price_oracle: deps.api.addr_validate(template.price_oracle.as_str())?,
+                        ..template
+                    };
+                    MARKET_TEMPLATES.save(deps.storage, &name, &template)?;
+                }
+                None => MARKET_TEMPLATES.remove(deps.storage, &name),
+            },
+            ParamChange::PromoteMarket { market } => {
+                let market = query::market_by_address(deps.as_ref(), &market)?.market;
+                if !ISOLATED_MARKETS.has(deps.storage, &market) {
+                    return Err(ContractError::MarketNotIsolated(market.to_string()));
+                }
+                ISOLATED_MARKETS.remove(deps.storage, &market);
+            }
+        }
         CONFIG.save(deps.storage, &cfg)?;
+        Ok(response)
+    }
+
+    /// Handler for `ExecuteMsg::StewardAdjust`
+    pub fn steward_adjust(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        market: String,
+        change: StewardChange,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        let bounds = ensure_risk_steward(&cfg, &info)?;
+        let market = query::market_by_address(deps.as_ref(), &market)?.market;
+
+        let market_change = match change.clone() {
+            StewardChange::MarketCap { new_cap } => {
+                let price: PriceRate = deps
+                    .querier
+                    .query_wasm_smart(&market, &MarketQueryMsg::PriceMarketLocalPerCommon {})?;
+                if new_cap * price.rate_sell_per_buy > bounds.max_market_cap_common {
+                    return Err(ContractError::StewardBoundsExceeded(
+                        "market_cap".to_owned(),
+                    ));
+                }
+                MarketParamChange::MarketCap {
+                    new_cap: Some(new_cap),
+                }
+            }
+            StewardChange::InterestRates { new_interest_rates } => {
+                let max_rate = new_interest_rates
+                    .clone()
+                    .validate()?
+                    .calculate_interest_rate(Decimal::one());
+                if max_rate > bounds.max_interest_rate {
+                    return Err(ContractError::StewardBoundsExceeded(
+                        "interest_rates".to_owned(),
+                    ));
+                }
+                MarketParamChange::InterestRates { new_interest_rates }
+            }
+            StewardChange::CollateralRatio { new_ratio } => {
+                if new_ratio < bounds.min_collateral_ratio
+                    || new_ratio > bounds.max_collateral_ratio
+                {
+                    return Err(ContractError::StewardBoundsExceeded(
+                        "collateral_ratio".to_owned(),
+                    ));
+                }
+                if new_ratio >= cfg.min_liquidation_price() {
+                    return Err(ContractError::MarketCfgCollateralFailure {});
+                }
+                MarketParamChange::CollateralRatio { new_ratio }
+            }
+        };
+
+        let id = NEXT_STEWARD_ACTION_ID
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        NEXT_STEWARD_ACTION_ID.save(deps.storage, &(id + 1))?;
+        let event = events::StewardAdjust {
+            sender: info.sender.clone(),
+            market: market.clone(),
+            id,
+            change: json(&change)?,
+        };
+        STEWARD_LOG.save(
+            deps.storage,
+            id,
+            &StewardAction {
+                steward: info.sender,
+                market: market.clone(),
+                change,
+                time: env.block.time,
+            },
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "steward_adjust")
+            .add_attribute("id", id.to_string())
+            .add_attribute("market", market.to_string())
+            .add_event(event.into())
+            .add_message(WasmMsg::Execute {
+                contract_addr: market.to_string(),
+                msg: to_binary(&MarketExecuteMsg::StewardParamChange {
+                    change: market_change,
+                })?,
+                funds: vec![],
+            }))
+    }
 
+    /// Sends `AdjustCommonToken` to all affiliated markets
+    fn adjust_markets_common_token(
+        deps: Deps,
+        new_common_token: Token,
+    ) -> Result<Vec<SubMsg>, ContractError> {
         let msg = to_binary(&MarketExecuteMsg::AdjustCommonToken {
             new_token: new_common_token,
         })?;
         let messages = MARKETS
             .range(deps.storage, None, None, Order::Ascending)
             .filter_map(|m| match m {
-                Ok((_, MarketState::Ready(addr))) => Some(SubMsg::new(WasmMsg::Execute {
+                Ok((_, MarketState::Ready(addr, _))) => Some(SubMsg::new(WasmMsg::Execute {
                     contract_addr: addr.to_string(),
                     msg: msg.clone(),
                     funds: vec![],
                 })),
                 _ => None,
             })
-            .collect::<Vec<SubMsg>>();
+            .collect();
+        Ok(messages)
+    }
+
+    /// Handler for `ExecuteMsg::ProposeNewGovernance`
+    pub fn propose_new_governance(
+        deps: DepsMut,
+        info: MessageInfo,
+        new_governance: String,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        ensure_governance(&cfg, &info)?;
+        let new_governance = deps.api.addr_validate(&new_governance)?;
+
+        let msg = to_binary(&MarketExecuteMsg::ProposeNewGovernance {
+            new_governance: new_governance.to_string(),
+        })?;
+        let messages: Vec<_> = all_markets(deps.storage)?
+            .into_iter()
+            .map(|market| WasmMsg::Execute {
+                contract_addr: market.to_string(),
+                msg: msg.clone(),
+                funds: vec![],
+            })
+            .collect();
+
+        PENDING_GOVERNANCE.save(
+            deps.storage,
+            &PendingGovernance {
+                new_governance: new_governance.clone(),
+            },
+        )?;
+        let event = events::GovernanceTransfer {
+            sender: info.sender,
+            status: "proposed",
+            governance: new_governance.clone(),
+        };
+        Ok(Response::new()
+            .add_attribute("action", "propose_new_governance")
+            .add_attribute("new_governance", new_governance)
+            .add_event(event.into())
+            .add_messages(messages))
+    }
+
+    /// Handler for `ExecuteMsg::AcceptGovernance`
+    pub fn accept_governance(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+        let mut cfg = CONFIG.load(deps.storage)?;
+        let pending = PENDING_GOVERNANCE
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoPendingGovernance {})?;
+        if pending.new_governance != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        PENDING_GOVERNANCE.remove(deps.storage);
+        cfg.gov_contract = pending.new_governance;
+        CONFIG.save(deps.storage, &cfg)?;
+
+        // Markets created since the proposal didn't get it, and the governance of a market could
+        // have replaced it in the meantime, so the transfer is proposed again right before it is
+        // accepted
+        let propose_msg = to_binary(&MarketExecuteMsg::ProposeNewGovernance {
+            new_governance: cfg.gov_contract.to_string(),
+        })?;
+        let accept_msg = to_binary(&MarketExecuteMsg::AcceptGovernance {})?;
+        let messages: Vec<_> = all_markets(deps.storage)?
+            .into_iter()
+            .flat_map(|market| {
+                [&propose_msg, &accept_msg].map(|msg| WasmMsg::Execute {
+                    contract_addr: market.to_string(),
+                    msg: msg.clone(),
+                    funds: vec![],
+                })
+            })
+            .collect();
+        let event = events::GovernanceTransfer {
+            sender: info.sender,
+            status: "accepted",
+            governance: cfg.gov_contract.clone(),
+        };
+        Ok(Response::new()
+            .add_attribute("action", "accept_governance")
+            .add_attribute("governance", cfg.gov_contract)
+            .add_event(event.into())
+            .add_messages(messages))
+    }
+
+    /// Addresses of all markets created by the credit agency
+    fn all_markets(storage: &dyn Storage) -> StdResult<Vec<Addr>> {
+        MARKETS
+            .range(storage, None, None, Order::Ascending)
+            .map(|m| m.map(|(_, state)| state.to_addr()))
+            .collect()
+    }
+
+    /// Deleveraging has to cost less than being liquidated, so the fee has to stay below the
+    /// highest liquidation discount
+    pub fn validate_deleverage_fee(cfg: &Config) -> Result<(), ContractError> {
+        if cfg.deleverage_fee >= Decimal::one() - cfg.min_liquidation_price() {
+            return Err(ContractError::InvalidDeleverageFee {});
+        }
+        Ok(())
+    }
+
+    pub fn validate_liquidation_auction(
+        auction: &Option<LiquidationAuction>,
+    ) -> Result<(), ContractError> {
+        match auction {
+            Some(auction)
+                if auction.min_discount > auction.max_discount
+                    || auction.max_discount >= Decimal::one()
+                    || auction.duration == 0 =>
+            {
+                Err(ContractError::InvalidLiquidationAuction {})
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn validate_steward_bounds(bounds: &StewardBounds) -> Result<(), ContractError> {
+        if bounds.min_collateral_ratio > bounds.max_collateral_ratio
+            || bounds.max_collateral_ratio >= Decimal::one()
+        {
+            return Err(ContractError::InvalidStewardBounds {});
+        }
+        Ok(())
+    }
+
+    /// Collateral ratios of markets created from the template have to pass the same check as
+    /// when creating a market
+    pub fn validate_market_template(
+        cfg: &Config,
+        template: &MarketTemplate,
+    ) -> Result<(), ContractError> {
+        if template.min_collateral_ratio > template.max_collateral_ratio
+            || template.max_collateral_ratio >= cfg.min_liquidation_price()
+        {
+            return Err(ContractError::InvalidMarketTemplate {});
+        }
+        Ok(())
+    }
 
-        Ok(Response::new().add_submessages(messages))
+    /// The highest discount has to keep liquidations reducing debt more than credit line,
+    /// same as checked when creating a market
+    fn validate_markets_collateral_ratio(deps: Deps, cfg: &Config) -> Result<(), ContractError> {
+        let min_liquidation_price = cfg.min_liquidation_price();
+        for market in MARKETS.range(deps.storage, None, None, Order::Ascending) {
+            let (_, market) = market?;
+            let market_cfg: MarketConfiguration = deps
+                .querier
+                .query_wasm_smart(market.to_addr(), &MarketQueryMsg::Configuration {})?;
+            if market_cfg.collateral_ratio >= min_liquidation_price {
+                return Err(ContractError::MarketCfgCollateralFailure {});
+            }
+        }
+        Ok(())
     }
 
-    fn find_market(deps: Deps, market_addr: &Addr) -> bool {
+    pub fn find_market(deps: Deps, market_addr: &Addr) -> bool {
         let found = MARKETS
             .range(deps.storage, None, None, Order::Ascending)
             .find(|m| match m {
-                Ok((_, MarketState::Ready(addr))) => market_addr == addr,
+                Ok((_, MarketState::Ready(addr, _))) => market_addr == addr,
                 _ => false,
             });
         found.is_some()
     }
 
+    /// Handler for `ExecuteMsg::FundEmission`
+    /// `reward` are funds sent along with the message, either native or cw20
+    pub fn fund_emission(
+        mut deps: DepsMut,
+        env: Env,
+        sender: Addr,
+        market: String,
+        duration: u64,
+        supplier_share: Decimal,
+        reward: Coin,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        if cfg.gov_contract != sender {
+            return Err(ContractError::Unauthorized {});
+        }
+        if cfg.reward_token != reward.denom {
+            return Err(ContractError::InvalidRewardToken(reward.denom.denom()));
+        }
+
+        let market_addr = deps.api.addr_validate(&market)?;
+        if !find_market(deps.as_ref(), &market_addr) {
+            return Err(ContractError::MarketSearchError { market });
+        }
+        if duration == 0 {
+            return Err(ContractError::InvalidEmissionDuration {});
+        }
+        if supplier_share > Decimal::one() {
+            return Err(ContractError::InvalidSupplierShare {});
+        }
+
+        let amount = reward.amount;
+
+        // Rewards accrued under the old schedule are emitted before it changes
+        let (messages, emitted) =
+            execute::emit_market_rewards(deps.branch(), &env, &sender, &market_addr)?;
+
+        let mut emission = EMISSIONS
+            .may_load(deps.storage, &market_addr)?
+            .unwrap_or(Emission {
+                remaining: Uint128::zero(),
+                last_emitted: env.block.time,
+                end: env.block.time,
+                supplier_share,
+            });
+        emission.remaining += amount;
+        emission.last_emitted = env.block.time;
+        emission.end = env.block.time.plus_seconds(duration);
+        emission.supplier_share = supplier_share;
+        EMISSIONS.save(deps.storage, &market_addr, &emission)?;
+
+        let event = events::FundEmission {
+            sender,
+            market: market_addr.clone(),
+            amount: reward,
+            end: emission.end,
+            supplier_share,
+        };
+        Ok(Response::new()
+            .add_attribute("action", "fund_emission")
+            .add_attribute("market", market_addr)
+            .add_attribute("amount", amount)
+            .add_attribute("end", emission.end.seconds().to_string())
+            .add_events(emitted)
+            .add_event(event.into())
+            .add_submessages(messages))
+    }
+
     pub fn migrate_market(
         deps: DepsMut,
         info: MessageInfo,
@@ -820,17 +3892,160 @@ mod restricted {
         let cfg = CONFIG.load(deps.storage)?;
         ensure_governance(&cfg, &info)?;
         let contract = deps.api.addr_validate(&contract_addr)?;
+        let market = query::market_by_address(deps.as_ref(), contract.as_str())?;
 
-        if !find_market(deps.as_ref(), &contract) {
-            return Err(ContractError::MarketSearchError {
-                market: contract_addr,
-            });
+        let msg = migrate_market_msg(
+            deps.storage,
+            &cfg,
+            (market.market_token, market.market_id),
+            &contract,
+            &migrate_msg,
+        )?;
+        let event = events::MigrateMarket {
+            sender: info.sender,
+            market: contract,
+            code_id: cfg.isotonic_market_id,
+        };
+        Ok(Response::new().add_event(event.into()).add_submessage(msg))
+    }
+
+    /// Handler for `ExecuteMsg::MigrateAllMarkets`
+    pub fn migrate_all_markets(
+        deps: DepsMut,
+        info: MessageInfo,
+        migrate_msg: MarketMigrateMsg,
+        start_after: Option<(Token, u32)>,
+        limit: Option<u32>,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        ensure_governance(&cfg, &info)?;
+        let limit = limit
+            .unwrap_or(DEFAULT_MIGRATE_LIMIT)
+            .min(MAX_MIGRATE_LIMIT) as usize;
+
+        let outdated: Vec<_> = MARKETS
+            .range(
+                deps.storage,
+                start_after
+                    .as_ref()
+                    .map(|(token, id)| Bound::exclusive((token, *id))),
+                None,
+                Order::Ascending,
+            )
+            .filter_map(|m| match m {
+                Ok((market_key, MarketState::Ready(addr, code)))
+                    if code.code_id != cfg.isotonic_market_id =>
+                {
+                    Some(Ok((market_key, addr)))
+                }
+                Ok(_) => None,
+                Err(err) => Some(Err(err)),
+            })
+            .take(limit)
+            .collect::<StdResult<_>>()?;
+
+        let mut response = Response::new()
+            .add_attribute("action", "migrate_all_markets")
+            .add_attribute("migrated", outdated.len().to_string());
+        if let Some(((last_token, last_id), _)) = outdated.last() {
+            response = response
+                .add_attribute("last_market", last_token.to_string())
+                .add_attribute("last_market_id", last_id.to_string());
+        }
+        for (market_key, addr) in outdated {
+            let msg = migrate_market_msg(deps.storage, &cfg, market_key, &addr, &migrate_msg)?;
+            let event = events::MigrateMarket {
+                sender: info.sender.clone(),
+                market: addr,
+                code_id: cfg.isotonic_market_id,
+            };
+            response = response.add_event(event.into()).add_submessage(msg);
         }
+        Ok(response)
+    }
 
-        Ok(Response::new().add_message(WasmMsg::Migrate {
-            contract_addr,
-            new_code_id: cfg.isotonic_market_id,
-            msg: to_binary(&migrate_msg)?,
-        }))
+    /// Migration to the current `isotonic_market_id`. The reply updates the code recorded for
+    /// the market.
+    fn migrate_market_msg(
+        storage: &mut dyn Storage,
+        cfg: &Config,
+        market_key: (Token, u32),
+        market: &Addr,
+        migrate_msg: &MarketMigrateMsg,
+    ) -> StdResult<SubMsg> {
+        let reply_id = NEXT_REPLY_ID.update(storage, |id| -> StdResult<_> { Ok(id + 1) })?;
+        REPLY_IDS.save(storage, reply_id, &market_key)?;
+
+        Ok(SubMsg::reply_on_success(
+            WasmMsg::Migrate {
+                contract_addr: market.to_string(),
+                new_code_id: cfg.isotonic_market_id,
+                msg: to_binary(migrate_msg)?,
+            },
+            reply_id,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::MarketCode;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cosmwasm_std::{ContractInfoResponse, ContractResult, Storage, SystemResult, WasmQuery};
+    use cw2::ContractVersion;
+
+    #[test]
+    fn migrate_legacy_markets_to_default_id() {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(|query| {
+            let response = match query {
+                WasmQuery::ContractInfo { .. } => {
+                    to_binary(&ContractInfoResponse::new(1, "credit_agency"))
+                }
+                WasmQuery::Raw { .. } => to_binary(&ContractVersion {
+                    contract: "crates.io:isotonic-market".to_owned(),
+                    version: "0.1.0".to_owned(),
+                }),
+                _ => panic!("Unexpected query"),
+            };
+            SystemResult::Ok(ContractResult::Ok(response.unwrap()))
+        });
+
+        // Entries as stored by the previous version
+        let atom = Token::Native("atom".to_owned());
+        let osmo = Token::Native("osmo".to_owned());
+        deps.storage
+            .set(&LEGACY_MARKETS.key(&atom), br#"{"Ready":"atom_market"}"#);
+        deps.storage
+            .set(&LEGACY_MARKETS.key(&osmo), br#""Instantiating""#);
+
+        let response = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(
+            response.attributes,
+            vec![
+                ("migrated_markets", "1").into(),
+                ("dropped_markets", "1").into()
+            ]
+        );
+
+        assert_eq!(
+            MARKETS
+                .load(deps.as_ref().storage, (&atom, DEFAULT_MARKET_ID))
+                .unwrap(),
+            MarketState::Ready(
+                Addr::unchecked("atom_market"),
+                MarketCode {
+                    code_id: 1,
+                    version: "0.1.0".to_owned(),
+                },
+            )
+        );
+        // Market which never got instantiated is dropped
+        assert!(!MARKETS.has(deps.as_ref().storage, (&osmo, DEFAULT_MARKET_ID)));
+        assert!(LEGACY_MARKETS
+            .range(deps.as_ref().storage, None, None, Order::Ascending)
+            .next()
+            .is_none());
     }
 }
@@ -1,6 +1,10 @@
-use cosmwasm_std::{Addr, StdError, Uint128};
+use cosmwasm_std::{Addr, Decimal, StdError, Uint128};
+use cw_utils::PaymentError;
 use utils::coin::Coin;
-use utils::{coin::CoinError, credit_line::InvalidCommonTokenDenom, price::PriceError};
+use utils::{
+    coin::CoinError, credit_line::InvalidCommonTokenDenom, interest::InterestError,
+    price::PriceError, timelock::TimelockError,
+};
 
 use thiserror::Error;
 
@@ -15,30 +19,36 @@ pub enum ContractError {
     #[error("{0}")]
     Coin(#[from] CoinError),
 
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("{0}")]
+    Timelock(#[from] TimelockError),
+
+    #[error("{0}")]
+    Interest(#[from] InterestError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
     #[error("Creating Market failure - collateral ratio must be lower than liquidation price")]
     MarketCfgCollateralFailure {},
 
-    #[error("Unrecognised reply id: {0}")]
-    UnrecognisedReply(u64),
-
-    #[error("Invalid reply from submessage {id}, {err}")]
-    ReplyParseFailure { id: u64, err: String },
-
     #[error("No market set up for base asset {0}")]
     NoMarket(String),
 
-    #[error("A market for base asset {0} is still being created")]
-    MarketCreating(String),
-
     #[error("A market for base asset {0} already exists")]
     MarketAlreadyExists(String),
 
     #[error("Account cannot be liquidated as it does not have more debt then credit line")]
     LiquidationNotAllowed {},
 
+    #[error("Liquidation profit of {profit} is lower than required {min_profit}")]
+    LiquidationProfitTooLow {
+        profit: Uint128,
+        min_profit: Uint128,
+    },
+
     #[error("Only one denom can be sent for liquidation")]
     LiquidationOnlyOneDenomRequired {},
 
@@ -48,6 +58,9 @@ pub enum ContractError {
     #[error("{market}: Market either does not exist or is not active yet")]
     MarketSearchError { market: String },
 
+    #[error("Market {market} does not handle {token}")]
+    MarketTokenMismatch { market: Addr, token: String },
+
     #[error("{address} is not on a market {market}")]
     NotOnMarket { address: Addr, market: Addr },
 
@@ -68,6 +81,99 @@ pub enum ContractError {
     #[error("Cw20 tokens are not supported yet")]
     Cw20TokensNotSupported,
 
+    #[error("Emission must be funded with the reward token {0}")]
+    InvalidRewardToken(String),
+
+    #[error("Emission duration must be greater than zero")]
+    InvalidEmissionDuration {},
+
+    #[error("Supplier share of emitted rewards must be between 0 and 1")]
+    InvalidSupplierShare {},
+
+    #[error("Liquidation auction requires min_discount <= max_discount < 1 and non-zero duration")]
+    InvalidLiquidationAuction {},
+
+    #[error("Deleverage fee must be lower than the highest liquidation discount")]
+    InvalidDeleverageFee {},
+
+    #[error("Deleverage has to improve the health factor of the account")]
+    DeleverageNotImproving {},
+
+    #[error("Target leverage must be greater than 1 and max slippage lower than 1")]
+    InvalidLeverage {},
+
+    #[error("Expected {expected} of initial collateral, but {received} was sent")]
+    InvalidInitialCollateral {
+        expected: Uint128,
+        received: Uint128,
+    },
+
+    #[error("Leveraged position debt {debt} is over the borrow limit {borrow_limit}")]
+    LeveragedPositionOverBorrowLimit {
+        debt: Uint128,
+        borrow_limit: Uint128,
+    },
+
+    #[error("Account has no debt in {0}")]
+    NoDebt(String),
+
+    #[error("Cannot swap {0} for itself")]
+    SwapSameDenom(String),
+
+    #[error("Debt {debt} after the collateral swap is over the borrow limit {borrow_limit}")]
+    CollateralSwapOverBorrowLimit {
+        debt: Uint128,
+        borrow_limit: Uint128,
+    },
+
+    #[error("New debt of {estimate} is over the limit of {max_new_debt}")]
+    NewDebtOverLimit {
+        estimate: Uint128,
+        max_new_debt: Uint128,
+    },
+
+    #[error("Debt {debt} after the swap is over the borrow limit {borrow_limit}")]
+    DebtSwapOverBorrowLimit {
+        debt: Uint128,
+        borrow_limit: Uint128,
+    },
+
+    #[error("No governance transfer is pending")]
+    NoPendingGovernance {},
+
+    #[error("Market for base asset {0} is not winding down")]
+    MarketNotWindingDown(String),
+
+    #[error("Market for base asset {0} still has cTokens or debt")]
+    MarketNotEmpty(String),
+
+    #[error("Steward bounds require min <= max and collateral ratio lower than liquidation price")]
+    InvalidStewardBounds {},
+
+    #[error("Risk steward cannot set {0} outside of its bounds")]
+    StewardBoundsExceeded(String),
+
+    #[error("No market template {0}")]
+    NoMarketTemplate(String),
+
+    #[error("Market template requires min <= max collateral ratio lower than liquidation price")]
+    InvalidMarketTemplate {},
+
+    #[error("Collateral ratio {0} is outside of the market template range")]
+    CollateralRatioOutOfTemplate(Decimal),
+
+    #[error("Default market id is reserved for markets created by governance")]
+    TemplateMarketDefaultId {},
+
+    #[error("Market for base asset {0} is already created by governance")]
+    GovernanceMarketExists(String),
+
+    #[error("Pair {pair} is not registered in the price oracle for {market_token}")]
+    OraclePoolNotRegistered { pair: String, market_token: String },
+
+    #[error("Market {0} is not in isolation")]
+    MarketNotIsolated(String),
+
     #[error("Repaying loan using collateral failed - your debt is bigger then your credit line")]
     RepayingLoanUsingCollateralFailed {},
 }
@@ -1,9 +1,11 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Decimal, Uint128};
+use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
 
 use cw20::Cw20ReceiveMsg;
 use isotonic_market::msg::MigrateMsg as MarketMigrateMsg;
-use utils::{coin::Coin, interest::Interest, token::Token};
+
+use crate::state::{LiquidationAuction, MarketCode, MarketTemplate, RiskSteward, StewardAction};
+use utils::{coin::Coin, credit_line::CreditLineResponse, interest::Interest, token::Token};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -24,16 +26,63 @@ pub struct InstantiateMsg {
     /// This is used to prevent borrowers from being liquidated (almost) immediately after borrowing,
     /// because they maxed out their credit limit.
     pub borrow_limit_ratio: Decimal,
+    /// If set, liquidation discount is auctioned instead of using fixed `liquidation_price`
+    pub liquidation_auction: Option<LiquidationAuction>,
+    /// Portion of the repaid value charged by `Deleverage`, paid in collateral to the
+    /// governance contract. Has to be lower than the highest liquidation discount
+    pub deleverage_fee: Decimal,
+    /// Time in seconds between proposing a parameter change and executing it, also used for
+    /// the created markets
+    pub param_change_delay: u64,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
     CreateMarket(MarketConfig),
-    /// Tries to perform liquidation on passed account using collateral's denom. The native tokens
-    /// sent along with this message define the debt market.
+    /// Creates a market in isolation with the risk parameters of the governance approved
+    /// template. Can be called by anyone, for assets without markets created by governance.
+    CreateMarketFromTemplate(TemplateMarketConfig),
+    /// Tries to perform liquidation on passed account, repaying debt on `debt_market` and seizing
+    /// collateral from `collateral_market`. The native tokens sent along with this message have
+    /// to be the market token of the debt market.
     Liquidate {
         account: String,
+        debt_market: String,
+        collateral_market: String,
+    },
+    /// Liquidates the account without the liquidator holding the debt token. Seized collateral
+    /// is sold on Wynd DEX for exactly `repay_amount` of `debt_denom`, which repays the debt,
+    /// and the rest of collateral is withdrawn and sent to the sender in `collateral_denom`.
+    /// Fails if the collateral left is less than `min_profit` collateral tokens, or if the
+    /// collateral market lacks the liquidity to withdraw it.
+    LiquidateWithSwap {
+        account: String,
+        debt_denom: Token,
         collateral_denom: Token,
+        repay_amount: Uint128,
+        min_profit: Uint128,
+        /// Id of the debt market, the default market of `debt_denom` if not set
+        debt_market_id: Option<u32>,
+        /// Id of the collateral market, the default market of `collateral_denom` if not set
+        collateral_market_id: Option<u32>,
+    },
+    /// Final step of `LiquidateWithSwap`, withdrawing cTokens of `collateral_market` seized above
+    /// the `ctokens_before` balance of the credit agency, and sending the collateral to the
+    /// liquidator.
+    ///
+    /// Only callable by the credit agency itself
+    SettleLiquidationWithSwap {
+        liquidator: String,
+        collateral_market: String,
+        ctokens_before: Uint128,
+        min_profit: Uint128,
+    },
+    /// Starts the liquidation auction of the account if it is liquidatable, or resets it if the
+    /// account is healthy again. Can be called by anyone - markets call it after repayments, and
+    /// the credit agency itself after its own operations repaying debt. Does nothing while the
+    /// auction is disabled.
+    UpdateLiquidationAuction {
+        account: String,
     },
     /// Ensures a given account has entered a market. Meant to be called by a specific
     /// market contract - so the sender of the msg would be the market. The store is treated as a set.
@@ -51,40 +100,283 @@ pub enum ExecuteMsg {
     /// Repay a loan by using some indicated collateral.
     /// The collateral is traded on Wynd DEX.
     RepayWithCollateral {
+        /// Market the collateral is taken from
+        collateral_market: String,
         /// The maximum amount of collateral to use
+        max_collateral: Uint128,
+        /// Market the loan is repaid to
+        debt_market: String,
+        /// How much of the loan is trying to be repaid
+        amount_to_repay: Uint128,
+    },
+    /// Repays a loan by selling indicated collateral on Wynd DEX, also when the account is
+    /// already liquidatable. Succeeds only if the health factor of the account improves, even if
+    /// the whole `max_collateral` is sold. Charges `deleverage_fee` of the repaid value.
+    Deleverage {
+        /// The maximum amount of collateral to sell
         max_collateral: Coin,
         /// How much of the loan is trying to be repaid
         amount_to_repay: Coin,
+        /// Id of the collateral market, the default market of the collateral if not set
+        collateral_market_id: Option<u32>,
+        /// Id of the debt market, the default market of the debt if not set
+        debt_market_id: Option<u32>,
+    },
+    /// Builds a leveraged position in a single transaction. `initial_amount` of native
+    /// `collateral_denom` has to be sent with the message. Borrows `debt_denom` worth
+    /// `target_leverage - 1` times the initial amount, buys collateral with it on Wynd DEX and
+    /// deposits all collateral for the sender. Fails if the swap returns more than `max_slippage`
+    /// less than the oracle price, or if the position ends up over the borrow limit.
+    OpenLeveragedPosition {
+        collateral_denom: Token,
+        debt_denom: Token,
+        initial_amount: Uint128,
+        target_leverage: Decimal,
+        max_slippage: Decimal,
+        /// Id of the collateral market, the default market of `collateral_denom` if not set
+        collateral_market_id: Option<u32>,
+        /// Id of the debt market, the default market of `debt_denom` if not set
+        debt_market_id: Option<u32>,
+    },
+    /// Unwinds a leveraged position - sells collateral on Wynd DEX for the whole `debt_denom`
+    /// debt of the sender and repays it. The collateral left stays deposited.
+    CloseLeveragedPosition {
+        collateral_denom: Token,
+        debt_denom: Token,
+        max_slippage: Decimal,
+        /// Id of the collateral market, the default market of `collateral_denom` if not set
+        collateral_market_id: Option<u32>,
+        /// Id of the debt market, the default market of `debt_denom` if not set
+        debt_market_id: Option<u32>,
+    },
+    /// Final step of `OpenLeveragedPosition`, depositing `collateral_denom` above the
+    /// `balance_before` balance of the credit agency for the account, and checking that the
+    /// account stays within its borrow limit.
+    ///
+    /// Only callable by the credit agency itself
+    SettleLeveragedPosition {
+        account: String,
+        collateral_denom: Token,
+        collateral_market_id: u32,
+        balance_before: Uint128,
+    },
+    /// Turns collateral into collateral of another market without repaying debt. Withdraws `from`
+    /// of the sender's collateral, sells it on Wynd DEX for at least `min_receive` of `to_denom`
+    /// and deposits it for the sender. Fails if the debt is over the borrow limit after the swap,
+    /// the same limit a withdrawal is checked against.
+    SwapCollateral {
+        from: Coin,
+        to_denom: Token,
+        min_receive: Uint128,
+        /// Id of the market the collateral is withdrawn from, the default market if not set
+        from_market_id: Option<u32>,
+        /// Id of the market the collateral is deposited to, the default market if not set
+        to_market_id: Option<u32>,
+    },
+    /// Final step of `SwapCollateral`, depositing `to_denom` above the `balance_before` balance
+    /// of the credit agency for the account, and checking its borrow limit.
+    ///
+    /// Only callable by the credit agency itself
+    SettleCollateralSwap {
+        account: String,
+        to_denom: Token,
+        to_market_id: u32,
+        balance_before: Uint128,
+    },
+    /// Refinances `amount` of the sender's `from_denom` debt with `to_denom` debt. Borrows
+    /// `to_denom` for the sender, buys `from_denom` with it on Wynd DEX and repays the debt. Fails
+    /// if more than `max_new_debt` has to be borrowed, or if the account ends up over its borrow
+    /// limit. `amount` is capped at the current debt.
+    SwapDebt {
+        from_denom: Token,
+        to_denom: Token,
+        amount: Uint128,
+        max_new_debt: Uint128,
+        /// Id of the market the debt is repaid to, the default market if not set
+        from_market_id: Option<u32>,
+        /// Id of the market the new debt is borrowed from, the default market if not set
+        to_market_id: Option<u32>,
+    },
+    /// Final step of `SwapDebt`, repaying the `from_denom` debt of the account with the balance
+    /// of the credit agency above `balance_before`, and checking the borrow limit of the account.
+    /// Whatever exceeds the debt is sent back to the account.
+    ///
+    /// Only callable by the credit agency itself
+    SettleDebtSwap {
+        account: String,
+        from_denom: Token,
+        from_market_id: u32,
+        balance_before: Uint128,
     },
     /// Handles contract's logics that involves receiving CW20 tokens.
     Receive(Cw20ReceiveMsg),
+    /// Emits rewards accrued so far on given markets. Can be called by anyone.
+    EmitRewards {
+        markets: Vec<String>,
+    },
+    /// Emits rewards accrued so far on given markets and sends all rewards of the sender,
+    /// both as a cToken holder and as a borrower, to the sender.
+    ClaimRewards {
+        markets: Vec<String>,
+    },
+    /// Queues the parameter change, executable after `param_change_delay`. Risk-reducing
+    /// changes, and all changes if there is no delay, are applied immediately.
+    ///
     /// Sender must be the Governance Contract
-    AdjustMarketId {
-        new_market_id: u64,
+    ProposeParamChange {
+        change: ParamChange,
     },
+    /// Applies the pending parameter change once its delay passed.
+    ///
     /// Sender must be the Governance Contract
-    AdjustTokenId {
-        new_token_id: u64,
+    ExecuteParamChange {
+        id: u64,
     },
-    /// Sets common_token parameter in configuration and sends AdjustCommonToken
-    /// message to all affiliated markets
+    /// Removes the pending parameter change.
     ///
     /// Sender must be the Governance Contract
-    AdjustCommonToken {
-        new_common_token: Token,
+    CancelParamChange {
+        id: u64,
+    },
+    /// Applies the change on the `market` immediately and records it in the steward log. New
+    /// values have to be within the steward bounds, market risk limits still apply.
+    ///
+    /// Sender must be the Risk Steward
+    StewardAdjust {
+        market: String,
+        change: StewardChange,
+    },
+    /// Removes a wound down market with no cTokens nor debt left, so a new market can be created
+    /// for its token and market id. Can be called by anyone.
+    RemoveMarket {
+        market: String,
+    },
+    /// Starts the transfer of the governance to `new_governance`, which has to accept it with
+    /// `AcceptGovernance`. The transfer is forwarded to all markets. Replaces the previous
+    /// proposal.
+    ///
+    /// Sender must be the Governance Contract
+    ProposeNewGovernance {
+        new_governance: String,
+    },
+    /// Finishes the governance transfer of the credit agency and of all its markets. The transfer
+    /// is proposed to the markets again before it is accepted, so it covers markets created after
+    /// the proposal and supersedes proposals the market governance made in the meantime.
+    ///
+    /// Sender must be the proposed governance
+    AcceptGovernance {},
+    /// Adds native `reward_token` sent with this message to the emission budget of the market and
+    /// restarts its schedule, so the whole remaining budget is released linearly over
+    /// `duration` seconds.
+    ///
+    /// Sender must be the Governance Contract
+    FundEmission {
+        market: String,
+        duration: u64,
+        /// Portion of emitted rewards going to cToken holders, rest goes to borrowers
+        supplier_share: Decimal,
     },
     /// Sender must be the Governance Contract
     MigrateMarket {
         contract: String,
         migrate_msg: MarketMigrateMsg,
     },
+    /// Migrates up to `limit` markets following `start_after` which don't run the current
+    /// `isotonic_market_id` code yet. Can be repeated until no outdated markets are left.
+    ///
+    /// Sender must be the Governance Contract
+    MigrateAllMarkets {
+        migrate_msg: MarketMigrateMsg,
+        start_after: Option<(Token, u32)>,
+        limit: Option<u32>,
+    },
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ReceiveMsg {
     Liquidate {
         account: String,
-        collateral_denom: Token,
+        debt_market: String,
+        collateral_market: String,
+    },
+    /// Cw20 version of `ExecuteMsg::FundEmission`, used when `reward_token` is a cw20 token
+    FundEmission {
+        market: String,
+        duration: u64,
+        supplier_share: Decimal,
+    },
+}
+
+/// Credit agency parameters governance can change through `ProposeParamChange`
+#[cw_serde]
+pub enum ParamChange {
+    MarketId {
+        new_market_id: u64,
+    },
+    TokenId {
+        new_token_id: u64,
+    },
+    /// Sets common_token parameter in configuration and sends AdjustCommonToken
+    /// message to all affiliated markets
+    CommonToken {
+        new_common_token: Token,
+    },
+    DeleverageFee {
+        fee: Decimal,
+    },
+    /// Enables, changes or (with `None`) disables the liquidation auction. Auctions already
+    /// running keep their start time.
+    LiquidationAuction {
+        auction: Option<LiquidationAuction>,
+    },
+    /// Delay of the credit agency and of markets created later. Markets already created
+    /// keep their own delay.
+    ParamChangeDelay {
+        new_delay: u64,
+    },
+    /// Sets, changes or (with `None`) removes the risk steward and its bounds
+    RiskSteward {
+        steward: Option<RiskSteward>,
+    },
+    /// Starts retiring the market. It stops accepting deposits and borrows, and its collateral
+    /// ratio drops to zero over `duration` seconds. With `force_liquidation` debt left after
+    /// that can be liquidated regardless of the account health.
+    WindDownMarket {
+        market: String,
+        duration: u64,
+        force_liquidation: bool,
+    },
+    /// Adds, replaces or (with `None`) removes the market template. Markets already created
+    /// from the template are not affected.
+    MarketTemplate {
+        name: String,
+        template: Option<MarketTemplate>,
+    },
+    /// Lifts the isolation of a market created from a template, so its collateral gives full
+    /// credit line
+    PromoteMarket {
+        market: String,
+    },
+}
+
+/// Market parameters the risk steward can change through `StewardAdjust`
+#[cw_serde]
+pub enum StewardChange {
+    /// Cap in market tokens, its common token value is checked against the bounds
+    MarketCap {
+        new_cap: Uint128,
+    },
+    InterestRates {
+        new_interest_rates: Interest,
+    },
+    /// Applied immediately in both directions. Lowering the ratio is the one exception to the
+    /// delay governance has to wait for - the steward reacts to market conditions, and how far
+    /// it can go is limited by `min_collateral_ratio` of its bounds.
+    CollateralRatio {
+        new_ratio: Decimal,
     },
 }
 
@@ -101,6 +393,9 @@ pub struct MarketConfig {
     pub decimals: u8,
     /// Token for the market token
     pub market_token: Token,
+    /// Distinguishes markets of the same `market_token`, eg. with different risk parameters
+    #[serde(default)]
+    pub market_id: u32,
     /// An optional cap on total number of tokens deposited into the market
     pub market_cap: Option<Uint128>,
     /// Interest rate curve
@@ -115,20 +410,63 @@ pub struct MarketConfig {
     pub reserve_factor: Decimal,
 }
 
+#[cw_serde]
+pub struct TemplateMarketConfig {
+    /// Name of the market template
+    pub template: String,
+    /// Name used to create the cToken name `Lent ${name}`.
+    /// Forwarded to `isotonic-token`.
+    pub name: String,
+    /// Symbol used to create the cToken `C${symbol}`.
+    /// Forwarded to `isotonic-token`.
+    pub symbol: String,
+    /// Decimals for cToken.
+    /// Forwarded to `isotonic-token`.
+    pub decimals: u8,
+    /// Token for the market token
+    pub market_token: Token,
+    /// Distinguishes markets of the same `market_token`. The default market id is reserved for
+    /// markets created by governance.
+    pub market_id: u32,
+    /// Ratio of how much tokens can be borrowed for one unit, within the template range
+    pub collateral_ratio: Decimal,
+    /// Wynd DEX pair of `market_token` and the common token, has to be registered in the price
+    /// oracle of the template
+    pub pair_contract: String,
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
     /// Returns current configuration
     #[returns(crate::state::Config)]
     Configuration {},
-    /// Queries a market address by market token
+    /// Queries a market address by market token and market id, the default market of the token
+    /// if no id is given
     #[returns(MarketResponse)]
-    Market { market_token: Token },
+    Market {
+        market_token: Token,
+        market_id: Option<u32>,
+    },
+    /// Returns the addresses the next market of `market_token` and `market_id` and its cToken
+    /// would be created at, the default market id if no id is given
+    #[returns(PredictMarketAddressResponse)]
+    PredictMarketAddress {
+        market_token: Token,
+        market_id: Option<u32>,
+    },
     /// List all base assets and the addresses of markets handling them.
-    /// Pagination by base asset
+    /// Pagination by base asset and market id
     #[returns(ListMarketsResponse)]
     ListMarkets {
-        start_after: Option<Token>,
+        start_after: Option<(Token, u32)>,
+        limit: Option<u32>,
+    },
+    /// Same as `ListMarkets`, but only lists markets which don't run the current
+    /// `isotonic_market_id` code, with the code they run.
+    #[returns(ListOutdatedMarketsResponse)]
+    ListOutdatedMarkets {
+        start_after: Option<(Token, u32)>,
         limit: Option<u32>,
     },
     /// Queries all markets for credit lines for particular account
@@ -148,15 +486,91 @@ pub enum QueryMsg {
     /// included in market before leaving it (to not waste tokens on obsolete call).
     #[returns(IsOnMarketResponse)]
     IsOnMarket { account: String, market: String },
+    /// Lists all accounts participating in any market. Pagination by account address.
+    #[returns(ListAccountsResponse)]
+    ListAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Checks up to `limit` accounts following `start_after` and returns those which can be
+    /// liquidated. As every account costs queries to all its markets, the limit is lower than
+    /// for other listings - use `last_checked` as `start_after` to continue.
+    #[returns(ListLiquidatableResponse)]
+    ListLiquidatable {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
     /// Checks if the given account is liquidatable and returns the necessary information to do so.
     #[returns(LiquidationResponse)]
     Liquidation { account: String },
+    /// Returns what `Liquidate` would do for the given repayment - how much collateral would be
+    /// seized and if it would succeed at all.
+    #[returns(LiquidationQuoteResponse)]
+    LiquidationQuote {
+        account: String,
+        debt_denom: Token,
+        repay_amount: Uint128,
+        collateral_denom: Token,
+        /// Id of the debt market, the default market of `debt_denom` if not set
+        debt_market_id: Option<u32>,
+        /// Id of the collateral market, the default market of `collateral_denom` if not set
+        collateral_market_id: Option<u32>,
+    },
+    /// Returns the liquidation auction of the account and the liquidation price applied to it now.
+    #[returns(LiquidationAuctionResponse)]
+    LiquidationAuction { account: String },
+    /// Returns health of the account - its health factor, and how every market it participates
+    /// in contributes to it.
+    #[returns(AccountHealthResponse)]
+    AccountHealth { account: String },
+    /// Applies hypothetical actions to the account one after another and returns how its credit
+    /// line would look like after every step. Actions which would be rejected by the market are
+    /// reported and skipped. Only the account credit is verified, market liquidity is not.
+    #[returns(SimulateActionsResponse)]
+    SimulateActions {
+        account: String,
+        actions: Vec<Action>,
+    },
+    /// Returns the reward emission schedule of the market.
+    #[returns(EmissionResponse)]
+    Emission { market: String },
+    /// Returns parameter changes waiting for their delay, ordered by id
+    #[returns(PendingParamChangesResponse)]
+    PendingParamChanges {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the audit log of risk steward actions, ordered by id
+    #[returns(StewardLogResponse)]
+    StewardLog {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the governance proposed with `ProposeNewGovernance`, not accepted yet
+    #[returns(PendingGovernanceResponse)]
+    PendingGovernance {},
+    /// Lists market templates approved by the governance. Pagination by template name.
+    #[returns(MarketTemplatesResponse)]
+    MarketTemplates {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the credit line cap of the market if it is in isolation
+    #[returns(IsolatedMarketResponse)]
+    IsolatedMarket { market: String },
 }
 
 #[cw_serde]
 pub struct MarketResponse {
     pub market_token: Token,
+    pub market_id: u32,
+    pub market: Addr,
+}
+
+#[cw_serde]
+pub struct PredictMarketAddressResponse {
     pub market: Addr,
+    pub ctoken: Addr,
 }
 
 #[cw_serde]
@@ -164,6 +578,21 @@ pub struct ListMarketsResponse {
     pub markets: Vec<MarketResponse>,
 }
 
+#[cw_serde]
+pub struct OutdatedMarketResponse {
+    pub market_token: Token,
+    pub market_id: u32,
+    pub market: Addr,
+    pub code: MarketCode,
+}
+
+#[cw_serde]
+pub struct ListOutdatedMarketsResponse {
+    /// Code id markets are migrated to
+    pub current_code_id: u64,
+    pub markets: Vec<OutdatedMarketResponse>,
+}
+
 #[cw_serde]
 pub struct ListEnteredMarketsResponse {
     pub markets: Vec<Addr>,
@@ -174,9 +603,190 @@ pub struct IsOnMarketResponse {
     pub participating: bool,
 }
 
+#[cw_serde]
+pub struct ListAccountsResponse {
+    pub accounts: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct LiquidatableAccount {
+    pub account: Addr,
+    pub credit_line: CreditLineResponse,
+}
+
+#[cw_serde]
+pub struct ListLiquidatableResponse {
+    pub accounts: Vec<LiquidatableAccount>,
+    /// Last account checked, `None` if there are no more accounts to check
+    pub last_checked: Option<Addr>,
+}
+
 #[cw_serde]
 pub struct LiquidationResponse {
     pub can_liquidate: bool,
     pub debt: Vec<(Addr, Coin)>,
     pub collateral: Vec<(Addr, Coin)>,
 }
+
+#[cw_serde]
+pub struct LiquidationQuoteResponse {
+    /// If the account has more debt than credit line
+    pub can_liquidate: bool,
+    /// If the liquidation would succeed - the account can be liquidated, the repayment doesn't
+    /// exceed its debt, and it has enough collateral
+    pub success: bool,
+    /// Value of the repayment, in common token
+    pub repay_value: Coin,
+    /// Collateral seized, in collateral token
+    pub seized: Coin,
+    /// Amount of cTokens of the collateral market transferred to the liquidator
+    pub seized_ctokens: Uint128,
+    /// Maximum repayment accepted for the account, limited by both its debt and its collateral
+    pub max_repay: Coin,
+}
+
+#[cw_serde]
+pub struct LiquidationAuctionResponse {
+    /// Time at which the auction started, `None` if it didn't start yet
+    pub start: Option<Timestamp>,
+    /// Price for collateral which liquidation would use now
+    pub liquidation_price: Decimal,
+}
+
+#[cw_serde]
+pub struct PendingParamChangeResponse {
+    pub id: u64,
+    pub change: ParamChange,
+    pub executable_at: Timestamp,
+}
+
+#[cw_serde]
+pub struct PendingParamChangesResponse {
+    pub changes: Vec<PendingParamChangeResponse>,
+}
+
+#[cw_serde]
+pub struct PendingGovernanceResponse {
+    pub new_governance: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct MarketTemplateEntry {
+    pub name: String,
+    pub template: MarketTemplate,
+}
+
+#[cw_serde]
+pub struct MarketTemplatesResponse {
+    pub templates: Vec<MarketTemplateEntry>,
+}
+
+#[cw_serde]
+pub struct IsolatedMarketResponse {
+    /// Highest credit line the market gives a single account, `None` if it is not isolated
+    pub credit_cap: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct StewardLogEntry {
+    pub id: u64,
+    pub action: StewardAction,
+}
+
+#[cw_serde]
+pub struct StewardLogResponse {
+    pub actions: Vec<StewardLogEntry>,
+}
+
+#[cw_serde]
+pub struct AccountHealthResponse {
+    /// `credit_line / debt` - account can be liquidated when it drops below 1.
+    /// `None` if account has no debt.
+    pub health_factor: Option<Decimal>,
+    /// Sum of all markets, in common token
+    pub total: CreditLineResponse,
+    pub markets: Vec<MarketHealth>,
+}
+
+#[cw_serde]
+pub struct MarketHealth {
+    pub market: Addr,
+    pub market_token: Token,
+    pub collateral_ratio: Decimal,
+    /// Price of single market token in common token, used for calculations
+    pub price: Decimal,
+    /// Collateral of the account in market token
+    pub collateral: Coin,
+    /// Debt of the account in market token
+    pub debt: Coin,
+    /// Contribution of the market to the account credit line, in common token
+    pub credit_line: CreditLineResponse,
+    /// Relative drop of the market token price, which would make the account liquidatable
+    /// if all other prices stay the same. Zero if the account can already be liquidated,
+    /// `None` if no drop of this price can make it liquidatable.
+    pub liquidation_price_drop: Option<Decimal>,
+    /// Price of the market token at which the account becomes liquidatable, matching
+    /// `liquidation_price_drop`
+    pub liquidation_price: Option<Decimal>,
+}
+
+/// Hypothetical change of an account position, used by `QueryMsg::SimulateActions`. Market ids
+/// default to the default market of the token.
+#[cw_serde]
+pub enum Action {
+    Deposit {
+        amount: Coin,
+        market_id: Option<u32>,
+    },
+    Withdraw {
+        amount: Coin,
+        market_id: Option<u32>,
+    },
+    Borrow {
+        amount: Coin,
+        market_id: Option<u32>,
+    },
+    Repay {
+        amount: Coin,
+        market_id: Option<u32>,
+    },
+    /// Replaces `sell` collateral with `buy` collateral, as if swapped on the DEX
+    SwapCollateral {
+        sell: Coin,
+        buy: Coin,
+        sell_market_id: Option<u32>,
+        buy_market_id: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct SimulateActionsResponse {
+    /// Credit line after all accepted actions, in common token
+    pub total: CreditLineResponse,
+    /// Health factor after all accepted actions, as in `AccountHealthResponse`
+    pub health_factor: Option<Decimal>,
+    pub actions: Vec<SimulatedAction>,
+}
+
+#[cw_serde]
+pub struct SimulatedAction {
+    pub action: Action,
+    /// If the market would accept the action - borrowing within the borrow limit, or
+    /// withdrawing within the transferable amount
+    pub accepted: bool,
+    /// Credit line after this step, in common token
+    pub total: CreditLineResponse,
+    pub health_factor: Option<Decimal>,
+}
+
+#[cw_serde]
+pub struct EmissionResponse {
+    /// Amount of rewards not yet emitted
+    pub remaining: Uint128,
+    /// Amount of rewards accrued since the last emission, emitted on the next `EmitRewards`
+    pub pending: Uint128,
+    /// Time at which all rewards are emitted
+    pub end: Timestamp,
+    /// Portion of emitted rewards going to cToken holders, rest goes to borrowers
+    pub supplier_share: Decimal,
+}
@@ -1,11 +1,29 @@
 /*
 mod liquidate;
+mod liquidate_with_swap;
+mod liquidation_auction;
+mod liquidation_quote;
+mod deleverage;
+mod emission;
+mod events;
+mod governance;
 mod instantiate;
+mod leveraged_position;
+mod list_accounts;
 mod market_create;
 mod market_participation;
+mod market_templates;
+mod market_tiers;
+mod migrate_markets;
+mod param_change;
 mod queries;
 mod repay_with_collateral;
 mod restricted;
+mod risk_steward;
+mod simulate_actions;
+mod swap_collateral;
+mod swap_debt;
+mod wind_down;
 
 mod total_credit_line;
 pub mod suite;
@@ -0,0 +1,223 @@
+use cosmwasm_std::{Addr, Decimal};
+use utils::events::Deleverage;
+use utils::tests::event_attribute;
+use utils::token::Token;
+
+use super::suite::{Suite, SuiteBuilder, COMMON, DEBTOR, GOVERNANCE, JUNO, LIQUIDATOR};
+use crate::error::ContractError;
+use crate::state::LiquidationAuction;
+
+const YEAR_IN_SECONDS: u64 = 365 * 24 * 3600;
+
+/// Same situation as in `liquidation_quote::liquidatable_suite` - `DEBTOR` has 576 JUNO of
+/// collateral, 474 JUNO of debt, and 460 of credit line
+fn liquidatable_suite() -> Suite {
+    let common_token = Token::Native(COMMON.to_owned());
+    let native_token = Token::Native(JUNO.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_funds(DEBTOR, &[native_token.clone().into_coin(600u128)])
+        .with_deleverage_fee(Decimal::percent(1))
+        .with_pool(
+            1,
+            (
+                common_token.into_coin(100u128),
+                native_token.clone().into_coin(100u128),
+            ),
+        )
+        .build();
+
+    suite
+        .create_market_quick(
+            GOVERNANCE,
+            &native_token.denom(),
+            native_token.clone(),
+            Decimal::percent(80),
+            None,
+            None,
+        )
+        .unwrap();
+
+    suite
+        .deposit_tokens_on_market(DEBTOR, native_token.clone().into_coin(500u128))
+        .unwrap();
+    suite
+        .borrow_tokens_from_market(DEBTOR, native_token.clone().into_coin(400u128))
+        .unwrap();
+
+    suite.advance_seconds(YEAR_IN_SECONDS);
+
+    // Repay some tokens to trigger interest rate charges
+    suite
+        .repay_tokens_on_market(DEBTOR, native_token.into_coin(2u128))
+        .unwrap();
+
+    suite
+}
+
+#[test]
+fn deleverage_liquidatable_account() {
+    let mut suite = liquidatable_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let ctoken = suite
+        .query_market_config(juno.clone())
+        .unwrap()
+        .ctoken_contract
+        .to_string();
+
+    let before = suite
+        .query_account_health(DEBTOR)
+        .unwrap()
+        .health_factor
+        .unwrap();
+    assert!(before < Decimal::one());
+
+    // `RepayWithCollateral` refuses as the account would stay unhealthy - debt of 374 over
+    // 460 - 0.8 * 110 = 372 of credit line
+    let err = suite
+        .repay_with_collateral(
+            DEBTOR,
+            juno.clone().into_coin(110u128),
+            juno.clone().into_coin(100u128),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::RepayingLoanUsingCollateralFailed {},
+        err.downcast().unwrap()
+    );
+
+    let response = suite
+        .deleverage(
+            DEBTOR,
+            juno.clone().into_coin(110u128),
+            juno.clone().into_coin(100u128),
+        )
+        .unwrap();
+    assert_eq!(
+        event_attribute::<Deleverage>(&response.events, "account"),
+        Some(DEBTOR.to_owned())
+    );
+    assert_eq!(
+        event_attribute::<Deleverage>(&response.events, "amount"),
+        Some(format!("100{}", JUNO))
+    );
+    assert_eq!(
+        event_attribute::<Deleverage>(&response.events, "collateral"),
+        Some(format!("110{}", JUNO))
+    );
+    assert!(event_attribute::<Deleverage>(&response.events, "fee_common").is_some());
+
+    let total = suite.query_total_credit_line(DEBTOR).unwrap();
+    assert_eq!(total.debt, suite.common_token().amount(374u128));
+    let after = suite
+        .query_account_health(DEBTOR)
+        .unwrap()
+        .health_factor
+        .unwrap();
+    assert!(after > before);
+
+    // 1% fee of 100 repaid
+    assert!(suite.query_cw20_balance(GOVERNANCE, ctoken).unwrap() > 0);
+}
+
+#[test]
+fn health_factor_has_to_improve() {
+    let mut suite = liquidatable_suite();
+    let juno = Token::Native(JUNO.to_owned());
+
+    // Selling 500 of collateral lowers the credit line by 400, much more than repaying 10
+    let err = suite
+        .deleverage(
+            DEBTOR,
+            juno.clone().into_coin(500u128),
+            juno.into_coin(10u128),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::DeleverageNotImproving {},
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn not_on_market() {
+    let mut suite = liquidatable_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let market = suite.query_market(juno.clone()).unwrap().market;
+
+    let err = suite
+        .deleverage(
+            LIQUIDATOR,
+            juno.clone().into_coin(100u128),
+            juno.into_coin(10u128),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NotOnMarket {
+            address: Addr::unchecked(LIQUIDATOR),
+            market,
+        },
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn adjust_fee() {
+    let mut suite = liquidatable_suite();
+
+    let err = suite
+        .adjust_deleverage_fee(LIQUIDATOR, Decimal::percent(2))
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    let err = suite
+        .adjust_deleverage_fee(GOVERNANCE, Decimal::one())
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidDeleverageFee {},
+        err.downcast().unwrap()
+    );
+
+    suite
+        .adjust_deleverage_fee(GOVERNANCE, Decimal::percent(2))
+        .unwrap();
+    assert_eq!(
+        suite.query_config().unwrap().deleverage_fee,
+        Decimal::percent(2)
+    );
+}
+
+#[test]
+fn fee_below_liquidation_discount() {
+    // Liquidation price is 0.92 - 8% of discount
+    let mut suite = liquidatable_suite();
+
+    let err = suite
+        .adjust_deleverage_fee(GOVERNANCE, Decimal::percent(8))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidDeleverageFee {},
+        err.downcast().unwrap()
+    );
+    suite
+        .adjust_deleverage_fee(GOVERNANCE, Decimal::percent(7))
+        .unwrap();
+
+    // Auction with lower discounts would make the fee too high
+    let err = suite
+        .adjust_liquidation_auction(
+            GOVERNANCE,
+            Some(LiquidationAuction {
+                min_discount: Decimal::percent(2),
+                max_discount: Decimal::percent(5),
+                duration: 1000,
+            }),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidDeleverageFee {},
+        err.downcast().unwrap()
+    );
+    assert_eq!(suite.query_config().unwrap().liquidation_auction, None);
+}
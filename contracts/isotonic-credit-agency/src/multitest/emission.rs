@@ -0,0 +1,144 @@
+use cosmwasm_std::{coin, Decimal, Uint128};
+use utils::token::Token;
+
+use super::suite::{Suite, SuiteBuilder, COMMON, GOVERNANCE, LENDER, OSMO};
+use crate::error::ContractError;
+
+const REWARD: &str = "reward";
+
+/// Creates a single OSMO market with `LENDER` being its only supplier
+fn suite_with_market() -> (Suite, String) {
+    let common_token = Token::Native(COMMON.to_owned());
+    let market_token = Token::Native(OSMO.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_reward_token(Token::Native(REWARD.to_owned()))
+        .with_funds(LENDER, &[market_token.clone().into_coin(1000u128)])
+        .with_funds(
+            GOVERNANCE,
+            &[Token::Native(REWARD.to_owned()).into_coin(2000u128)],
+        )
+        .with_pool(
+            1,
+            (
+                common_token.into_coin(100u128),
+                market_token.clone().into_coin(100u128),
+            ),
+        )
+        .build();
+
+    suite
+        .create_market_quick(
+            GOVERNANCE,
+            &market_token.denom(),
+            market_token.clone(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(LENDER, market_token.clone().into_coin(1000u128))
+        .unwrap();
+
+    let market = suite.query_market(market_token).unwrap().market.to_string();
+    (suite, market)
+}
+
+#[test]
+fn fund_emission_requires_governance() {
+    let (mut suite, market) = suite_with_market();
+
+    let err = suite
+        .fund_emission(LENDER, &market, 100, Decimal::one(), &[])
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+}
+
+#[test]
+fn fund_emission_validates_params() {
+    let (mut suite, market) = suite_with_market();
+
+    let err = suite
+        .fund_emission(
+            GOVERNANCE,
+            &market,
+            0,
+            Decimal::one(),
+            &[coin(1000, REWARD)],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidEmissionDuration {},
+        err.downcast().unwrap()
+    );
+
+    let err = suite
+        .fund_emission(
+            GOVERNANCE,
+            &market,
+            100,
+            Decimal::percent(101),
+            &[coin(1000, REWARD)],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidSupplierShare {},
+        err.downcast().unwrap()
+    );
+
+    let err = suite
+        .fund_emission(
+            GOVERNANCE,
+            LENDER,
+            100,
+            Decimal::one(),
+            &[coin(1000, REWARD)],
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MarketSearchError {
+            market: LENDER.to_owned()
+        },
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn rewards_are_emitted_linearly() {
+    let (mut suite, market) = suite_with_market();
+
+    suite
+        .fund_emission(
+            GOVERNANCE,
+            &market,
+            100,
+            Decimal::percent(50),
+            &[coin(1000, REWARD)],
+        )
+        .unwrap();
+
+    suite.advance_seconds(50);
+    let emission = suite.query_emission(&market).unwrap();
+    assert_eq!(emission.pending, Uint128::new(500));
+    assert_eq!(emission.remaining, Uint128::new(1000));
+
+    // There are no borrowers, so their half is kept for later
+    suite.emit_rewards(LENDER, &[&market]).unwrap();
+    let emission = suite.query_emission(&market).unwrap();
+    assert_eq!(emission.pending, Uint128::zero());
+    assert_eq!(emission.remaining, Uint128::new(750));
+
+    suite.claim_rewards(LENDER, &[&market]).unwrap();
+    assert_eq!(suite.query_native_balance(LENDER, REWARD).unwrap(), 250);
+
+    // After the end of the period everything left is emitted
+    suite.advance_seconds(100);
+    suite.claim_rewards(LENDER, &[&market]).unwrap();
+    assert_eq!(suite.query_native_balance(LENDER, REWARD).unwrap(), 625);
+    assert_eq!(
+        suite.query_emission(&market).unwrap().remaining,
+        Uint128::new(375)
+    );
+}
@@ -0,0 +1,94 @@
+use cosmwasm_std::{Addr, Decimal};
+use utils::events::{Borrow, CreateMarket, EnterMarket, ExitMarket};
+use utils::tests::{assert_event, event_attribute};
+use utils::token::Token;
+
+use super::suite::{SuiteBuilder, ACTOR, COMMON, GOVERNANCE, JUNO};
+
+#[test]
+fn market_lifecycle_events() {
+    let juno = Token::Native(JUNO.to_owned());
+    let mut suite = SuiteBuilder::new().with_gov(GOVERNANCE).build();
+
+    let response = suite
+        .create_market_quick(GOVERNANCE, JUNO, juno.clone(), None, None, None)
+        .unwrap();
+    let market = suite.query_market(juno).unwrap().market;
+    assert_eq!(
+        event_attribute::<CreateMarket>(&response.events, "market"),
+        Some(market.to_string())
+    );
+    assert_eq!(
+        event_attribute::<CreateMarket>(&response.events, "market_token"),
+        Some(JUNO.to_owned())
+    );
+    assert_eq!(
+        event_attribute::<CreateMarket>(&response.events, "template"),
+        None
+    );
+
+    let response = suite.enter_market(market.as_str(), ACTOR).unwrap();
+    assert_event(
+        &response.events,
+        &EnterMarket {
+            sender: market.clone(),
+            account: Addr::unchecked(ACTOR),
+            market: market.clone(),
+        },
+    );
+
+    let response = suite.exit_market(ACTOR, market.as_str()).unwrap();
+    assert_event(
+        &response.events,
+        &ExitMarket {
+            sender: Addr::unchecked(ACTOR),
+            account: Addr::unchecked(ACTOR),
+            market,
+        },
+    );
+}
+
+#[test]
+fn borrow_enters_market() {
+    let common = Token::Native(COMMON.to_owned());
+    let juno = Token::Native(JUNO.to_owned());
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_funds(ACTOR, &[juno.clone().into_coin(100u128)])
+        .with_pool(
+            1,
+            (common.into_coin(100u128), juno.clone().into_coin(100u128)),
+        )
+        .build();
+
+    suite
+        .create_market_quick(
+            GOVERNANCE,
+            JUNO,
+            juno.clone(),
+            Decimal::percent(80),
+            None,
+            None,
+        )
+        .unwrap();
+    let market = suite.query_market(juno.clone()).unwrap().market;
+    suite
+        .deposit_tokens_on_market(ACTOR, juno.clone().into_coin(100u128))
+        .unwrap();
+
+    let response = suite
+        .borrow_tokens_from_market(ACTOR, juno.into_coin(50u128))
+        .unwrap();
+    assert_eq!(
+        event_attribute::<Borrow>(&response.events, "amount"),
+        Some(format!("50{}", JUNO))
+    );
+    assert_event(
+        &response.events,
+        &EnterMarket {
+            sender: market.clone(),
+            account: Addr::unchecked(ACTOR),
+            market,
+        },
+    );
+}
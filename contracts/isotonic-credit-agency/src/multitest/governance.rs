@@ -0,0 +1,114 @@
+use cosmwasm_std::Addr;
+use cw_multi_test::Executor;
+use isotonic_market::msg::ExecuteMsg as MarketExecuteMsg;
+use utils::token::Token;
+
+use super::suite::{SuiteBuilder, ATOM, GOVERNANCE, JUNO, LIQUIDATOR, OSMO};
+use crate::error::ContractError;
+
+const NEW_GOVERNANCE: &str = "new_governance";
+
+#[test]
+fn transfer_forwarded_to_markets() {
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+    let mut suite = SuiteBuilder::new().with_gov(GOVERNANCE).build();
+    for token in [&juno, &atom] {
+        suite
+            .create_market_quick(GOVERNANCE, &token.denom(), token.clone(), None, None, None)
+            .unwrap();
+    }
+
+    let err = suite
+        .propose_new_governance(LIQUIDATOR, NEW_GOVERNANCE)
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    suite
+        .propose_new_governance(GOVERNANCE, NEW_GOVERNANCE)
+        .unwrap();
+    assert_eq!(
+        suite.query_pending_governance().unwrap(),
+        Some(Addr::unchecked(NEW_GOVERNANCE))
+    );
+    assert_eq!(
+        suite.query_config().unwrap().gov_contract,
+        Addr::unchecked(GOVERNANCE)
+    );
+
+    let err = suite.accept_governance(GOVERNANCE).unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    suite.accept_governance(NEW_GOVERNANCE).unwrap();
+    assert_eq!(
+        suite.query_config().unwrap().gov_contract,
+        Addr::unchecked(NEW_GOVERNANCE)
+    );
+    assert_eq!(suite.query_pending_governance().unwrap(), None);
+    for token in [juno, atom] {
+        assert_eq!(
+            suite
+                .query_market_config(token)
+                .unwrap()
+                .governance_contract,
+            Addr::unchecked(NEW_GOVERNANCE)
+        );
+    }
+}
+
+#[test]
+fn transfer_covers_markets_changed_since_proposal() {
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+    let osmo = Token::Native(OSMO.to_owned());
+    let mut suite = SuiteBuilder::new().with_gov(GOVERNANCE).build();
+    for token in [&juno, &atom] {
+        suite
+            .create_market_quick(GOVERNANCE, &token.denom(), token.clone(), None, None, None)
+            .unwrap();
+    }
+
+    suite
+        .propose_new_governance(GOVERNANCE, NEW_GOVERNANCE)
+        .unwrap();
+
+    // Market governance replaces the forwarded proposal
+    let atom_market = suite.query_market(atom.clone()).unwrap().market;
+    suite
+        .app()
+        .execute_contract(
+            Addr::unchecked(GOVERNANCE),
+            atom_market,
+            &MarketExecuteMsg::ProposeNewGovernance {
+                new_governance: LIQUIDATOR.to_owned(),
+            },
+            &[],
+        )
+        .unwrap();
+    // Market created after the proposal
+    suite
+        .create_market_quick(GOVERNANCE, OSMO, osmo.clone(), None, None, None)
+        .unwrap();
+
+    suite.accept_governance(NEW_GOVERNANCE).unwrap();
+    for token in [juno, atom, osmo] {
+        assert_eq!(
+            suite
+                .query_market_config(token)
+                .unwrap()
+                .governance_contract,
+            Addr::unchecked(NEW_GOVERNANCE)
+        );
+    }
+}
+
+#[test]
+fn nothing_to_accept() {
+    let mut suite = SuiteBuilder::new().with_gov(GOVERNANCE).build();
+
+    let err = suite.accept_governance(NEW_GOVERNANCE).unwrap_err();
+    assert_eq!(
+        ContractError::NoPendingGovernance {},
+        err.downcast().unwrap()
+    );
+}
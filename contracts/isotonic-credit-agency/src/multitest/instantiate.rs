@@ -27,6 +27,10 @@ fn market_instantiate_and_query_config() {
             common_token,
             liquidation_price: Decimal::percent(92),
             borrow_limit_ratio: Decimal::one(),
+            liquidation_auction: None,
+            deleverage_fee: Decimal::zero(),
+            param_change_delay: 0,
+            risk_steward: None,
         },
         suite.query_config().unwrap()
     );
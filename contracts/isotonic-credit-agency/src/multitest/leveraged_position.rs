@@ -0,0 +1,197 @@
+use cosmwasm_std::{Decimal, Uint128};
+use utils::events::{CloseLeveragedPosition, OpenLeveragedPosition, SettleDeposit};
+use utils::tests::event_attribute;
+use utils::token::Token;
+
+use super::suite::{
+    Suite, SuiteBuilder, ACTOR, ATOM, COMMON, DEPOSIT, DEPOSIT_2, GOVERNANCE, JUNO,
+};
+use crate::error::ContractError;
+
+/// JUNO (price 2.0) and ATOM (price 0.5) markets with plenty of liquidity, and `ACTOR` holding
+/// 100k JUNO to build a position with.
+fn leverage_suite() -> Suite {
+    let common_token = Token::Native(COMMON.to_owned());
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_funds(DEPOSIT, &[juno.clone().into_coin(10_000_000u128)])
+        .with_funds(DEPOSIT_2, &[atom.clone().into_coin(10_000_000u128)])
+        .with_funds(ACTOR, &[juno.clone().into_coin(100_000u128)])
+        .with_pool(
+            1,
+            (
+                common_token.clone().into_coin(20_000_000u128),
+                juno.clone().into_coin(10_000_000u128),
+            ),
+        )
+        .with_pool(
+            2,
+            (
+                common_token.into_coin(5_000_000u128),
+                atom.clone().into_coin(10_000_000u128),
+            ),
+        )
+        .build();
+
+    for token in [&juno, &atom] {
+        suite
+            .create_market_quick(GOVERNANCE, &token.denom(), token.clone(), None, None, None)
+            .unwrap();
+    }
+
+    suite
+        .deposit_tokens_on_market(DEPOSIT, juno.into_coin(10_000_000u128))
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(DEPOSIT_2, atom.into_coin(10_000_000u128))
+        .unwrap();
+
+    suite
+}
+
+fn debt_of(suite: &Suite, token: &Token) -> Uint128 {
+    suite
+        .query_account_health(ACTOR)
+        .unwrap()
+        .markets
+        .into_iter()
+        .find(|m| &m.market_token == token)
+        .map(|m| m.debt.amount)
+        .unwrap_or_default()
+}
+
+#[test]
+fn open_and_close() {
+    let mut suite = leverage_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    // 1.8x of 100k JUNO - 80k JUNO (160k common) is bought for 320k ATOM
+    let response = suite
+        .open_leveraged_position(
+            ACTOR,
+            juno.clone().into_coin(100_000u128),
+            atom.clone(),
+            Decimal::percent(180),
+            Decimal::percent(10),
+        )
+        .unwrap();
+    assert_eq!(
+        event_attribute::<OpenLeveragedPosition>(&response.events, "amount"),
+        Some(format!("100000{}", JUNO))
+    );
+    assert_eq!(
+        event_attribute::<OpenLeveragedPosition>(&response.events, "borrowed"),
+        Some(format!("320000{}", ATOM))
+    );
+    assert_eq!(
+        event_attribute::<OpenLeveragedPosition>(&response.events, "leverage"),
+        Some("1.8".to_owned())
+    );
+    let juno_market = suite.query_market(juno.clone()).unwrap().market;
+    assert_eq!(
+        event_attribute::<SettleDeposit>(&response.events, "market"),
+        Some(juno_market.to_string())
+    );
+
+    assert_eq!(debt_of(&suite, &atom), Uint128::new(320_000));
+    assert_eq!(suite.query_native_balance(ACTOR, JUNO).unwrap(), 0);
+
+    // All collateral is deposited - a bit less than 180k JUNO because of fees and price impact
+    let total = suite.query_total_credit_line(ACTOR).unwrap();
+    assert!(total.collateral.amount > Uint128::new(340_000));
+    assert!(total.collateral.amount < Uint128::new(360_000));
+    assert!(total.credit_line.amount >= total.debt.amount);
+
+    // Nothing is left on the credit agency
+    let ca = suite.credit_agency();
+    assert_eq!(suite.query_native_balance(ca.as_str(), JUNO).unwrap(), 0);
+    assert_eq!(suite.query_native_balance(ca.as_str(), ATOM).unwrap(), 0);
+
+    let response = suite
+        .close_leveraged_position(ACTOR, juno, atom.clone(), Decimal::percent(10))
+        .unwrap();
+    assert_eq!(
+        event_attribute::<CloseLeveragedPosition>(&response.events, "account"),
+        Some(ACTOR.to_owned())
+    );
+    assert_eq!(
+        event_attribute::<CloseLeveragedPosition>(&response.events, "amount"),
+        Some(format!("320000{}", ATOM))
+    );
+
+    assert_eq!(debt_of(&suite, &atom), Uint128::zero());
+    // Collateral not needed for the repayment stays deposited
+    let total = suite.query_total_credit_line(ACTOR).unwrap();
+    assert!(total.collateral.amount > Uint128::new(150_000));
+    assert!(total.collateral.amount < Uint128::new(250_000));
+}
+
+#[test]
+fn slippage_too_high() {
+    let mut suite = leverage_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    // Price impact of the 320k ATOM swap is over 1%
+    suite
+        .open_leveraged_position(
+            ACTOR,
+            juno.into_coin(100_000u128),
+            atom.clone(),
+            Decimal::percent(180),
+            Decimal::percent(1),
+        )
+        .unwrap_err();
+
+    assert_eq!(debt_of(&suite, &atom), Uint128::zero());
+    assert_eq!(suite.query_native_balance(ACTOR, JUNO).unwrap(), 100_000);
+}
+
+#[test]
+fn over_borrow_limit() {
+    let mut suite = leverage_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    // 400k common of debt for less than 300k JUNO with 0.5 collateral ratio
+    let err = suite
+        .open_leveraged_position(
+            ACTOR,
+            juno.into_coin(100_000u128),
+            atom,
+            Decimal::percent(300),
+            Decimal::percent(20),
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::LeveragedPositionOverBorrowLimit { debt, .. } if debt == Uint128::new(400_000)
+    ));
+}
+
+#[test]
+fn invalid_params() {
+    let mut suite = leverage_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    let err = suite
+        .open_leveraged_position(
+            ACTOR,
+            juno.clone().into_coin(100_000u128),
+            atom.clone(),
+            Decimal::one(),
+            Decimal::percent(10),
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidLeverage {}, err.downcast().unwrap());
+
+    let err = suite
+        .close_leveraged_position(ACTOR, juno, atom.clone(), Decimal::percent(10))
+        .unwrap_err();
+    assert_eq!(ContractError::NoDebt(atom.denom()), err.downcast().unwrap());
+}
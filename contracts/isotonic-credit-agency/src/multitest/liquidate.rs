@@ -7,6 +7,8 @@ use isotonic_token::error::ContractError as TokenContractError;
 use cosmwasm_std::{Decimal, Uint128};
 
 use utils::credit_line::{CreditLineResponse, CreditLineValues};
+use utils::events::Liquidate;
+use utils::tests::event_attribute;
 use utils::token::Token;
 
 const YEAR_IN_SECONDS: u64 = 365 * 24 * 3600;
@@ -212,7 +214,8 @@ fn liquidating_whole_debt_native() {
         .make_response(suite.common_token().clone())
     );
 
-    suite
+    let market = suite.query_market(native_token.clone()).unwrap().market;
+    let response = suite
         .liquidate(
             LIQUIDATOR,
             DEBTOR,
@@ -220,6 +223,26 @@ fn liquidating_whole_debt_native() {
             native_token,
         )
         .unwrap();
+    assert_eq!(
+        event_attribute::<Liquidate>(&response.events, "account"),
+        Some(DEBTOR.to_owned())
+    );
+    assert_eq!(
+        event_attribute::<Liquidate>(&response.events, "debt_market"),
+        Some(market.to_string())
+    );
+    assert_eq!(
+        event_attribute::<Liquidate>(&response.events, "collateral_market"),
+        Some(market.to_string())
+    );
+    assert_eq!(
+        event_attribute::<Liquidate>(&response.events, "amount"),
+        Some(format!("474{}", JUNO))
+    );
+    assert_eq!(
+        event_attribute::<Liquidate>(&response.events, "liquidation_price"),
+        Some("0.92".to_owned())
+    );
 
     // Liquidation price is 0.92
     // Repaid value is 474 * 1.0 (oracle's price for same denom) * 0.92 = 515.22
@@ -431,17 +454,22 @@ fn receive_reward_in_different_denom_fails_if_theres_no_reward_market() {
         .repay_tokens_on_market(DEBTOR, native_token.clone().into_coin(2u128))
         .unwrap();
 
+    // There is no market for the reward token
+    let debt_market = suite.query_market(native_token.clone()).unwrap().market;
     let err = suite
-        .liquidate(
+        .liquidate_on_markets(
             LIQUIDATOR,
             DEBTOR,
             &[native_token.into_coin(474u128).try_into().unwrap()],
-            reward_token.clone(),
+            debt_market.as_str(),
+            &reward_token.denom(),
         )
         .unwrap_err();
 
     assert_eq!(
-        ContractError::NoMarket(reward_token.denom()),
+        ContractError::MarketSearchError {
+            market: reward_token.denom()
+        },
         err.downcast().unwrap()
     );
 }
@@ -771,6 +799,7 @@ fn receive_reward_in_different_denoms_with_six_months_interests() {
                 symbol: ("c".to_owned() + &native_token_2.denom()),
                 decimals: 9,
                 market_token: native_token_2.clone(),
+                market_id: 0,
                 market_cap: None,
                 interest_rate: utils::interest::Interest::Linear {
                     base: Decimal::percent(3),
@@ -792,6 +821,7 @@ fn receive_reward_in_different_denoms_with_six_months_interests() {
                 symbol: ("c".to_owned() + &native_token_1.denom()),
                 decimals: 9,
                 market_token: native_token_1.clone(),
+                market_id: 0,
                 market_cap: None,
                 interest_rate: utils::interest::Interest::Linear {
                     base: Decimal::percent(3),
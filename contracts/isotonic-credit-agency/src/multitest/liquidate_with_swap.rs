@@ -0,0 +1,166 @@
+use cosmwasm_std::Uint128;
+use utils::events::{LiquidateWithSwap, SettleLiquidation};
+use utils::tests::event_attribute;
+use utils::token::Token;
+
+use super::suite::{
+    Suite, SuiteBuilder, ACTOR, ATOM, COMMON, DEPOSIT, DEPOSIT_2, GOVERNANCE, JUNO, LIQUIDATOR,
+};
+use crate::error::ContractError;
+
+const YEAR_IN_SECONDS: u64 = 365 * 24 * 3600;
+
+/// `ACTOR` has 1M JUNO (price 2.0) of collateral, and borrows ATOM (price 0.5) up to the credit
+/// line. Interest makes the account liquidatable after a year.
+fn liquidatable_suite() -> Suite {
+    let common_token = Token::Native(COMMON.to_owned());
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_funds(DEPOSIT, &[juno.clone().into_coin(10_000_000u128)])
+        .with_funds(DEPOSIT_2, &[atom.clone().into_coin(10_000_000u128)])
+        .with_funds(ACTOR, &[juno.clone().into_coin(1_000_000u128)])
+        .with_pool(
+            1,
+            (
+                common_token.clone().into_coin(20_000_000u128),
+                juno.clone().into_coin(10_000_000u128),
+            ),
+        )
+        .with_pool(
+            2,
+            (
+                common_token.into_coin(5_000_000u128),
+                atom.clone().into_coin(10_000_000u128),
+            ),
+        )
+        .build();
+
+    for token in [&juno, &atom] {
+        suite
+            .create_market_quick(GOVERNANCE, &token.denom(), token.clone(), None, None, None)
+            .unwrap();
+    }
+
+    suite
+        .deposit_tokens_on_market(DEPOSIT, juno.clone().into_coin(10_000_000u128))
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(DEPOSIT_2, atom.clone().into_coin(10_000_000u128))
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(ACTOR, juno.into_coin(1_000_000u128))
+        .unwrap();
+    // 1M common of debt, same as credit line
+    suite
+        .borrow_tokens_from_market(ACTOR, atom.into_coin(2_000_000u128))
+        .unwrap();
+
+    suite.advance_seconds(YEAR_IN_SECONDS);
+    suite
+}
+
+fn atom_debt(suite: &Suite) -> Uint128 {
+    let atom = Token::Native(ATOM.to_owned());
+    suite
+        .query_account_health(ACTOR)
+        .unwrap()
+        .markets
+        .into_iter()
+        .find(|m| m.market_token == atom)
+        .unwrap()
+        .debt
+        .amount
+}
+
+#[test]
+fn liquidator_without_debt_tokens() {
+    let mut suite = liquidatable_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+    let ctoken = suite
+        .query_market_config(juno.clone())
+        .unwrap()
+        .ctoken_contract
+        .to_string();
+
+    let quote = suite
+        .query_liquidation_quote(ACTOR, atom.clone().into_coin(200_000u128), juno.clone())
+        .unwrap();
+    assert!(quote.success);
+    let debt_before = atom_debt(&suite);
+
+    let response = suite
+        .liquidate_with_swap(LIQUIDATOR, ACTOR, atom.into_coin(200_000u128), juno, 1_000)
+        .unwrap();
+
+    assert_eq!(debt_before - atom_debt(&suite), Uint128::new(200_000));
+    assert_eq!(
+        event_attribute::<LiquidateWithSwap>(&response.events, "account"),
+        Some(ACTOR.to_owned())
+    );
+    assert_eq!(
+        event_attribute::<LiquidateWithSwap>(&response.events, "amount"),
+        Some(format!("200000{}", ATOM))
+    );
+    assert_eq!(
+        event_attribute::<LiquidateWithSwap>(&response.events, "min_profit"),
+        Some("1000".to_owned())
+    );
+    assert_eq!(
+        event_attribute::<SettleLiquidation>(&response.events, "recipient"),
+        Some(LIQUIDATOR.to_owned())
+    );
+
+    // Liquidator gets seized collateral not sold to repay the debt, in JUNO - 54_347 JUNO is
+    // seized for 100k common of debt, and buying 200k ATOM takes a bit over 50k JUNO with fees
+    let profit = suite.query_native_balance(LIQUIDATOR, JUNO).unwrap();
+    assert!(profit > 1_000 && profit < 5_000);
+    assert!(profit < quote.seized.amount.u128());
+    assert_eq!(
+        event_attribute::<SettleLiquidation>(&response.events, "amount"),
+        Some(format!("{}{}", profit, JUNO))
+    );
+    assert_eq!(
+        suite
+            .query_cw20_balance(LIQUIDATOR, ctoken.clone())
+            .unwrap(),
+        0
+    );
+    // Nothing but rounding dust of cTokens is left on the credit agency
+    let ca = suite.credit_agency();
+    assert_eq!(suite.query_native_balance(ca.as_str(), JUNO).unwrap(), 0);
+    assert!(suite.query_cw20_balance(ca.as_str(), ctoken).unwrap() <= 1);
+}
+
+#[test]
+fn profit_lower_than_required() {
+    let mut suite = liquidatable_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    let err = suite
+        .liquidate_with_swap(LIQUIDATOR, ACTOR, atom.into_coin(200_000u128), juno, 10_000)
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::LiquidationProfitTooLow { min_profit, .. } if min_profit == Uint128::new(10_000)
+    ));
+}
+
+#[test]
+fn healthy_account() {
+    let mut suite = liquidatable_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    let err = suite
+        .liquidate_with_swap(LIQUIDATOR, DEPOSIT, atom.into_coin(200_000u128), juno, 0)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::LiquidationNotAllowed {},
+        err.downcast().unwrap()
+    );
+}
@@ -0,0 +1,359 @@
+use cosmwasm_std::Decimal;
+use utils::events::LiquidationAuction as LiquidationAuctionEvent;
+use utils::tests::find_events;
+use utils::token::Token;
+
+use super::suite::{Suite, SuiteBuilder, COMMON, DEBTOR, GOVERNANCE, JUNO, LIQUIDATOR};
+use crate::error::ContractError;
+use crate::state::LiquidationAuction;
+
+const YEAR_IN_SECONDS: u64 = 365 * 24 * 3600;
+
+fn auction() -> LiquidationAuction {
+    LiquidationAuction {
+        min_discount: Decimal::percent(2),
+        max_discount: Decimal::percent(15),
+        duration: 1000,
+    }
+}
+
+/// Same situation as in `liquidation_quote::liquidatable_suite` - `DEBTOR` has 576 JUNO of
+/// collateral, 474 JUNO of debt, and 460 of credit line - but with the liquidation auction
+fn liquidatable_suite() -> Suite {
+    let common_token = Token::Native(COMMON.to_owned());
+    let native_token = Token::Native(JUNO.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_funds(LIQUIDATOR, &[native_token.clone().into_coin(5_000u128)])
+        .with_funds(DEBTOR, &[native_token.clone().into_coin(600u128)])
+        .with_liquidation_auction(auction())
+        .with_pool(
+            1,
+            (
+                common_token.into_coin(100u128),
+                native_token.clone().into_coin(100u128),
+            ),
+        )
+        .build();
+
+    suite
+        .create_market_quick(
+            GOVERNANCE,
+            &native_token.denom(),
+            native_token.clone(),
+            Decimal::percent(80),
+            None,
+            None,
+        )
+        .unwrap();
+
+    suite
+        .deposit_tokens_on_market(DEBTOR, native_token.clone().into_coin(500u128))
+        .unwrap();
+    suite
+        .borrow_tokens_from_market(DEBTOR, native_token.clone().into_coin(400u128))
+        .unwrap();
+
+    suite.advance_seconds(YEAR_IN_SECONDS);
+
+    // Repay some tokens to trigger interest rate charges
+    suite
+        .repay_tokens_on_market(DEBTOR, native_token.into_coin(2u128))
+        .unwrap();
+
+    suite
+}
+
+#[test]
+fn discount_grows_over_time() {
+    let mut suite = liquidatable_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let ctoken = suite
+        .query_market_config(juno.clone())
+        .unwrap()
+        .ctoken_contract
+        .to_string();
+
+    // Not started yet - the minimal discount applies
+    let auction = suite.query_liquidation_auction(DEBTOR).unwrap();
+    assert_eq!(auction.start, None);
+    assert_eq!(auction.liquidation_price, Decimal::percent(98));
+
+    suite
+        .update_liquidation_auction(LIQUIDATOR, DEBTOR)
+        .unwrap();
+    let start = suite.query_liquidation_auction(DEBTOR).unwrap().start;
+    assert!(start.is_some());
+
+    // 100 / 0.98 = 102.04
+    let quote = suite
+        .query_liquidation_quote(DEBTOR, juno.clone().into_coin(100u128), juno.clone())
+        .unwrap();
+    assert_eq!(quote.seized, juno.clone().into_coin(102u128));
+
+    // Half of the auction, discount 2% + 13% / 2 = 8.5%, 100 / 0.915 = 109.29
+    suite.advance_seconds(500);
+    let auction = suite.query_liquidation_auction(DEBTOR).unwrap();
+    assert_eq!(auction.start, start);
+    assert_eq!(auction.liquidation_price, Decimal::permille(915));
+    let quote = suite
+        .query_liquidation_quote(DEBTOR, juno.clone().into_coin(100u128), juno.clone())
+        .unwrap();
+    assert_eq!(quote.seized, juno.clone().into_coin(109u128));
+
+    // Discount stops growing at the end of the auction, 100 / 0.85 = 117.65
+    suite.advance_seconds(1000);
+    let auction = suite.query_liquidation_auction(DEBTOR).unwrap();
+    assert_eq!(auction.liquidation_price, Decimal::percent(85));
+    let quote = suite
+        .query_liquidation_quote(DEBTOR, juno.clone().into_coin(100u128), juno.clone())
+        .unwrap();
+    assert_eq!(quote.seized, juno.clone().into_coin(117u128));
+
+    // Updating running auction doesn't restart it
+    suite
+        .update_liquidation_auction(LIQUIDATOR, DEBTOR)
+        .unwrap();
+    assert_eq!(
+        suite.query_liquidation_auction(DEBTOR).unwrap().start,
+        start
+    );
+
+    suite
+        .liquidate(
+            LIQUIDATOR,
+            DEBTOR,
+            &[juno.clone().into_coin(100u128).try_into().unwrap()],
+            juno,
+        )
+        .unwrap();
+    assert_eq!(
+        suite.query_cw20_balance(LIQUIDATOR, ctoken).unwrap(),
+        quote.seized_ctokens.u128()
+    );
+}
+
+#[test]
+fn liquidation_starts_auction() {
+    let mut suite = liquidatable_suite();
+    let juno = Token::Native(JUNO.to_owned());
+
+    suite
+        .liquidate(
+            LIQUIDATOR,
+            DEBTOR,
+            &[juno.clone().into_coin(10u128).try_into().unwrap()],
+            juno,
+        )
+        .unwrap();
+
+    let auction = suite.query_liquidation_auction(DEBTOR).unwrap();
+    assert!(auction.start.is_some());
+    assert_eq!(auction.liquidation_price, Decimal::percent(98));
+}
+
+#[test]
+fn liquidation_restarts_auction() {
+    let mut suite = liquidatable_suite();
+    let juno = Token::Native(JUNO.to_owned());
+
+    suite
+        .update_liquidation_auction(LIQUIDATOR, DEBTOR)
+        .unwrap();
+    let start = suite.query_liquidation_auction(DEBTOR).unwrap().start;
+    suite.advance_seconds(1500);
+    assert_eq!(
+        suite
+            .query_liquidation_auction(DEBTOR)
+            .unwrap()
+            .liquidation_price,
+        Decimal::percent(85)
+    );
+
+    // Liquidation gets the grown discount, the rest of the debt is auctioned from the start
+    suite
+        .liquidate(
+            LIQUIDATOR,
+            DEBTOR,
+            &[juno.clone().into_coin(10u128).try_into().unwrap()],
+            juno,
+        )
+        .unwrap();
+    let auction = suite.query_liquidation_auction(DEBTOR).unwrap();
+    assert!(auction.start.is_some());
+    assert_ne!(auction.start, start);
+    assert_eq!(auction.liquidation_price, Decimal::percent(98));
+}
+
+#[test]
+fn update_with_auction_disabled() {
+    let mut suite = liquidatable_suite();
+    suite.adjust_liquidation_auction(GOVERNANCE, None).unwrap();
+
+    let response = suite
+        .update_liquidation_auction(LIQUIDATOR, DEBTOR)
+        .unwrap();
+    assert!(find_events::<LiquidationAuctionEvent>(&response.events).is_empty());
+    assert_eq!(suite.query_liquidation_auction(DEBTOR).unwrap().start, None);
+}
+
+#[test]
+fn auction_resets_when_healthy() {
+    let mut suite = liquidatable_suite();
+    let juno = Token::Native(JUNO.to_owned());
+
+    suite
+        .update_liquidation_auction(LIQUIDATOR, DEBTOR)
+        .unwrap();
+    suite.advance_seconds(500);
+
+    // Deposit makes the account healthy - credit line 0.8 * 676 = 540 over 474 of debt
+    suite
+        .deposit_tokens_on_market(DEBTOR, juno.clone().into_coin(100u128))
+        .unwrap();
+    let auction = suite.query_liquidation_auction(DEBTOR).unwrap();
+    assert_eq!(auction.start, None);
+    assert_eq!(auction.liquidation_price, Decimal::percent(98));
+
+    // Healthy account has no auction to start
+    suite
+        .update_liquidation_auction(LIQUIDATOR, DEBTOR)
+        .unwrap();
+    assert_eq!(suite.query_liquidation_auction(DEBTOR).unwrap().start, None);
+
+    let err = suite
+        .liquidate(
+            LIQUIDATOR,
+            DEBTOR,
+            &[juno.clone().into_coin(10u128).try_into().unwrap()],
+            juno,
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::LiquidationNotAllowed {},
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn auction_resets_after_repay() {
+    let mut suite = liquidatable_suite();
+    let juno = Token::Native(JUNO.to_owned());
+
+    suite
+        .update_liquidation_auction(LIQUIDATOR, DEBTOR)
+        .unwrap();
+    suite.advance_seconds(500);
+
+    // Repay makes the account healthy - 454 of debt under 460 of credit line
+    suite
+        .repay_tokens_on_market(DEBTOR, juno.clone().into_coin(20u128))
+        .unwrap();
+    assert_eq!(suite.query_liquidation_auction(DEBTOR).unwrap().start, None);
+
+    // Interest makes the account liquidatable again, long after the old auction would end
+    suite.advance_seconds(2 * YEAR_IN_SECONDS);
+    suite
+        .repay_tokens_on_market(DEBTOR, juno.clone().into_coin(1u128))
+        .unwrap();
+    suite
+        .update_liquidation_auction(LIQUIDATOR, DEBTOR)
+        .unwrap();
+    let auction = suite.query_liquidation_auction(DEBTOR).unwrap();
+    assert!(auction.start.is_some());
+    assert_eq!(auction.liquidation_price, Decimal::percent(98));
+
+    // 100 / 0.98 = 102.04
+    let quote = suite
+        .query_liquidation_quote(DEBTOR, juno.clone().into_coin(100u128), juno)
+        .unwrap();
+    assert_eq!(quote.seized.amount.u128(), 102);
+}
+
+#[test]
+fn partial_liquidation_restoring_health_resets_auction() {
+    let mut suite = liquidatable_suite();
+    let juno = Token::Native(JUNO.to_owned());
+
+    suite
+        .update_liquidation_auction(LIQUIDATOR, DEBTOR)
+        .unwrap();
+    suite.advance_seconds(500);
+
+    // 150 of debt repaid for 150 / 0.915 = 164 of collateral - 324 of debt under
+    // 0.8 * 412 = 329 of credit line
+    suite
+        .liquidate(
+            LIQUIDATOR,
+            DEBTOR,
+            &[juno.clone().into_coin(150u128).try_into().unwrap()],
+            juno,
+        )
+        .unwrap();
+    assert_eq!(suite.query_liquidation_auction(DEBTOR).unwrap().start, None);
+}
+
+#[test]
+fn adjust_auction() {
+    let mut suite = liquidatable_suite();
+
+    let err = suite
+        .adjust_liquidation_auction(LIQUIDATOR, None)
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    let err = suite
+        .adjust_liquidation_auction(
+            GOVERNANCE,
+            Some(LiquidationAuction {
+                duration: 0,
+                ..auction()
+            }),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidLiquidationAuction {},
+        err.downcast().unwrap()
+    );
+
+    let err = suite
+        .adjust_liquidation_auction(
+            GOVERNANCE,
+            Some(LiquidationAuction {
+                min_discount: Decimal::percent(20),
+                ..auction()
+            }),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidLiquidationAuction {},
+        err.downcast().unwrap()
+    );
+
+    // Liquidation price of 0.75 is lower than the 0.8 collateral ratio of the market
+    let err = suite
+        .adjust_liquidation_auction(
+            GOVERNANCE,
+            Some(LiquidationAuction {
+                max_discount: Decimal::percent(25),
+                ..auction()
+            }),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MarketCfgCollateralFailure {},
+        err.downcast().unwrap()
+    );
+
+    // Disabling the auction brings back the fixed liquidation price
+    suite.adjust_liquidation_auction(GOVERNANCE, None).unwrap();
+    assert_eq!(suite.query_config().unwrap().liquidation_auction, None);
+    assert_eq!(
+        suite
+            .query_liquidation_auction(DEBTOR)
+            .unwrap()
+            .liquidation_price,
+        Decimal::percent(92)
+    );
+}
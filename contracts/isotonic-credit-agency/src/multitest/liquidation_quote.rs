@@ -0,0 +1,127 @@
+use cosmwasm_std::{Decimal, Uint128};
+use utils::token::Token;
+
+use super::suite::{Suite, SuiteBuilder, COMMON, DEBTOR, GOVERNANCE, JUNO, LIQUIDATOR, OSMO};
+
+const YEAR_IN_SECONDS: u64 = 365 * 24 * 3600;
+
+/// Same situation as in `liquidate::liquidating_whole_debt_native` - `DEBTOR` has 576 JUNO of
+/// collateral, 474 JUNO of debt, and 460 of credit line
+fn liquidatable_suite() -> Suite {
+    let common_token = Token::Native(COMMON.to_owned());
+    let native_token = Token::Native(JUNO.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_funds(LIQUIDATOR, &[native_token.clone().into_coin(5_000u128)])
+        .with_funds(DEBTOR, &[native_token.clone().into_coin(600u128)])
+        .with_liquidation_price(Decimal::percent(92))
+        .with_pool(
+            1,
+            (
+                common_token.into_coin(100u128),
+                native_token.clone().into_coin(100u128),
+            ),
+        )
+        .build();
+
+    suite
+        .create_market_quick(
+            GOVERNANCE,
+            &native_token.denom(),
+            native_token.clone(),
+            Decimal::percent(80),
+            None,
+            None,
+        )
+        .unwrap();
+
+    suite
+        .deposit_tokens_on_market(DEBTOR, native_token.clone().into_coin(500u128))
+        .unwrap();
+    suite
+        .borrow_tokens_from_market(DEBTOR, native_token.clone().into_coin(400u128))
+        .unwrap();
+
+    suite.advance_seconds(YEAR_IN_SECONDS);
+
+    // Repay some tokens to trigger interest rate charges
+    suite
+        .repay_tokens_on_market(DEBTOR, native_token.into_coin(2u128))
+        .unwrap();
+
+    suite
+}
+
+#[test]
+fn quote_matches_liquidation() {
+    let mut suite = liquidatable_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let ctoken = suite
+        .query_market_config(juno.clone())
+        .unwrap()
+        .ctoken_contract
+        .to_string();
+
+    let quote = suite
+        .query_liquidation_quote(DEBTOR, juno.clone().into_coin(474u128), juno.clone())
+        .unwrap();
+    assert!(quote.can_liquidate);
+    assert!(quote.success);
+    assert_eq!(quote.repay_value, suite.common_token().amount(474u128));
+    // 474 / 0.92 = 515.22
+    assert_eq!(quote.seized, juno.clone().into_coin(515u128));
+    assert_eq!(quote.max_repay, juno.clone().into_coin(474u128));
+
+    let debtor_ctokens = suite.query_cw20_balance(DEBTOR, ctoken.clone()).unwrap();
+    suite
+        .liquidate(
+            LIQUIDATOR,
+            DEBTOR,
+            &[juno.clone().into_coin(474u128).try_into().unwrap()],
+            juno,
+        )
+        .unwrap();
+
+    assert_eq!(
+        suite
+            .query_cw20_balance(LIQUIDATOR, ctoken.clone())
+            .unwrap(),
+        quote.seized_ctokens.u128()
+    );
+    assert_eq!(
+        suite.query_cw20_balance(DEBTOR, ctoken).unwrap(),
+        debtor_ctokens - quote.seized_ctokens.u128()
+    );
+}
+
+#[test]
+fn quote_failing_liquidation() {
+    let suite = liquidatable_suite();
+    let juno = Token::Native(JUNO.to_owned());
+
+    // More than debt
+    let quote = suite
+        .query_liquidation_quote(DEBTOR, juno.clone().into_coin(475u128), juno.clone())
+        .unwrap();
+    assert!(quote.can_liquidate);
+    assert!(!quote.success);
+    assert_eq!(quote.max_repay, juno.clone().into_coin(474u128));
+
+    // Healthy account
+    let quote = suite
+        .query_liquidation_quote(LIQUIDATOR, juno.clone().into_coin(10u128), juno.clone())
+        .unwrap();
+    assert!(!quote.can_liquidate);
+    assert!(!quote.success);
+    assert_eq!(quote.max_repay.amount, Uint128::zero());
+
+    // No market for collateral
+    suite
+        .query_liquidation_quote(
+            DEBTOR,
+            juno.into_coin(10u128),
+            Token::Native(OSMO.to_owned()),
+        )
+        .unwrap_err();
+}
@@ -0,0 +1,138 @@
+use cosmwasm_std::{Addr, Decimal};
+use utils::token::Token;
+
+use super::suite::{Suite, SuiteBuilder, ACTOR, COMMON, DEBTOR, GOVERNANCE, JUNO, LENDER, OSMO};
+
+const YEAR_IN_SECONDS: u64 = 365 * 24 * 3600;
+
+/// `DEBTOR` is the only one on JUNO market and borrows all it can, `ACTOR` and `LENDER` only
+/// deposit on OSMO market
+fn suite_with_accounts() -> Suite {
+    let common_token = Token::Native(COMMON.to_owned());
+    let juno = Token::Native(JUNO.to_owned());
+    let osmo = Token::Native(OSMO.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_funds(DEBTOR, &[juno.clone().into_coin(500u128)])
+        .with_funds(ACTOR, &[osmo.clone().into_coin(100u128)])
+        .with_funds(LENDER, &[osmo.clone().into_coin(100u128)])
+        .with_pool(
+            1,
+            (
+                common_token.clone().into_coin(100u128),
+                juno.clone().into_coin(100u128),
+            ),
+        )
+        .with_pool(
+            2,
+            (
+                common_token.into_coin(100u128),
+                osmo.clone().into_coin(100u128),
+            ),
+        )
+        .build();
+
+    for token in [&juno, &osmo] {
+        suite
+            .create_market_quick(
+                GOVERNANCE,
+                &token.denom(),
+                token.clone(),
+                Decimal::percent(80),
+                None,
+                None,
+            )
+            .unwrap();
+    }
+
+    suite
+        .deposit_tokens_on_market(DEBTOR, juno.clone().into_coin(500u128))
+        .unwrap();
+    suite
+        .borrow_tokens_from_market(DEBTOR, juno.into_coin(400u128))
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(ACTOR, osmo.clone().into_coin(100u128))
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(LENDER, osmo.into_coin(100u128))
+        .unwrap();
+
+    suite
+}
+
+#[test]
+fn list_accounts() {
+    let suite = suite_with_accounts();
+
+    let accounts = suite.list_accounts(None, None).unwrap().accounts;
+    assert_eq!(
+        accounts,
+        [ACTOR, DEBTOR, LENDER].map(Addr::unchecked).to_vec()
+    );
+
+    let accounts = suite.list_accounts(ACTOR.to_owned(), 1).unwrap().accounts;
+    assert_eq!(accounts, vec![Addr::unchecked(DEBTOR)]);
+
+    let accounts = suite
+        .list_accounts(LENDER.to_owned(), None)
+        .unwrap()
+        .accounts;
+    assert!(accounts.is_empty());
+}
+
+#[test]
+fn account_exiting_all_markets_is_not_listed() {
+    let mut suite = suite_with_accounts();
+    let osmo_market = suite
+        .query_market(Token::Native(OSMO.to_owned()))
+        .unwrap()
+        .market;
+
+    suite.exit_market(ACTOR, osmo_market.as_str()).unwrap();
+
+    let accounts = suite.list_accounts(None, None).unwrap().accounts;
+    assert_eq!(accounts, [DEBTOR, LENDER].map(Addr::unchecked).to_vec());
+    let accounts = suite.list_accounts(None, 1).unwrap().accounts;
+    assert_eq!(accounts, vec![Addr::unchecked(DEBTOR)]);
+}
+
+#[test]
+fn list_liquidatable() {
+    let mut suite = suite_with_accounts();
+
+    let resp = suite.list_liquidatable(None, None).unwrap();
+    assert!(resp.accounts.is_empty());
+    assert_eq!(resp.last_checked, None);
+
+    // Interest makes debt of `DEBTOR` higher than its credit line, see `liquidate` tests
+    suite.advance_seconds(YEAR_IN_SECONDS);
+
+    let resp = suite.list_liquidatable(None, None).unwrap();
+    assert_eq!(resp.last_checked, None);
+    assert_eq!(resp.accounts.len(), 1);
+    assert_eq!(resp.accounts[0].account, DEBTOR);
+    let credit_line = &resp.accounts[0].credit_line;
+    assert!(credit_line.debt > credit_line.credit_line);
+    assert_eq!(
+        credit_line.debt,
+        suite.query_total_credit_line(DEBTOR).unwrap().debt
+    );
+
+    // Paging through accounts one by one
+    let mut start_after = None;
+    let mut liquidatable = vec![];
+    let mut pages = 0;
+    loop {
+        let resp = suite.list_liquidatable(start_after, 1).unwrap();
+        liquidatable.extend(resp.accounts.into_iter().map(|a| a.account));
+        pages += 1;
+        match resp.last_checked {
+            Some(last) => start_after = Some(last.to_string()),
+            None => break,
+        }
+    }
+    assert_eq!(liquidatable, vec![Addr::unchecked(DEBTOR)]);
+    assert_eq!(pages, 4);
+}
@@ -201,3 +201,33 @@ fn collateral_ratio_higher_then_liquidation_price() {
         err.downcast().unwrap()
     );
 }
+
+#[test]
+fn market_created_at_predicted_address() {
+    let market_token = Token::Native(JUNO.to_owned());
+    let mut suite = SuiteBuilder::new().with_gov(GOVERNANCE).build();
+
+    let predicted = suite
+        .query_predict_market_address(market_token.clone(), None)
+        .unwrap();
+    // Markets of the same token with different ids get different addresses
+    let predicted_tier = suite
+        .query_predict_market_address(market_token.clone(), 1)
+        .unwrap();
+    assert_ne!(predicted.market, predicted_tier.market);
+
+    suite
+        .create_market_quick(GOVERNANCE, JUNO, market_token.clone(), None, None, None)
+        .unwrap();
+    assert_eq!(
+        suite.query_market(market_token.clone()).unwrap().market,
+        predicted.market
+    );
+    assert_eq!(
+        suite
+            .query_market_config(market_token)
+            .unwrap()
+            .ctoken_contract,
+        predicted.ctoken
+    );
+}
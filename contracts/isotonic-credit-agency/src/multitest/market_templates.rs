@@ -0,0 +1,275 @@
+use cosmwasm_std::{coins, Addr, Decimal, Uint128};
+use cw_multi_test::Executor;
+use isotonic_market::msg::ExecuteMsg as MarketExecuteMsg;
+use utils::interest::Interest;
+use utils::token::Token;
+
+use super::suite::{Suite, SuiteBuilder, ACTOR, COMMON, GOVERNANCE, JUNO, LENDER};
+use crate::error::ContractError;
+use crate::msg::{ParamChange, TemplateMarketConfig};
+use crate::state::MarketTemplate;
+
+const TEMPLATE: &str = "volatile";
+const PAIR: &str = "pair";
+/// Default market id is reserved for governance
+const MARKET_ID: u32 = 1;
+
+fn template(suite: &Suite) -> MarketTemplate {
+    MarketTemplate {
+        interest_rate: Interest::Linear {
+            base: Decimal::percent(5),
+            slope: Decimal::percent(40),
+        },
+        interest_charge_period: 3600,
+        min_collateral_ratio: Decimal::percent(30),
+        max_collateral_ratio: Decimal::percent(50),
+        reserve_factor: Decimal::percent(10),
+        market_cap: Uint128::new(1_000_000),
+        isolated_credit_cap: Uint128::new(100),
+        price_oracle: suite.oracle_contract.clone(),
+    }
+}
+
+fn market_cfg(market_token: Token, collateral_ratio: Decimal) -> TemplateMarketConfig {
+    TemplateMarketConfig {
+        template: TEMPLATE.to_owned(),
+        name: market_token.denom(),
+        symbol: market_token.denom(),
+        decimals: 9,
+        market_token,
+        market_id: MARKET_ID,
+        collateral_ratio,
+        pair_contract: PAIR.to_owned(),
+    }
+}
+
+/// Suite with the template set by the governance
+fn template_suite() -> Suite {
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_funds(
+            LENDER,
+            &[Token::Native(COMMON.to_owned()).into_coin(1000u128)],
+        )
+        .build();
+    let template = template(&suite);
+    suite
+        .propose_param_change(
+            GOVERNANCE,
+            ParamChange::MarketTemplate {
+                name: TEMPLATE.to_owned(),
+                template: Some(template),
+            },
+        )
+        .unwrap();
+    suite
+}
+
+#[test]
+fn anyone_creates_market_from_template() {
+    let juno = Token::Native(JUNO.to_owned());
+    let mut suite = template_suite();
+
+    let templates = suite.query_market_templates().unwrap().templates;
+    assert_eq!(templates.len(), 1);
+    assert_eq!(templates[0].name, TEMPLATE);
+    assert_eq!(templates[0].template, template(&suite));
+
+    // The pool has to be registered in the oracle first
+    let err = suite
+        .create_market_from_template(ACTOR, market_cfg(juno.clone(), Decimal::percent(40)))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::OraclePoolNotRegistered {
+            pair: PAIR.to_owned(),
+            market_token: JUNO.to_owned()
+        },
+        err.downcast().unwrap()
+    );
+    suite
+        .register_oracle_pool(PAIR, juno.clone(), Token::Native(COMMON.to_owned()))
+        .unwrap();
+
+    let err = suite
+        .create_market_from_template(ACTOR, market_cfg(juno.clone(), Decimal::percent(60)))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::CollateralRatioOutOfTemplate(Decimal::percent(60)),
+        err.downcast().unwrap()
+    );
+    let err = suite
+        .create_market_from_template(
+            ACTOR,
+            TemplateMarketConfig {
+                template: "stable".to_owned(),
+                ..market_cfg(juno.clone(), Decimal::percent(40))
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NoMarketTemplate("stable".to_owned()),
+        err.downcast().unwrap()
+    );
+
+    suite
+        .create_market_from_template(ACTOR, market_cfg(juno.clone(), Decimal::percent(40)))
+        .unwrap();
+    let config = suite
+        .query_market_config_with_id(juno.clone(), MARKET_ID)
+        .unwrap();
+    assert_eq!(config.collateral_ratio, Decimal::percent(40));
+    assert_eq!(config.market_cap, Some(Uint128::new(1_000_000)));
+    assert_eq!(config.reserve_factor, Decimal::percent(10));
+
+    let market = suite.query_market_with_id(juno, MARKET_ID).unwrap().market;
+    assert_eq!(
+        suite.query_isolated_market(&market).unwrap(),
+        Some(Uint128::new(100))
+    );
+}
+
+#[test]
+fn isolated_market_caps_credit_line() {
+    let common = Token::Native(COMMON.to_owned());
+    let mut suite = template_suite();
+    // Common token market is priced 1:1, without querying the pool
+    suite
+        .register_oracle_pool(PAIR, common.clone(), common.clone())
+        .unwrap();
+    suite
+        .create_market_from_template(ACTOR, market_cfg(common.clone(), Decimal::percent(50)))
+        .unwrap();
+    let market = suite
+        .query_market_with_id(common, MARKET_ID)
+        .unwrap()
+        .market;
+    suite
+        .app()
+        .execute_contract(
+            Addr::unchecked(LENDER),
+            market.clone(),
+            &MarketExecuteMsg::Deposit {},
+            &coins(1000, COMMON),
+        )
+        .unwrap();
+
+    let credit_line = suite.query_total_credit_line(LENDER).unwrap();
+    assert_eq!(credit_line.collateral.amount, Uint128::new(1000));
+    assert_eq!(credit_line.credit_line.amount, Uint128::new(100));
+    assert_eq!(credit_line.borrow_limit.amount, Uint128::new(100));
+
+    suite
+        .propose_param_change(
+            GOVERNANCE,
+            ParamChange::PromoteMarket {
+                market: market.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(suite.query_isolated_market(&market).unwrap(), None);
+    let credit_line = suite.query_total_credit_line(LENDER).unwrap();
+    assert_eq!(credit_line.credit_line.amount, Uint128::new(500));
+
+    let err = suite
+        .propose_param_change(
+            GOVERNANCE,
+            ParamChange::PromoteMarket {
+                market: market.to_string(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MarketNotIsolated(market.to_string()),
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn governance_markets_are_reserved() {
+    let juno = Token::Native(JUNO.to_owned());
+    let mut suite = template_suite();
+    suite
+        .register_oracle_pool(PAIR, juno.clone(), Token::Native(COMMON.to_owned()))
+        .unwrap();
+
+    let err = suite
+        .create_market_from_template(
+            ACTOR,
+            TemplateMarketConfig {
+                market_id: 0,
+                ..market_cfg(juno.clone(), Decimal::percent(40))
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::TemplateMarketDefaultId {},
+        err.downcast().unwrap()
+    );
+
+    // Another template market of the same asset is fine
+    suite
+        .create_market_from_template(ACTOR, market_cfg(juno.clone(), Decimal::percent(40)))
+        .unwrap();
+    suite
+        .create_market_from_template(
+            ACTOR,
+            TemplateMarketConfig {
+                market_id: 2,
+                ..market_cfg(juno.clone(), Decimal::percent(30))
+            },
+        )
+        .unwrap();
+
+    // Governance still creates the default market, after which the asset is closed for templates
+    suite
+        .create_market_quick(GOVERNANCE, "juno", juno.clone(), None, None, None)
+        .unwrap();
+    let err = suite
+        .create_market_from_template(
+            ACTOR,
+            TemplateMarketConfig {
+                market_id: 3,
+                ..market_cfg(juno, Decimal::percent(40))
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::GovernanceMarketExists(JUNO.to_owned()),
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn invalid_template() {
+    let mut suite = SuiteBuilder::new().with_gov(GOVERNANCE).build();
+
+    // Liquidation price is 0.92
+    let err = suite
+        .propose_param_change(
+            GOVERNANCE,
+            ParamChange::MarketTemplate {
+                name: TEMPLATE.to_owned(),
+                template: Some(MarketTemplate {
+                    max_collateral_ratio: Decimal::percent(95),
+                    ..template(&suite)
+                }),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidMarketTemplate {},
+        err.downcast().unwrap()
+    );
+
+    let err = suite
+        .propose_param_change(
+            ACTOR,
+            ParamChange::MarketTemplate {
+                name: TEMPLATE.to_owned(),
+                template: Some(template(&suite)),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    assert_eq!(suite.query_market_templates().unwrap().templates, vec![]);
+}
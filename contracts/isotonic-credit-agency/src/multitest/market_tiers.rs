@@ -0,0 +1,187 @@
+use cosmwasm_std::{coins, Addr, Decimal, Uint128};
+use cw_multi_test::Executor;
+use isotonic_market::msg::ExecuteMsg as MarketExecuteMsg;
+use utils::token::Token;
+
+use super::suite::{SuiteBuilder, COMMON, DEBTOR, GOVERNANCE, JUNO, LIQUIDATOR, OSMO};
+use crate::error::ContractError;
+
+const YEAR_IN_SECONDS: u64 = 365 * 24 * 3600;
+
+#[test]
+fn several_markets_per_asset() {
+    let juno = Token::Native(JUNO.to_owned());
+    let mut suite = SuiteBuilder::new().with_gov(GOVERNANCE).build();
+
+    suite
+        .create_market_quick(GOVERNANCE, "juno", juno.clone(), None, None, None)
+        .unwrap();
+    suite
+        .create_market_with_id(
+            GOVERNANCE,
+            "junohy",
+            juno.clone(),
+            1,
+            Decimal::percent(30),
+            (Decimal::percent(10), Decimal::percent(50)),
+            None,
+        )
+        .unwrap();
+
+    let default = suite.query_market(juno.clone()).unwrap();
+    assert_eq!(default.market_id, 0);
+    let high_yield = suite.query_market_with_id(juno.clone(), 1).unwrap();
+    assert_eq!(high_yield.market_id, 1);
+    assert_ne!(default.market, high_yield.market);
+    assert_eq!(
+        suite
+            .query_market_config(juno.clone())
+            .unwrap()
+            .collateral_ratio,
+        Decimal::percent(50)
+    );
+
+    let markets = suite.list_markets().unwrap().markets;
+    assert_eq!(markets, vec![default, high_yield.clone()]);
+    let markets = suite
+        .list_markets_with_pagination((juno.clone(), 0), None)
+        .unwrap()
+        .markets;
+    assert_eq!(markets, vec![high_yield]);
+
+    let err = suite
+        .create_market_with_id(GOVERNANCE, "junohy", juno.clone(), 1, None, None, None)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MarketAlreadyExists(JUNO.to_owned()),
+        err.downcast().unwrap()
+    );
+    let err = suite.query_market_with_id(juno, 2).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains(&ContractError::NoMarket(JUNO.to_owned()).to_string()));
+}
+
+#[test]
+fn liquidate_on_non_default_market() {
+    let common_token = Token::Native(COMMON.to_owned());
+    let juno = Token::Native(JUNO.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_funds(LIQUIDATOR, &[juno.clone().into_coin(5_000u128)])
+        .with_funds(DEBTOR, &[juno.clone().into_coin(600u128)])
+        .with_liquidation_price(Decimal::percent(92))
+        .with_pool(
+            1,
+            (
+                common_token.into_coin(100u128),
+                juno.clone().into_coin(100u128),
+            ),
+        )
+        .build();
+    suite
+        .create_market_quick(GOVERNANCE, "juno", juno.clone(), None, None, None)
+        .unwrap();
+    suite
+        .create_market_with_id(
+            GOVERNANCE,
+            "junohy",
+            juno.clone(),
+            1,
+            Decimal::percent(80),
+            None,
+            None,
+        )
+        .unwrap();
+    suite
+        .create_market_quick(
+            GOVERNANCE,
+            "osmo",
+            Token::Native(OSMO.to_owned()),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    let tier = suite.query_market_with_id(juno.clone(), 1).unwrap().market;
+    let osmo_market = suite
+        .query_market(Token::Native(OSMO.to_owned()))
+        .unwrap()
+        .market;
+
+    let debtor = Addr::unchecked(DEBTOR);
+    suite
+        .app()
+        .execute_contract(
+            debtor.clone(),
+            tier.clone(),
+            &MarketExecuteMsg::Deposit {},
+            &coins(500, JUNO),
+        )
+        .unwrap();
+    suite
+        .app()
+        .execute_contract(
+            debtor.clone(),
+            tier.clone(),
+            &MarketExecuteMsg::Borrow {
+                amount: Uint128::new(400),
+            },
+            &[],
+        )
+        .unwrap();
+    suite.advance_seconds(YEAR_IN_SECONDS);
+    // Repay some tokens to trigger interest rate charges
+    suite
+        .app()
+        .execute_contract(
+            debtor,
+            tier.clone(),
+            &MarketExecuteMsg::Repay {},
+            &coins(2, JUNO),
+        )
+        .unwrap();
+
+    // Sent tokens have to match the debt market
+    let err = suite
+        .liquidate_on_markets(
+            LIQUIDATOR,
+            DEBTOR,
+            &coins(100, JUNO),
+            osmo_market.as_str(),
+            tier.as_str(),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::MarketTokenMismatch {
+            market: osmo_market,
+            token: JUNO.to_owned()
+        },
+        err.downcast().unwrap()
+    );
+
+    // Debt is on the tier, not on the default market
+    let quote = suite
+        .query_liquidation_quote_with_ids(DEBTOR, juno.clone().amount(100u128), 1, juno.clone(), 1)
+        .unwrap();
+    assert!(quote.can_liquidate);
+    assert!(quote.success);
+    let quote = suite
+        .query_liquidation_quote(DEBTOR, juno.clone().amount(100u128), juno)
+        .unwrap();
+    assert!(!quote.success);
+
+    let debt_before = suite.query_total_credit_line(DEBTOR).unwrap().debt;
+    suite
+        .liquidate_on_markets(
+            LIQUIDATOR,
+            DEBTOR,
+            &coins(100, JUNO),
+            tier.as_str(),
+            tier.as_str(),
+        )
+        .unwrap();
+    let debt_after = suite.query_total_credit_line(DEBTOR).unwrap().debt;
+    assert!(debt_after.amount < debt_before.amount);
+}
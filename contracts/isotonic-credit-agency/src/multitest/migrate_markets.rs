@@ -0,0 +1,91 @@
+use isotonic_market::msg::MigrateMsg as MarketMigrateMsg;
+use utils::token::Token;
+
+use super::suite::{contract_market, SuiteBuilder, ATOM, GOVERNANCE, JUNO, LIQUIDATOR, OSMO};
+use crate::error::ContractError;
+
+const TOKENS: [&str; 3] = [ATOM, JUNO, OSMO];
+
+#[test]
+fn migrate_all_markets_in_pages() {
+    let mut suite = SuiteBuilder::new().with_gov(GOVERNANCE).build();
+    for denom in TOKENS {
+        suite
+            .create_market_quick(
+                GOVERNANCE,
+                denom,
+                Token::Native(denom.to_owned()),
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+    }
+    let old_market_id = suite.query_config().unwrap().isotonic_market_id;
+    assert_eq!(suite.query_outdated_markets().unwrap().markets, vec![]);
+
+    let new_market_id = suite.app().store_code(contract_market());
+    suite.sudo_adjust_market_id(new_market_id).unwrap();
+
+    let outdated = suite.query_outdated_markets().unwrap();
+    assert_eq!(outdated.current_code_id, new_market_id);
+    assert_eq!(outdated.markets.len(), 3);
+    assert_eq!(outdated.markets[0].code.code_id, old_market_id);
+    assert_eq!(
+        outdated.markets[0].code.version,
+        env!("CARGO_PKG_VERSION"),
+        "market and credit agency share the workspace version"
+    );
+
+    let migrate_msg = MarketMigrateMsg {
+        isotonic_token_id: None,
+    };
+    let err = suite
+        .migrate_all_markets(LIQUIDATOR, migrate_msg.clone(), None)
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    suite
+        .migrate_all_markets(GOVERNANCE, migrate_msg.clone(), 2)
+        .unwrap();
+    let outdated = suite.query_outdated_markets().unwrap().markets;
+    assert_eq!(outdated.len(), 1);
+    assert_eq!(outdated[0].market_token, Token::Native(OSMO.to_owned()));
+
+    suite
+        .migrate_all_markets(GOVERNANCE, migrate_msg, 2)
+        .unwrap();
+    assert_eq!(suite.query_outdated_markets().unwrap().markets, vec![]);
+    for denom in TOKENS {
+        assert_eq!(
+            suite
+                .query_contract_code_id(Token::Native(denom.to_owned()))
+                .unwrap(),
+            new_market_id
+        );
+    }
+}
+
+#[test]
+fn single_migration_updates_code() {
+    let juno = Token::Native(JUNO.to_owned());
+    let mut suite = SuiteBuilder::new().with_gov(GOVERNANCE).build();
+    suite
+        .create_market_quick(GOVERNANCE, JUNO, juno.clone(), None, None, None)
+        .unwrap();
+
+    let new_market_id = suite.app().store_code(contract_market());
+    suite.sudo_adjust_market_id(new_market_id).unwrap();
+    assert_eq!(suite.query_outdated_markets().unwrap().markets.len(), 1);
+
+    let market = suite.query_market(juno).unwrap().market;
+    suite
+        .sudo_migrate_market(
+            market.as_str(),
+            MarketMigrateMsg {
+                isotonic_token_id: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(suite.query_outdated_markets().unwrap().markets, vec![]);
+}
@@ -0,0 +1,146 @@
+use cosmwasm_std::Decimal;
+use utils::timelock::TimelockError;
+use utils::token::Token;
+
+use super::suite::{SuiteBuilder, GOVERNANCE, JUNO, LIQUIDATOR};
+use crate::error::ContractError;
+use crate::msg::ParamChange;
+
+const DELAY: u64 = 3600;
+
+#[test]
+fn change_waits_for_delay() {
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_param_change_delay(DELAY)
+        .build();
+    let change = ParamChange::DeleverageFee {
+        fee: Decimal::percent(2),
+    };
+
+    suite
+        .propose_param_change(GOVERNANCE, change.clone())
+        .unwrap();
+    assert_eq!(
+        suite.query_config().unwrap().deleverage_fee,
+        Decimal::zero()
+    );
+
+    let pending = suite.query_pending_param_changes().unwrap().changes;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].change, change);
+    let id = pending[0].id;
+
+    let err = suite.execute_param_change(GOVERNANCE, id).unwrap_err();
+    assert_eq!(
+        ContractError::Timelock(TimelockError::NotReady {
+            id,
+            executable_at: pending[0].executable_at,
+        }),
+        err.downcast().unwrap()
+    );
+
+    suite.advance_seconds(DELAY);
+    let err = suite.execute_param_change(LIQUIDATOR, id).unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    suite.execute_param_change(GOVERNANCE, id).unwrap();
+    assert_eq!(
+        suite.query_config().unwrap().deleverage_fee,
+        Decimal::percent(2)
+    );
+    assert_eq!(suite.query_pending_param_changes().unwrap().changes, vec![]);
+}
+
+#[test]
+fn longer_delay_applies_immediately() {
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_param_change_delay(DELAY)
+        .build();
+
+    suite
+        .propose_param_change(
+            GOVERNANCE,
+            ParamChange::ParamChangeDelay {
+                new_delay: 2 * DELAY,
+            },
+        )
+        .unwrap();
+    assert_eq!(suite.query_config().unwrap().param_change_delay, 2 * DELAY);
+
+    // Shortening the delay has to wait for the current one
+    suite
+        .propose_param_change(GOVERNANCE, ParamChange::ParamChangeDelay { new_delay: 0 })
+        .unwrap();
+    assert_eq!(suite.query_config().unwrap().param_change_delay, 2 * DELAY);
+    assert_eq!(
+        suite.query_pending_param_changes().unwrap().changes.len(),
+        1
+    );
+}
+
+#[test]
+fn cancel_change() {
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_param_change_delay(DELAY)
+        .build();
+
+    suite
+        .propose_param_change(GOVERNANCE, ParamChange::MarketId { new_market_id: 30 })
+        .unwrap();
+    let id = suite.query_pending_param_changes().unwrap().changes[0].id;
+
+    let err = suite.cancel_param_change(LIQUIDATOR, id).unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    suite.cancel_param_change(GOVERNANCE, id).unwrap();
+    assert_eq!(suite.query_pending_param_changes().unwrap().changes, vec![]);
+
+    suite.advance_seconds(DELAY);
+    let err = suite.execute_param_change(GOVERNANCE, id).unwrap_err();
+    assert_eq!(
+        ContractError::Timelock(TimelockError::NotFound(id)),
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn invalid_change_rejected_on_proposal() {
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_param_change_delay(DELAY)
+        .build();
+
+    let err = suite
+        .propose_param_change(
+            GOVERNANCE,
+            ParamChange::DeleverageFee {
+                fee: Decimal::one(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidDeleverageFee {},
+        err.downcast().unwrap()
+    );
+    assert_eq!(suite.query_pending_param_changes().unwrap().changes, vec![]);
+}
+
+#[test]
+fn markets_use_the_delay() {
+    let juno = Token::Native(JUNO.to_owned());
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_param_change_delay(DELAY)
+        .build();
+
+    suite
+        .create_market_quick(GOVERNANCE, JUNO, juno.clone(), None, None, None)
+        .unwrap();
+    assert_eq!(
+        suite.query_market_config(juno).unwrap().param_change_delay,
+        DELAY
+    );
+}
@@ -1,6 +1,9 @@
-use utils::token::Token;
+use cosmwasm_std::Decimal;
+use utils::{credit_line::CreditLineValues, token::Token};
 
-use crate::multitest::suite::{ATOM, GOVERNANCE, JUNO, MARKET_TOKEN, WYND};
+use crate::multitest::suite::{
+    ATOM, BORROWER, COMMON, GOVERNANCE, JUNO, LENDER, MARKET_TOKEN, OSMO, WYND,
+};
 
 use super::suite::SuiteBuilder;
 
@@ -144,7 +147,7 @@ fn list_markets_default_pagination() {
     assert_eq!(list1, generate_denoms("TOKEN", 0, 10));
 
     let mut list2: Vec<_> = suite
-        .list_markets_with_pagination(list1.last().unwrap().clone(), None)
+        .list_markets_with_pagination((list1.last().unwrap().clone(), 0), None)
         .unwrap()
         .markets
         .into_iter()
@@ -184,7 +187,7 @@ fn list_markets_custom_pagination() {
     assert_eq!(list1, generate_denoms("TOKEN", 0, 3));
 
     let mut list2: Vec<_> = suite
-        .list_markets_with_pagination(list1.last().unwrap().clone(), 3)
+        .list_markets_with_pagination((list1.last().unwrap().clone(), 0), 3)
         .unwrap()
         .markets
         .into_iter()
@@ -193,3 +196,88 @@ fn list_markets_custom_pagination() {
     list2.sort();
     assert_eq!(list2, generate_denoms("TOKEN", 3, 5));
 }
+
+#[test]
+fn account_health() {
+    let common_token = Token::Native(COMMON.to_owned());
+    let collateral_token = Token::Native(JUNO.to_owned());
+    let debt_token = Token::Native(OSMO.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_funds(BORROWER, &[collateral_token.clone().into_coin(1000u128)])
+        .with_funds(LENDER, &[debt_token.clone().into_coin(500u128)])
+        // collateral token is worth 2.0 common tokens
+        .with_pool(
+            1,
+            (
+                common_token.clone().into_coin(200u128),
+                collateral_token.clone().into_coin(100u128),
+            ),
+        )
+        // debt token is worth 1.0 common token
+        .with_pool(
+            2,
+            (
+                common_token.into_coin(100u128),
+                debt_token.clone().into_coin(100u128),
+            ),
+        )
+        .build();
+
+    for token in [&collateral_token, &debt_token] {
+        suite
+            .create_market_quick(GOVERNANCE, &token.denom(), token.clone(), None, None, None)
+            .unwrap();
+    }
+
+    // Without debt there is no health factor
+    suite
+        .deposit_tokens_on_market(BORROWER, collateral_token.clone().into_coin(1000u128))
+        .unwrap();
+    let health = suite.query_account_health(BORROWER).unwrap();
+    assert_eq!(health.health_factor, None);
+    assert_eq!(health.markets[0].liquidation_price_drop, None);
+
+    suite
+        .deposit_tokens_on_market(LENDER, debt_token.clone().into_coin(500u128))
+        .unwrap();
+    suite
+        .borrow_tokens_from_market(BORROWER, debt_token.clone().into_coin(400u128))
+        .unwrap();
+
+    // 1000 collateral * 2.0 price * 0.5 collateral ratio = 1000 credit line
+    let health = suite.query_account_health(BORROWER).unwrap();
+    assert_eq!(health.health_factor, Some(Decimal::percent(250)));
+    assert_eq!(
+        health.total,
+        CreditLineValues::new(2000u128, 1000u128, 1000u128, 400u128)
+            .make_response(suite.common_token().clone())
+    );
+
+    let collateral_market = suite.query_market(collateral_token.clone()).unwrap().market;
+    let collateral = health
+        .markets
+        .iter()
+        .find(|m| m.market == collateral_market)
+        .unwrap();
+    assert_eq!(collateral.market_token, collateral_token);
+    assert_eq!(collateral.price, Decimal::percent(200));
+    assert_eq!(collateral.collateral, collateral_token.into_coin(1000u128));
+    // Credit line can drop by 600 before it is lower than debt
+    assert_eq!(
+        collateral.liquidation_price_drop,
+        Some(Decimal::percent(60))
+    );
+    assert_eq!(collateral.liquidation_price, Some(Decimal::percent(80)));
+
+    let debt_market = suite.query_market(debt_token.clone()).unwrap().market;
+    let debt = health
+        .markets
+        .iter()
+        .find(|m| m.market == debt_market)
+        .unwrap();
+    assert_eq!(debt.debt, debt_token.into_coin(400u128));
+    // Lower price of borrowed token only makes account healthier
+    assert_eq!(debt.liquidation_price_drop, None);
+}
@@ -1,4 +1,4 @@
-use super::suite::{SuiteBuilder, COMMON};
+use super::suite::{Suite, SuiteBuilder, COMMON};
 use crate::{
     error::ContractError,
     multitest::suite::{
@@ -7,6 +7,8 @@ use crate::{
 };
 
 use cosmwasm_std::{Addr, Decimal, Uint128};
+use utils::events::RepayWithCollateral;
+use utils::tests::event_attribute;
 use utils::{assert_approx_eq, credit_line::CreditLineValues, token::Token};
 
 #[test]
@@ -756,3 +758,130 @@ fn borrow_limit_cw20() {
         err.downcast().unwrap()
     );
 }
+
+/// JUNO (price 2.0) and ATOM (price 0.5) markets with plenty of liquidity, and `ACTOR` with
+/// 100k JUNO deposited
+fn juno_atom_suite() -> Suite {
+    let common_token = Token::Native(COMMON.to_owned());
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_funds(DEPOSIT, &[juno.clone().into_coin(10_000_000u128)])
+        .with_funds(DEPOSIT_2, &[atom.clone().into_coin(10_000_000u128)])
+        .with_funds(ACTOR, &[juno.clone().into_coin(100_000u128)])
+        .with_pool(
+            1,
+            (
+                common_token.clone().into_coin(20_000_000u128),
+                juno.clone().into_coin(10_000_000u128),
+            ),
+        )
+        .with_pool(
+            2,
+            (
+                common_token.into_coin(5_000_000u128),
+                atom.clone().into_coin(10_000_000u128),
+            ),
+        )
+        .build();
+
+    for token in [&juno, &atom] {
+        suite
+            .create_market_quick(GOVERNANCE, &token.denom(), token.clone(), None, None, None)
+            .unwrap();
+    }
+    suite
+        .deposit_tokens_on_market(DEPOSIT, juno.clone().into_coin(10_000_000u128))
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(DEPOSIT_2, atom.into_coin(10_000_000u128))
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(ACTOR, juno.into_coin(100_000u128))
+        .unwrap();
+    suite
+}
+
+#[test]
+fn same_denom_burns_collateral() {
+    let juno = Token::Native(JUNO.to_owned());
+    let mut suite = juno_atom_suite();
+    suite
+        .borrow_tokens_from_market(ACTOR, juno.clone().into_coin(20_000u128))
+        .unwrap();
+    let market = suite.query_market(juno.clone()).unwrap().market;
+
+    let response = suite
+        .repay_with_collateral(
+            ACTOR,
+            juno.clone().into_coin(30_000u128),
+            juno.clone().into_coin(20_000u128),
+        )
+        .unwrap();
+    assert_eq!(
+        event_attribute::<RepayWithCollateral>(&response.events, "account"),
+        Some(ACTOR.to_owned())
+    );
+    assert_eq!(
+        event_attribute::<RepayWithCollateral>(&response.events, "collateral_market"),
+        Some(market.to_string())
+    );
+    assert_eq!(
+        event_attribute::<RepayWithCollateral>(&response.events, "debt_market"),
+        Some(market.to_string())
+    );
+    assert_eq!(
+        event_attribute::<RepayWithCollateral>(&response.events, "amount"),
+        Some(format!("20000{}", JUNO))
+    );
+
+    // Only the repaid amount is withdrawn from the collateral, nothing is swapped
+    let total_credit_line = suite.query_total_credit_line(ACTOR).unwrap();
+    assert_eq!(total_credit_line.collateral.amount, Uint128::new(160_000));
+    assert_eq!(total_credit_line.debt.amount, Uint128::zero());
+    assert_eq!(
+        suite
+            .query_tokens_balance(juno.clone(), ACTOR)
+            .unwrap()
+            .collateral
+            .amount,
+        Uint128::new(80_000)
+    );
+    let ca = suite.credit_agency();
+    assert_eq!(suite.query_native_balance(ca.as_str(), JUNO).unwrap(), 0);
+}
+
+#[test]
+fn different_denoms_sell_only_estimate() {
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+    let mut suite = juno_atom_suite();
+    suite
+        .borrow_tokens_from_market(ACTOR, atom.clone().into_coin(20_000u128))
+        .unwrap();
+
+    // The whole collateral is allowed to be sold, but ~5k JUNO is enough to buy 20k ATOM
+    suite
+        .repay_with_collateral(
+            ACTOR,
+            juno.clone().into_coin(100_000u128),
+            atom.into_coin(20_000u128),
+        )
+        .unwrap();
+
+    let total_credit_line = suite.query_total_credit_line(ACTOR).unwrap();
+    assert_eq!(total_credit_line.debt.amount, Uint128::zero());
+    let collateral = suite
+        .query_tokens_balance(juno, ACTOR)
+        .unwrap()
+        .collateral
+        .amount;
+    assert!(collateral > Uint128::new(94_000), "{}", collateral);
+    assert!(collateral < Uint128::new(95_000), "{}", collateral);
+
+    let ca = suite.credit_agency();
+    assert_eq!(suite.query_native_balance(ca.as_str(), JUNO).unwrap(), 0);
+    assert_eq!(suite.query_native_balance(ca.as_str(), ATOM).unwrap(), 0);
+}
@@ -0,0 +1,255 @@
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use utils::interest::{Interest, ValidatedInterest};
+use utils::token::Token;
+
+use super::suite::{Suite, SuiteBuilder, COMMON, GOVERNANCE, JUNO, LIQUIDATOR};
+use crate::error::ContractError;
+use crate::msg::{ParamChange, StewardChange};
+use crate::state::{RiskSteward, StewardBounds};
+
+const STEWARD: &str = "steward";
+const DELAY: u64 = 3600;
+
+fn bounds() -> StewardBounds {
+    StewardBounds {
+        min_collateral_ratio: Decimal::percent(40),
+        max_collateral_ratio: Decimal::percent(60),
+        max_market_cap_common: Uint128::new(1_000_000),
+        max_interest_rate: Decimal::percent(30),
+    }
+}
+
+/// JUNO market with collateral ratio 0.5 and price 2.0, and the steward set by the governance
+fn steward_suite() -> Suite {
+    let common = Token::Native(COMMON.to_owned());
+    let juno = Token::Native(JUNO.to_owned());
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_pool(
+            1,
+            (common.into_coin(200u128), juno.clone().into_coin(100u128)),
+        )
+        .build();
+    suite
+        .create_market_quick(GOVERNANCE, JUNO, juno, None, None, None)
+        .unwrap();
+    suite
+        .propose_param_change(
+            GOVERNANCE,
+            ParamChange::RiskSteward {
+                steward: Some(RiskSteward {
+                    address: Addr::unchecked(STEWARD),
+                    bounds: bounds(),
+                }),
+            },
+        )
+        .unwrap();
+    suite
+}
+
+#[test]
+fn steward_adjusts_market() {
+    let mut suite = steward_suite();
+    let juno = Token::Native(JUNO.to_owned());
+
+    suite
+        .steward_adjust(
+            STEWARD,
+            juno.clone(),
+            StewardChange::CollateralRatio {
+                new_ratio: Decimal::percent(60),
+            },
+        )
+        .unwrap();
+    // 500k JUNO is worth 1M common, the highest cap allowed
+    suite
+        .steward_adjust(
+            STEWARD,
+            juno.clone(),
+            StewardChange::MarketCap {
+                new_cap: Uint128::new(500_000),
+            },
+        )
+        .unwrap();
+
+    let market_cfg = suite.query_market_config(juno.clone()).unwrap();
+    assert_eq!(market_cfg.collateral_ratio, Decimal::percent(60));
+    assert_eq!(market_cfg.market_cap, Some(Uint128::new(500_000)));
+
+    let log = suite.query_steward_log().unwrap().actions;
+    assert_eq!(log.len(), 2);
+    assert_eq!(log[0].id, 0);
+    assert_eq!(log[0].action.steward, Addr::unchecked(STEWARD));
+    assert_eq!(
+        log[0].action.market,
+        suite.query_market(juno).unwrap().market
+    );
+    assert_eq!(
+        log[1].action.change,
+        StewardChange::MarketCap {
+            new_cap: Uint128::new(500_000)
+        }
+    );
+}
+
+#[test]
+fn steward_skips_delay() {
+    let juno = Token::Native(JUNO.to_owned());
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_param_change_delay(DELAY)
+        .build();
+    suite
+        .create_market_quick(GOVERNANCE, JUNO, juno.clone(), None, None, None)
+        .unwrap();
+    suite
+        .propose_param_change(
+            GOVERNANCE,
+            ParamChange::RiskSteward {
+                steward: Some(RiskSteward {
+                    address: Addr::unchecked(STEWARD),
+                    bounds: bounds(),
+                }),
+            },
+        )
+        .unwrap();
+    suite.advance_seconds(DELAY);
+    let id = suite.query_pending_param_changes().unwrap().changes[0].id;
+    suite.execute_param_change(GOVERNANCE, id).unwrap();
+
+    // Raising the rate is not risk-reducing, governance would have to wait
+    let rates = Interest::Linear {
+        base: Decimal::percent(5),
+        slope: Decimal::percent(25),
+    };
+    suite
+        .steward_adjust(
+            STEWARD,
+            juno.clone(),
+            StewardChange::InterestRates {
+                new_interest_rates: rates.clone(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        suite.query_market_config(juno.clone()).unwrap().rates,
+        ValidatedInterest::unchecked(rates)
+    );
+
+    // Lowering the collateral ratio, which governance has to propose with the delay, is applied
+    // immediately as well
+    suite
+        .steward_adjust(
+            STEWARD,
+            juno.clone(),
+            StewardChange::CollateralRatio {
+                new_ratio: Decimal::percent(40),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        suite.query_market_config(juno).unwrap().collateral_ratio,
+        Decimal::percent(40)
+    );
+}
+
+#[test]
+fn changes_outside_bounds_rejected() {
+    let mut suite = steward_suite();
+    let juno = Token::Native(JUNO.to_owned());
+
+    let err = suite
+        .steward_adjust(
+            STEWARD,
+            juno.clone(),
+            StewardChange::CollateralRatio {
+                new_ratio: Decimal::percent(30),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::StewardBoundsExceeded("collateral_ratio".to_owned()),
+        err.downcast().unwrap()
+    );
+
+    let err = suite
+        .steward_adjust(
+            STEWARD,
+            juno.clone(),
+            StewardChange::MarketCap {
+                new_cap: Uint128::new(500_001),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::StewardBoundsExceeded("market_cap".to_owned()),
+        err.downcast().unwrap()
+    );
+
+    // Rate at full utilisation would be 35%
+    let err = suite
+        .steward_adjust(
+            STEWARD,
+            juno,
+            StewardChange::InterestRates {
+                new_interest_rates: Interest::Linear {
+                    base: Decimal::percent(5),
+                    slope: Decimal::percent(30),
+                },
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::StewardBoundsExceeded("interest_rates".to_owned()),
+        err.downcast().unwrap()
+    );
+
+    assert_eq!(suite.query_steward_log().unwrap().actions, vec![]);
+}
+
+#[test]
+fn only_steward_can_adjust() {
+    let mut suite = steward_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let change = StewardChange::CollateralRatio {
+        new_ratio: Decimal::percent(45),
+    };
+
+    for sender in [LIQUIDATOR, GOVERNANCE] {
+        let err = suite
+            .steward_adjust(sender, juno.clone(), change.clone())
+            .unwrap_err();
+        assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    }
+
+    suite
+        .propose_param_change(GOVERNANCE, ParamChange::RiskSteward { steward: None })
+        .unwrap();
+    assert_eq!(suite.query_config().unwrap().risk_steward, None);
+    let err = suite.steward_adjust(STEWARD, juno, change).unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+}
+
+#[test]
+fn invalid_bounds() {
+    let mut suite = SuiteBuilder::new().with_gov(GOVERNANCE).build();
+
+    let err = suite
+        .propose_param_change(
+            GOVERNANCE,
+            ParamChange::RiskSteward {
+                steward: Some(RiskSteward {
+                    address: Addr::unchecked(STEWARD),
+                    bounds: StewardBounds {
+                        min_collateral_ratio: Decimal::percent(70),
+                        ..bounds()
+                    },
+                }),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidStewardBounds {},
+        err.downcast().unwrap()
+    );
+}
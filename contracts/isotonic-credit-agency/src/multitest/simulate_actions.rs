@@ -0,0 +1,249 @@
+use cosmwasm_std::Decimal;
+use utils::{coin::Coin, credit_line::CreditLineValues, token::Token};
+
+use super::suite::{Suite, SuiteBuilder, ATOM, BORROWER, COMMON, GOVERNANCE, JUNO, LENDER, OSMO};
+use crate::error::ContractError;
+use crate::msg::{Action, ParamChange};
+
+fn juno(amount: u128) -> Coin {
+    Coin::new(amount, Token::Native(JUNO.to_owned()))
+}
+
+fn osmo(amount: u128) -> Coin {
+    Coin::new(amount, Token::Native(OSMO.to_owned()))
+}
+
+/// `BORROWER` has 1000 JUNO worth 2.0 common tokens each deposited, `LENDER` provides 500 OSMO
+/// worth 1.0 common token each
+fn suite_with_collateral() -> Suite {
+    let common_token = Token::Native(COMMON.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_funds(BORROWER, &[juno(1000)])
+        .with_funds(LENDER, &[osmo(500)])
+        .with_pool(1, (common_token.clone().into_coin(200u128), juno(100)))
+        .with_pool(2, (common_token.into_coin(100u128), osmo(100)))
+        .build();
+
+    for token in [juno(0).denom, osmo(0).denom] {
+        suite
+            .create_market_quick(GOVERNANCE, &token.denom(), token.clone(), None, None, None)
+            .unwrap();
+    }
+
+    suite
+        .deposit_tokens_on_market(BORROWER, juno(1000))
+        .unwrap();
+    suite.deposit_tokens_on_market(LENDER, osmo(500)).unwrap();
+
+    suite
+}
+
+#[test]
+fn actions_applied_in_order() {
+    let suite = suite_with_collateral();
+
+    let resp = suite
+        .query_simulate_actions(
+            BORROWER,
+            vec![
+                Action::Borrow {
+                    amount: osmo(400),
+                    market_id: None,
+                },
+                // 600 common of credit line left, worth 600 JUNO at collateral ratio 0.5
+                Action::Withdraw {
+                    amount: juno(600),
+                    market_id: None,
+                },
+                Action::Borrow {
+                    amount: osmo(1),
+                    market_id: None,
+                },
+                Action::Repay {
+                    amount: osmo(100),
+                    market_id: None,
+                },
+                Action::SwapCollateral {
+                    sell: juno(400),
+                    buy: osmo(800),
+                    sell_market_id: None,
+                    buy_market_id: None,
+                },
+            ],
+        )
+        .unwrap();
+
+    let accepted: Vec<_> = resp.actions.iter().map(|a| a.accepted).collect();
+    assert_eq!(accepted, [true, true, false, true, true]);
+
+    let health: Vec<_> = resp.actions.iter().map(|a| a.health_factor).collect();
+    assert_eq!(
+        health,
+        [
+            Some(Decimal::percent(250)),
+            Some(Decimal::one()),
+            Some(Decimal::one()),
+            Some(Decimal::from_ratio(4u128, 3u128)),
+            Some(Decimal::from_ratio(4u128, 3u128)),
+        ]
+    );
+    assert_eq!(
+        resp.actions[1].total,
+        CreditLineValues::new(800u128, 400u128, 400u128, 400u128)
+            .make_response(suite.common_token().clone())
+    );
+    assert_eq!(
+        resp.total,
+        CreditLineValues::new(800u128, 400u128, 400u128, 300u128)
+            .make_response(suite.common_token().clone())
+    );
+    assert_eq!(resp.health_factor, Some(Decimal::from_ratio(4u128, 3u128)));
+
+    // Nothing really happened
+    let health = suite.query_account_health(BORROWER).unwrap();
+    assert_eq!(health.health_factor, None);
+}
+
+#[test]
+fn rejected_actions_are_skipped() {
+    let suite = suite_with_collateral();
+
+    let resp = suite
+        .query_simulate_actions(
+            BORROWER,
+            vec![
+                Action::Borrow {
+                    amount: osmo(1001),
+                    market_id: None,
+                },
+                Action::Borrow {
+                    amount: osmo(500),
+                    market_id: None,
+                },
+                // Only 500 JUNO are not needed to cover the debt
+                Action::Withdraw {
+                    amount: juno(501),
+                    market_id: None,
+                },
+                // Bought collateral is not enough to cover sold one
+                Action::SwapCollateral {
+                    sell: juno(1000),
+                    buy: osmo(500),
+                    sell_market_id: None,
+                    buy_market_id: None,
+                },
+                Action::Withdraw {
+                    amount: juno(500),
+                    market_id: None,
+                },
+            ],
+        )
+        .unwrap();
+
+    let accepted: Vec<_> = resp.actions.iter().map(|a| a.accepted).collect();
+    assert_eq!(accepted, [false, true, false, false, true]);
+    assert_eq!(
+        resp.total,
+        CreditLineValues::new(1000u128, 500u128, 500u128, 500u128)
+            .make_response(suite.common_token().clone())
+    );
+}
+
+#[test]
+fn deposit_on_new_market() {
+    let suite = suite_with_collateral();
+
+    // Lender has no debt, so the whole collateral can be withdrawn
+    let resp = suite
+        .query_simulate_actions(
+            LENDER,
+            vec![
+                Action::Deposit {
+                    amount: juno(100),
+                    market_id: None,
+                },
+                Action::Withdraw {
+                    amount: osmo(500),
+                    market_id: None,
+                },
+            ],
+        )
+        .unwrap();
+    assert!(resp.actions.iter().all(|a| a.accepted));
+    assert_eq!(
+        resp.total,
+        CreditLineValues::new(200u128, 100u128, 100u128, 0u128)
+            .make_response(suite.common_token().clone())
+    );
+    assert_eq!(resp.health_factor, None);
+
+    let err = suite
+        .query_simulate_actions(
+            LENDER,
+            vec![Action::Deposit {
+                amount: Coin::new(100, Token::Native(ATOM.to_owned())),
+                market_id: None,
+            }],
+        )
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains(&ContractError::NoMarket(ATOM.to_owned()).to_string()));
+
+    // Only the default JUNO market exists
+    let err = suite
+        .query_simulate_actions(
+            LENDER,
+            vec![Action::Deposit {
+                amount: juno(100),
+                market_id: Some(1),
+            }],
+        )
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains(&ContractError::NoMarket(JUNO.to_owned()).to_string()));
+}
+
+#[test]
+fn wind_down_lowers_simulated_credit_line() {
+    const DURATION: u64 = 3600;
+    let mut suite = suite_with_collateral();
+    suite
+        .borrow_tokens_from_market(BORROWER, osmo(400))
+        .unwrap();
+
+    let market = suite.query_market(juno(0).denom).unwrap().market;
+    suite
+        .propose_param_change(
+            GOVERNANCE,
+            ParamChange::WindDownMarket {
+                market: market.to_string(),
+                duration: DURATION,
+                force_liquidation: false,
+            },
+        )
+        .unwrap();
+
+    // Halfway the collateral ratio is 0.25 - 500 common of credit line, so only 100 common of
+    // it, worth 200 JUNO at that ratio, is not needed to cover the debt
+    suite.advance_seconds(DURATION / 2);
+    let withdraw = |amount| Action::Withdraw {
+        amount: juno(amount),
+        market_id: None,
+    };
+    let resp = suite
+        .query_simulate_actions(BORROWER, vec![withdraw(300), withdraw(190)])
+        .unwrap();
+    let accepted: Vec<_> = resp.actions.iter().map(|a| a.accepted).collect();
+    assert_eq!(accepted, [false, true]);
+
+    // Wound down collateral doesn't back the debt anymore, all of it can be withdrawn
+    suite.advance_seconds(DURATION / 2);
+    let resp = suite
+        .query_simulate_actions(BORROWER, vec![withdraw(1000)])
+        .unwrap();
+    assert!(resp.actions[0].accepted);
+}
@@ -14,7 +14,7 @@ use cosmwasm_std::{
 use cw_multi_test::{AppResponse, Contract, ContractWrapper, Executor, App};
 use isotonic_market::msg::{
     ExecuteMsg as MarketExecuteMsg, MigrateMsg as MarketMigrateMsg, QueryMsg as MarketQueryMsg,
-    ReceiveMsg as MarketReceiveMsg, TokensBalanceResponse,
+    ReceiveMsg as MarketReceiveMsg, TokensBalanceResponse, WindDownResponse,
 };
 use isotonic_market::state::SECONDS_IN_YEAR;
 
@@ -28,11 +28,16 @@ use utils::{credit_line::CreditLineResponse, interest::Interest, token::Token};
 use cw20_base::msg::{ExecuteMsg as Cw20BaseExecuteMsg, InstantiateMsg as Cw20BaseInstantiateMsg};
 
 use crate::msg::{
-    ExecuteMsg, InstantiateMsg, IsOnMarketResponse, LiquidationResponse,
-    ListEnteredMarketsResponse, ListMarketsResponse, MarketConfig, MarketResponse, QueryMsg,
-    ReceiveMsg,
+    AccountHealthResponse, Action, EmissionResponse, ExecuteMsg, InstantiateMsg,
+    IsOnMarketResponse, IsolatedMarketResponse, LiquidationAuctionResponse,
+    LiquidationQuoteResponse, LiquidationResponse, ListAccountsResponse,
+    ListEnteredMarketsResponse, ListLiquidatableResponse, ListMarketsResponse,
+    ListOutdatedMarketsResponse, MarketConfig, MarketResponse, MarketTemplatesResponse,
+    ParamChange, PendingGovernanceResponse, PendingParamChangesResponse,
+    PredictMarketAddressResponse, QueryMsg, ReceiveMsg,
+    SimulateActionsResponse, StewardChange, StewardLogResponse, TemplateMarketConfig,
 };
-use crate::state::Config;
+use crate::state::{Config, LiquidationAuction, DEFAULT_MARKET_ID};
 
 pub const DAY: u64 = 24 * 3600;
 // Generic
@@ -159,6 +164,9 @@ pub struct SuiteBuilder {
     /// with token addresses.
     pools: HashMap<u64, (utils::coin::Coin, utils::coin::Coin)>,
     borrow_limit_ratio: Decimal,
+    liquidation_auction: Option<LiquidationAuction>,
+    deleverage_fee: Decimal,
+    param_change_delay: u64,
     initial_cw20: HashMap<String, Vec<Cw20Coin>>,
 }
 
@@ -174,6 +182,9 @@ impl SuiteBuilder {
             pools: HashMap::new(),
             lsd_pools: HashMap::new(),
             borrow_limit_ratio: Decimal::one(),
+            liquidation_auction: None,
+            deleverage_fee: Decimal::zero(),
+            param_change_delay: 0,
             initial_cw20: HashMap::new(),
         }
     }
@@ -242,6 +253,21 @@ impl SuiteBuilder {
         self
     }
 
+    pub fn with_liquidation_auction(mut self, auction: LiquidationAuction) -> Self {
+        self.liquidation_auction = Some(auction);
+        self
+    }
+
+    pub fn with_deleverage_fee(mut self, fee: Decimal) -> Self {
+        self.deleverage_fee = fee;
+        self
+    }
+
+    pub fn with_param_change_delay(mut self, delay: u64) -> Self {
+        self.param_change_delay = delay;
+        self
+    }
+
     #[track_caller]
     pub fn build(self) -> Suite {
         let mut app = App::default();
@@ -288,6 +314,9 @@ impl SuiteBuilder {
                     common_token: self.common_token,
                     liquidation_price: self.liquidation_price,
                     borrow_limit_ratio: self.borrow_limit_ratio,
+                    liquidation_auction: self.liquidation_auction,
+                    deleverage_fee: self.deleverage_fee,
+                    param_change_delay: self.param_change_delay,
                 },
                 &[],
                 "credit-agency",
@@ -426,6 +455,29 @@ impl Suite {
         collateral_ratio: impl Into<Option<Decimal>>,
         interest_rates: impl Into<Option<(Decimal, Decimal)>>,
         reserve_factor: impl Into<Option<Decimal>>,
+    ) -> AnyResult<AppResponse> {
+        self.create_market_with_id(
+            caller,
+            isotonic_token,
+            market_token,
+            DEFAULT_MARKET_ID,
+            collateral_ratio,
+            interest_rates,
+            reserve_factor,
+        )
+    }
+
+    /// Same as `create_market_quick`, but creates the market with given `market_id`
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_market_with_id(
+        &mut self,
+        caller: &str,
+        isotonic_token: &str,
+        market_token: Token,
+        market_id: u32,
+        collateral_ratio: impl Into<Option<Decimal>>,
+        interest_rates: impl Into<Option<(Decimal, Decimal)>>,
+        reserve_factor: impl Into<Option<Decimal>>,
     ) -> AnyResult<AppResponse> {
         self.create_market(
             caller,
@@ -434,6 +486,7 @@ impl Suite {
                 symbol: isotonic_token.to_string(),
                 decimals: 9,
                 market_token,
+                market_id,
                 market_cap: None,
                 interest_rate: match interest_rates.into() {
                     Some((base, slope)) => Interest::Linear { base, slope },
@@ -474,6 +527,10 @@ impl Suite {
         )
     }
 
+    pub fn credit_agency(&self) -> Addr {
+        self.contract.clone()
+    }
+
     pub fn common_token(&self) -> &Token {
         &self.common_token
     }
@@ -487,12 +544,37 @@ impl Suite {
         Ok(resp)
     }
 
-    /// Queries the Credit Agency contract for market addr associated to `asset`.
+    pub fn query_predict_market_address(
+        &self,
+        asset: Token,
+        market_id: impl Into<Option<u32>>,
+    ) -> AnyResult<PredictMarketAddressResponse> {
+        let resp: PredictMarketAddressResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::PredictMarketAddress {
+                market_token: asset,
+                market_id: market_id.into(),
+            },
+        )?;
+        Ok(resp)
+    }
+
+    /// Queries the Credit Agency contract for the default market addr associated to `asset`.
     pub fn query_market(&self, asset: Token) -> AnyResult<MarketResponse> {
+        self.query_market_with_id(asset, None)
+    }
+
+    /// Queries the Credit Agency contract for market addr associated to `asset` and `market_id`.
+    pub fn query_market_with_id(
+        &self,
+        asset: Token,
+        market_id: impl Into<Option<u32>>,
+    ) -> AnyResult<MarketResponse> {
         let resp: MarketResponse = self.app.wrap().query_wasm_smart(
             self.contract.clone(),
             &QueryMsg::Market {
                 market_token: asset,
+                market_id: market_id.into(),
             },
         )?;
         Ok(resp)
@@ -534,7 +616,7 @@ impl Suite {
     /// Queries the Credit Agency contract for a list of markets with pagination
     pub fn list_markets_with_pagination(
         &self,
-        start_after: impl Into<Option<Token>>,
+        start_after: impl Into<Option<(Token, u32)>>,
         limit: impl Into<Option<u32>>,
     ) -> AnyResult<ListMarketsResponse> {
         let resp: ListMarketsResponse = self.app.wrap().query_wasm_smart(
@@ -547,6 +629,36 @@ impl Suite {
         Ok(resp)
     }
 
+    pub fn list_accounts(
+        &self,
+        start_after: impl Into<Option<String>>,
+        limit: impl Into<Option<u32>>,
+    ) -> AnyResult<ListAccountsResponse> {
+        let resp: ListAccountsResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::ListAccounts {
+                start_after: start_after.into(),
+                limit: limit.into(),
+            },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn list_liquidatable(
+        &self,
+        start_after: impl Into<Option<String>>,
+        limit: impl Into<Option<u32>>,
+    ) -> AnyResult<ListLiquidatableResponse> {
+        let resp: ListLiquidatableResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::ListLiquidatable {
+                start_after: start_after.into(),
+                limit: limit.into(),
+            },
+        )?;
+        Ok(resp)
+    }
+
     /// Deposit tokens on market selected by denom of Coin. It manages both native and cw20 tokens.
     pub fn deposit_tokens_on_market(
         &mut self,
@@ -614,12 +726,34 @@ impl Suite {
         )
     }
 
+    /// Liquidates on the default markets of the first sent token and of `collateral_denom`
     pub fn liquidate(
         &mut self,
         sender: &str,
         account: &str,
         tokens: &[Coin],
         collateral_denom: Token,
+    ) -> AnyResult<AppResponse> {
+        let debt_market = self
+            .query_market(Token::Native(tokens[0].denom.clone()))?
+            .market;
+        let collateral_market = self.query_market(collateral_denom)?.market;
+        self.liquidate_on_markets(
+            sender,
+            account,
+            tokens,
+            debt_market.as_str(),
+            collateral_market.as_str(),
+        )
+    }
+
+    pub fn liquidate_on_markets(
+        &mut self,
+        sender: &str,
+        account: &str,
+        tokens: &[Coin],
+        debt_market: &str,
+        collateral_market: &str,
     ) -> AnyResult<AppResponse> {
         let ca = self.contract.clone();
 
@@ -628,7 +762,8 @@ impl Suite {
             ca,
             &ExecuteMsg::Liquidate {
                 account: account.to_owned(),
-                collateral_denom,
+                debt_market: debt_market.to_owned(),
+                collateral_market: collateral_market.to_owned(),
             },
             tokens,
         )
@@ -641,9 +776,12 @@ impl Suite {
         tokens: utils::coin::Coin,
         collateral_denom: Token,
     ) -> AnyResult<AppResponse> {
+        let debt_market = self.query_market(tokens.denom.clone())?.market;
+        let collateral_market = self.query_market(collateral_denom)?.market;
         let msg: Binary = to_binary(&ReceiveMsg::Liquidate {
             account: account.to_owned(),
-            collateral_denom,
+            debt_market: debt_market.to_string(),
+            collateral_market: collateral_market.to_string(),
         })?;
 
         self.app.execute_contract(
@@ -690,19 +828,354 @@ impl Suite {
         }
     }
 
+    pub fn liquidate_with_swap(
+        &mut self,
+        sender: &str,
+        account: &str,
+        repay: utils::coin::Coin,
+        collateral_denom: Token,
+        min_profit: u128,
+    ) -> AnyResult<AppResponse> {
+        let ca = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            ca,
+            &ExecuteMsg::LiquidateWithSwap {
+                account: account.to_owned(),
+                debt_denom: repay.denom,
+                collateral_denom,
+                repay_amount: repay.amount,
+                min_profit: min_profit.into(),
+                debt_market_id: None,
+                collateral_market_id: None,
+            },
+            &[],
+        )
+    }
+
+    pub fn update_liquidation_auction(
+        &mut self,
+        sender: &str,
+        account: &str,
+    ) -> AnyResult<AppResponse> {
+        let ca = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            ca,
+            &ExecuteMsg::UpdateLiquidationAuction {
+                account: account.to_owned(),
+            },
+            &[],
+        )
+    }
+
+    pub fn propose_param_change(
+        &mut self,
+        sender: &str,
+        change: ParamChange,
+    ) -> AnyResult<AppResponse> {
+        let ca = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            ca,
+            &ExecuteMsg::ProposeParamChange { change },
+            &[],
+        )
+    }
+
+    pub fn execute_param_change(&mut self, sender: &str, id: u64) -> AnyResult<AppResponse> {
+        let ca = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            ca,
+            &ExecuteMsg::ExecuteParamChange { id },
+            &[],
+        )
+    }
+
+    pub fn cancel_param_change(&mut self, sender: &str, id: u64) -> AnyResult<AppResponse> {
+        let ca = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            ca,
+            &ExecuteMsg::CancelParamChange { id },
+            &[],
+        )
+    }
+
+    pub fn query_pending_param_changes(&self) -> AnyResult<PendingParamChangesResponse> {
+        let response: PendingParamChangesResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::PendingParamChanges {
+                start_after: None,
+                limit: None,
+            },
+        )?;
+        Ok(response)
+    }
+
+    pub fn steward_adjust(
+        &mut self,
+        sender: &str,
+        market_token: Token,
+        change: StewardChange,
+    ) -> AnyResult<AppResponse> {
+        let market = self.query_market(market_token)?.market;
+        let ca = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            ca,
+            &ExecuteMsg::StewardAdjust {
+                market: market.to_string(),
+                change,
+            },
+            &[],
+        )
+    }
+
+    pub fn query_steward_log(&self) -> AnyResult<StewardLogResponse> {
+        let response: StewardLogResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::StewardLog {
+                start_after: None,
+                limit: None,
+            },
+        )?;
+        Ok(response)
+    }
+
+    pub fn propose_new_governance(
+        &mut self,
+        sender: &str,
+        new_governance: &str,
+    ) -> AnyResult<AppResponse> {
+        let ca = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            ca,
+            &ExecuteMsg::ProposeNewGovernance {
+                new_governance: new_governance.to_owned(),
+            },
+            &[],
+        )
+    }
+
+    pub fn accept_governance(&mut self, sender: &str) -> AnyResult<AppResponse> {
+        let ca = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            ca,
+            &ExecuteMsg::AcceptGovernance {},
+            &[],
+        )
+    }
+
+    pub fn query_pending_governance(&self) -> AnyResult<Option<Addr>> {
+        let response: PendingGovernanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(self.contract.clone(), &QueryMsg::PendingGovernance {})?;
+        Ok(response.new_governance)
+    }
+
+    pub fn create_market_from_template(
+        &mut self,
+        sender: &str,
+        cfg: TemplateMarketConfig,
+    ) -> AnyResult<AppResponse> {
+        let ca = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            ca,
+            &ExecuteMsg::CreateMarketFromTemplate(cfg),
+            &[],
+        )
+    }
+
+    /// Registers the pair of given tokens in the price oracle, as its controller
+    pub fn register_oracle_pool(
+        &mut self,
+        pair_contract: &str,
+        token1: Token,
+        token2: Token,
+    ) -> AnyResult<AppResponse> {
+        let oracle = self.oracle_contract.clone();
+        self.app.execute_contract(
+            self.owner.clone(),
+            oracle,
+            &OracleExecuteMsg::RegisterPool {
+                pair_contract: pair_contract.to_owned(),
+                denom1: token1.into(),
+                denom2: token2.into(),
+            },
+            &[],
+        )
+    }
+
+    pub fn query_market_templates(&self) -> AnyResult<MarketTemplatesResponse> {
+        let response: MarketTemplatesResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::MarketTemplates {
+                start_after: None,
+                limit: None,
+            },
+        )?;
+        Ok(response)
+    }
+
+    pub fn query_isolated_market(&self, market: &Addr) -> AnyResult<Option<Uint128>> {
+        let response: IsolatedMarketResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::IsolatedMarket {
+                market: market.to_string(),
+            },
+        )?;
+        Ok(response.credit_cap)
+    }
+
+    pub fn adjust_deleverage_fee(&mut self, sender: &str, fee: Decimal) -> AnyResult<AppResponse> {
+        self.propose_param_change(sender, ParamChange::DeleverageFee { fee })
+    }
+
+    pub fn adjust_liquidation_auction(
+        &mut self,
+        sender: &str,
+        auction: Option<LiquidationAuction>,
+    ) -> AnyResult<AppResponse> {
+        self.propose_param_change(sender, ParamChange::LiquidationAuction { auction })
+    }
+
+    pub fn open_leveraged_position(
+        &mut self,
+        sender: &str,
+        initial: utils::coin::Coin,
+        debt_denom: Token,
+        target_leverage: Decimal,
+        max_slippage: Decimal,
+    ) -> AnyResult<AppResponse> {
+        let ca = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            ca,
+            &ExecuteMsg::OpenLeveragedPosition {
+                collateral_denom: initial.denom.clone(),
+                debt_denom,
+                initial_amount: initial.amount,
+                target_leverage,
+                max_slippage,
+                collateral_market_id: None,
+                debt_market_id: None,
+            },
+            &[initial.try_into()?],
+        )
+    }
+
+    pub fn close_leveraged_position(
+        &mut self,
+        sender: &str,
+        collateral_denom: Token,
+        debt_denom: Token,
+        max_slippage: Decimal,
+    ) -> AnyResult<AppResponse> {
+        let ca = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            ca,
+            &ExecuteMsg::CloseLeveragedPosition {
+                collateral_denom,
+                debt_denom,
+                max_slippage,
+                collateral_market_id: None,
+                debt_market_id: None,
+            },
+            &[],
+        )
+    }
+
+    pub fn swap_collateral(
+        &mut self,
+        sender: &str,
+        from: utils::coin::Coin,
+        to_denom: Token,
+        min_receive: impl Into<Uint128>,
+    ) -> AnyResult<AppResponse> {
+        let ca = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            ca,
+            &ExecuteMsg::SwapCollateral {
+                from,
+                to_denom,
+                min_receive: min_receive.into(),
+                from_market_id: None,
+                to_market_id: None,
+            },
+            &[],
+        )
+    }
+
+    pub fn swap_debt(
+        &mut self,
+        sender: &str,
+        from_denom: Token,
+        to_denom: Token,
+        amount: impl Into<Uint128>,
+        max_new_debt: impl Into<Uint128>,
+    ) -> AnyResult<AppResponse> {
+        let ca = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            ca,
+            &ExecuteMsg::SwapDebt {
+                from_denom,
+                to_denom,
+                amount: amount.into(),
+                max_new_debt: max_new_debt.into(),
+                from_market_id: None,
+                to_market_id: None,
+            },
+            &[],
+        )
+    }
+
+    /// Repays on the default markets of given tokens
     pub fn repay_with_collateral(
         &mut self,
         sender: &str,
         max_collateral: utils::coin::Coin,
         amount_to_repay: utils::coin::Coin,
     ) -> AnyResult<AppResponse> {
+        let collateral_market = self.query_market(max_collateral.denom)?.market;
+        let debt_market = self.query_market(amount_to_repay.denom)?.market;
         let ca = self.contract.clone();
         self.app.execute_contract(
             Addr::unchecked(sender),
             ca,
             &ExecuteMsg::RepayWithCollateral {
+                collateral_market: collateral_market.to_string(),
+                max_collateral: max_collateral.amount,
+                debt_market: debt_market.to_string(),
+                amount_to_repay: amount_to_repay.amount,
+            },
+            &[],
+        )
+    }
+
+    pub fn deleverage(
+        &mut self,
+        sender: &str,
+        max_collateral: utils::coin::Coin,
+        amount_to_repay: utils::coin::Coin,
+    ) -> AnyResult<AppResponse> {
+        let ca = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            ca,
+            &ExecuteMsg::Deleverage {
                 max_collateral,
                 amount_to_repay,
+                collateral_market_id: None,
+                debt_market_id: None,
             },
             &[],
         )
@@ -760,7 +1233,15 @@ impl Suite {
 
     /// Queries configuration from market selected by token
     pub fn query_market_config(&self, token: Token) -> AnyResult<isotonic_market::state::Config> {
-        let market = self.query_market(token)?;
+        self.query_market_config_with_id(token, None)
+    }
+
+    pub fn query_market_config_with_id(
+        &self,
+        token: Token,
+        market_id: impl Into<Option<u32>>,
+    ) -> AnyResult<isotonic_market::state::Config> {
+        let market = self.query_market_with_id(token, market_id)?;
 
         let resp: isotonic_market::state::Config = self
             .app
@@ -791,6 +1272,76 @@ impl Suite {
         Ok(resp)
     }
 
+    pub fn query_account_health(&self, account: &str) -> AnyResult<AccountHealthResponse> {
+        let resp: AccountHealthResponse = self.app.wrap().query_wasm_smart(
+            Addr::unchecked(&self.contract),
+            &QueryMsg::AccountHealth {
+                account: account.to_owned(),
+            },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn query_liquidation_quote(
+        &self,
+        account: &str,
+        repay: utils::coin::Coin,
+        collateral_denom: Token,
+    ) -> AnyResult<LiquidationQuoteResponse> {
+        self.query_liquidation_quote_with_ids(account, repay, None, collateral_denom, None)
+    }
+
+    /// Same as `query_liquidation_quote`, but on markets with given ids
+    pub fn query_liquidation_quote_with_ids(
+        &self,
+        account: &str,
+        repay: utils::coin::Coin,
+        debt_market_id: impl Into<Option<u32>>,
+        collateral_denom: Token,
+        collateral_market_id: impl Into<Option<u32>>,
+    ) -> AnyResult<LiquidationQuoteResponse> {
+        let resp: LiquidationQuoteResponse = self.app.wrap().query_wasm_smart(
+            Addr::unchecked(&self.contract),
+            &QueryMsg::LiquidationQuote {
+                account: account.to_owned(),
+                debt_denom: repay.denom,
+                repay_amount: repay.amount,
+                collateral_denom,
+                debt_market_id: debt_market_id.into(),
+                collateral_market_id: collateral_market_id.into(),
+            },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn query_liquidation_auction(
+        &self,
+        account: &str,
+    ) -> AnyResult<LiquidationAuctionResponse> {
+        let resp: LiquidationAuctionResponse = self.app.wrap().query_wasm_smart(
+            Addr::unchecked(&self.contract),
+            &QueryMsg::LiquidationAuction {
+                account: account.to_owned(),
+            },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn query_simulate_actions(
+        &self,
+        account: &str,
+        actions: Vec<Action>,
+    ) -> AnyResult<SimulateActionsResponse> {
+        let resp: SimulateActionsResponse = self.app.wrap().query_wasm_smart(
+            Addr::unchecked(&self.contract),
+            &QueryMsg::SimulateActions {
+                account: account.to_owned(),
+                actions,
+            },
+        )?;
+        Ok(resp)
+    }
+
     pub fn query_cw20_balance(&self, owner: &str, contract: String) -> StdResult<u128> {
         let balance: BalanceResponse = self.app.wrap().query_wasm_smart(
             contract,
@@ -803,35 +1354,76 @@ impl Suite {
     }
 
     pub fn sudo_adjust_market_id(&mut self, new_market_id: u64) -> AnyResult<AppResponse> {
-        let contract = self.contract.clone();
+        let gov = self.gov_contract.clone();
+        self.propose_param_change(gov.as_str(), ParamChange::MarketId { new_market_id })
+    }
+
+    pub fn sudo_adjust_token_id(&mut self, new_token_id: u64) -> AnyResult<AppResponse> {
+        let gov = self.gov_contract.clone();
+        self.propose_param_change(gov.as_str(), ParamChange::TokenId { new_token_id })
+    }
+
+    pub fn sudo_adjust_common_token(&mut self, new_common_token: Token) -> AnyResult<AppResponse> {
+        let gov = self.gov_contract.clone();
+        self.propose_param_change(gov.as_str(), ParamChange::CommonToken { new_common_token })
+    }
+
+    pub fn fund_emission(
+        &mut self,
+        sender: &str,
+        market: &str,
+        duration: u64,
+        supplier_share: Decimal,
+        funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
         self.app.execute_contract(
-            self.gov_contract.clone(),
-            contract,
-            &ExecuteMsg::AdjustMarketId { new_market_id },
-            &[],
+            Addr::unchecked(sender),
+            self.contract.clone(),
+            &ExecuteMsg::FundEmission {
+                market: market.to_owned(),
+                duration,
+                supplier_share,
+            },
+            funds,
         )
     }
 
-    pub fn sudo_adjust_token_id(&mut self, new_token_id: u64) -> AnyResult<AppResponse> {
-        let contract = self.contract.clone();
+    pub fn emit_rewards(&mut self, sender: &str, markets: &[&str]) -> AnyResult<AppResponse> {
         self.app.execute_contract(
-            self.gov_contract.clone(),
-            contract,
-            &ExecuteMsg::AdjustTokenId { new_token_id },
+            Addr::unchecked(sender),
+            self.contract.clone(),
+            &ExecuteMsg::EmitRewards {
+                markets: markets.iter().map(|m| m.to_string()).collect(),
+            },
             &[],
         )
     }
 
-    pub fn sudo_adjust_common_token(&mut self, new_common_token: Token) -> AnyResult<AppResponse> {
-        let contract = self.contract.clone();
+    pub fn claim_rewards(&mut self, sender: &str, markets: &[&str]) -> AnyResult<AppResponse> {
         self.app.execute_contract(
-            self.gov_contract.clone(),
-            contract,
-            &ExecuteMsg::AdjustCommonToken { new_common_token },
+            Addr::unchecked(sender),
+            self.contract.clone(),
+            &ExecuteMsg::ClaimRewards {
+                markets: markets.iter().map(|m| m.to_string()).collect(),
+            },
             &[],
         )
     }
 
+    pub fn query_emission(&self, market: &str) -> AnyResult<EmissionResponse> {
+        let resp: EmissionResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::Emission {
+                market: market.to_owned(),
+            },
+        )?;
+        Ok(resp)
+    }
+
+    pub fn query_native_balance(&self, owner: &str, denom: &str) -> AnyResult<u128> {
+        Ok(self.app.wrap().query_balance(owner, denom)?.amount.u128())
+    }
+
     pub fn sudo_migrate_market(
         &mut self,
         market: &str,
@@ -848,4 +1440,57 @@ impl Suite {
             &[],
         )
     }
+
+    pub fn migrate_all_markets(
+        &mut self,
+        sender: &str,
+        migrate_msg: MarketMigrateMsg,
+        limit: impl Into<Option<u32>>,
+    ) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            contract,
+            &ExecuteMsg::MigrateAllMarkets {
+                migrate_msg,
+                start_after: None,
+                limit: limit.into(),
+            },
+            &[],
+        )
+    }
+
+    pub fn query_outdated_markets(&self) -> AnyResult<ListOutdatedMarketsResponse> {
+        let response: ListOutdatedMarketsResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::ListOutdatedMarkets {
+                start_after: None,
+                limit: None,
+            },
+        )?;
+        Ok(response)
+    }
+
+    pub fn remove_market(&mut self, sender: &str, market_token: Token) -> AnyResult<AppResponse> {
+        let market = self.query_market(market_token)?.market;
+        let contract = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            contract,
+            &ExecuteMsg::RemoveMarket {
+                market: market.to_string(),
+            },
+            &[],
+        )
+    }
+
+    pub fn query_market_wind_down(&self, token: Token) -> AnyResult<WindDownResponse> {
+        let market = self.query_market(token)?;
+
+        let resp: WindDownResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(market.market, &MarketQueryMsg::WindDown {})?;
+        Ok(resp)
+    }
 }
@@ -0,0 +1,225 @@
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use utils::events::{SettleDeposit, SwapCollateral};
+use utils::tests::{assert_event, event_attribute};
+use utils::token::Token;
+
+use super::suite::{
+    Suite, SuiteBuilder, ACTOR, ATOM, COMMON, DEPOSIT, DEPOSIT_2, GOVERNANCE, JUNO,
+};
+use crate::error::ContractError;
+
+/// JUNO (price 2.0, collateral ratio 0.5) and ATOM (price 0.5, collateral ratio 0.3) markets
+/// with plenty of liquidity, and `ACTOR` with 100k JUNO deposited - 100k of credit line.
+fn swap_suite() -> Suite {
+    swap_suite_with_borrow_limit(Decimal::one())
+}
+
+fn swap_suite_with_borrow_limit(borrow_limit_ratio: Decimal) -> Suite {
+    let common_token = Token::Native(COMMON.to_owned());
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_borrow_limit_ratio(borrow_limit_ratio)
+        .with_funds(DEPOSIT, &[juno.clone().into_coin(10_000_000u128)])
+        .with_funds(DEPOSIT_2, &[atom.clone().into_coin(10_000_000u128)])
+        .with_funds(ACTOR, &[juno.clone().into_coin(100_000u128)])
+        .with_pool(
+            1,
+            (
+                common_token.clone().into_coin(20_000_000u128),
+                juno.clone().into_coin(10_000_000u128),
+            ),
+        )
+        .with_pool(
+            2,
+            (
+                common_token.into_coin(5_000_000u128),
+                atom.clone().into_coin(10_000_000u128),
+            ),
+        )
+        .build();
+
+    suite
+        .create_market_quick(GOVERNANCE, JUNO, juno.clone(), None, None, None)
+        .unwrap();
+    suite
+        .create_market_quick(
+            GOVERNANCE,
+            ATOM,
+            atom.clone(),
+            Decimal::percent(30),
+            None,
+            None,
+        )
+        .unwrap();
+
+    suite
+        .deposit_tokens_on_market(DEPOSIT, juno.clone().into_coin(10_000_000u128))
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(DEPOSIT_2, atom.into_coin(10_000_000u128))
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(ACTOR, juno.into_coin(100_000u128))
+        .unwrap();
+
+    suite
+}
+
+fn collateral_of(suite: &Suite, token: &Token) -> Uint128 {
+    suite
+        .query_account_health(ACTOR)
+        .unwrap()
+        .markets
+        .into_iter()
+        .find(|m| &m.market_token == token)
+        .map(|m| m.collateral.amount)
+        .unwrap_or_default()
+}
+
+#[test]
+fn swap_to_other_market() {
+    let mut suite = swap_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    // 50k JUNO is 100k common, which is 200k ATOM minus fees and price impact
+    let response = suite
+        .swap_collateral(
+            ACTOR,
+            juno.clone().into_coin(50_000u128),
+            atom.clone(),
+            190_000u128,
+        )
+        .unwrap();
+
+    assert_eq!(collateral_of(&suite, &juno), Uint128::new(50_000));
+    let atom_collateral = collateral_of(&suite, &atom);
+    assert!(atom_collateral > Uint128::new(190_000));
+    assert!(atom_collateral < Uint128::new(200_000));
+
+    assert_event(
+        &response.events,
+        &SwapCollateral {
+            sender: Addr::unchecked(ACTOR),
+            account: Addr::unchecked(ACTOR),
+            amount: juno.clone().amount(50_000u128),
+            to_denom: atom.clone(),
+            min_receive: Uint128::new(190_000),
+        },
+    );
+    let atom_market = suite.query_market(atom.clone()).unwrap().market;
+    assert_eq!(
+        event_attribute::<SettleDeposit>(&response.events, "market"),
+        Some(atom_market.to_string())
+    );
+    assert_eq!(
+        event_attribute::<SettleDeposit>(&response.events, "amount"),
+        Some(format!("{}{}", atom_collateral, ATOM))
+    );
+
+    // Nothing is left on the credit agency
+    let ca = suite.credit_agency();
+    assert_eq!(suite.query_native_balance(ca.as_str(), JUNO).unwrap(), 0);
+    assert_eq!(suite.query_native_balance(ca.as_str(), ATOM).unwrap(), 0);
+}
+
+#[test]
+fn min_receive_not_reached() {
+    let mut suite = swap_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    suite
+        .swap_collateral(
+            ACTOR,
+            juno.clone().into_coin(50_000u128),
+            atom.clone(),
+            200_000u128,
+        )
+        .unwrap_err();
+
+    assert_eq!(collateral_of(&suite, &juno), Uint128::new(100_000));
+    assert_eq!(collateral_of(&suite, &atom), Uint128::zero());
+}
+
+#[test]
+fn debt_has_to_stay_covered() {
+    let mut suite = swap_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    // 60k common of debt
+    suite
+        .borrow_tokens_from_market(ACTOR, atom.clone().into_coin(120_000u128))
+        .unwrap();
+
+    // Credit line would drop to less than 0.3 * 200k = 60k
+    let err = suite
+        .swap_collateral(
+            ACTOR,
+            juno.clone().into_coin(100_000u128),
+            atom.clone(),
+            0u128,
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::CollateralSwapOverBorrowLimit { debt, .. } if debt == Uint128::new(60_000)
+    ));
+    assert_eq!(collateral_of(&suite, &juno), Uint128::new(100_000));
+
+    // 80k JUNO left covers the debt on its own
+    suite
+        .swap_collateral(ACTOR, juno.into_coin(20_000u128), atom, 0u128)
+        .unwrap();
+}
+
+#[test]
+fn debt_has_to_stay_under_borrow_limit() {
+    let mut suite = swap_suite_with_borrow_limit(Decimal::percent(80));
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    // 60k common of debt, borrow limit is 80k
+    suite
+        .borrow_tokens_from_market(ACTOR, atom.clone().into_coin(120_000u128))
+        .unwrap();
+
+    // Credit line stays over 70k, but the borrow limit drops under 60k
+    let err = suite
+        .swap_collateral(
+            ACTOR,
+            juno.clone().into_coin(70_000u128),
+            atom.clone(),
+            0u128,
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::CollateralSwapOverBorrowLimit { debt, borrow_limit }
+            if debt == Uint128::new(60_000) && borrow_limit < debt
+    ));
+    assert_eq!(collateral_of(&suite, &juno), Uint128::new(100_000));
+
+    // Borrow limit stays over 63k
+    suite
+        .swap_collateral(ACTOR, juno.into_coin(50_000u128), atom, 0u128)
+        .unwrap();
+}
+
+#[test]
+fn same_denom() {
+    let mut suite = swap_suite();
+    let juno = Token::Native(JUNO.to_owned());
+
+    let err = suite
+        .swap_collateral(ACTOR, juno.clone().into_coin(10_000u128), juno, 0u128)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::SwapSameDenom(JUNO.to_owned()),
+        err.downcast().unwrap()
+    );
+}
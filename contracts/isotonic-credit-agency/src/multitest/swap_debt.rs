@@ -0,0 +1,205 @@
+use cosmwasm_std::Uint128;
+use utils::events::{SettleDebtSwap, SwapDebt};
+use utils::tests::event_attribute;
+use utils::token::Token;
+
+use super::suite::{
+    Suite, SuiteBuilder, ACTOR, ATOM, COMMON, DEPOSIT, DEPOSIT_2, GOVERNANCE, JUNO,
+};
+use crate::error::ContractError;
+
+/// JUNO (price 2.0) and ATOM (price 0.5) markets with plenty of liquidity, and `ACTOR` with
+/// 100k JUNO deposited - 100k of credit line - and 20k JUNO (40k common) of debt.
+fn swap_suite() -> Suite {
+    let common_token = Token::Native(COMMON.to_owned());
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_funds(DEPOSIT, &[juno.clone().into_coin(10_000_000u128)])
+        .with_funds(DEPOSIT_2, &[atom.clone().into_coin(10_000_000u128)])
+        .with_funds(ACTOR, &[juno.clone().into_coin(100_000u128)])
+        .with_pool(
+            1,
+            (
+                common_token.clone().into_coin(20_000_000u128),
+                juno.clone().into_coin(10_000_000u128),
+            ),
+        )
+        .with_pool(
+            2,
+            (
+                common_token.into_coin(5_000_000u128),
+                atom.clone().into_coin(10_000_000u128),
+            ),
+        )
+        .build();
+
+    for token in [&juno, &atom] {
+        suite
+            .create_market_quick(GOVERNANCE, &token.denom(), token.clone(), None, None, None)
+            .unwrap();
+    }
+
+    suite
+        .deposit_tokens_on_market(DEPOSIT, juno.clone().into_coin(10_000_000u128))
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(DEPOSIT_2, atom.into_coin(10_000_000u128))
+        .unwrap();
+    suite
+        .deposit_tokens_on_market(ACTOR, juno.clone().into_coin(100_000u128))
+        .unwrap();
+    suite
+        .borrow_tokens_from_market(ACTOR, juno.into_coin(20_000u128))
+        .unwrap();
+
+    suite
+}
+
+fn debt_of(suite: &Suite, token: &Token) -> Uint128 {
+    suite
+        .query_account_health(ACTOR)
+        .unwrap()
+        .markets
+        .into_iter()
+        .find(|m| &m.market_token == token)
+        .map(|m| m.debt.amount)
+        .unwrap_or_default()
+}
+
+#[test]
+fn refinance_whole_debt() {
+    let mut suite = swap_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    // 20k JUNO is 40k common, which is 80k ATOM plus fees and price impact. Amount over the debt
+    // is capped.
+    let response = suite
+        .swap_debt(ACTOR, juno.clone(), atom.clone(), 30_000u128, 82_000u128)
+        .unwrap();
+
+    assert_eq!(debt_of(&suite, &juno), Uint128::zero());
+    let atom_debt = debt_of(&suite, &atom);
+    assert!(atom_debt > Uint128::new(80_000));
+    assert!(atom_debt < Uint128::new(82_000));
+
+    assert_eq!(
+        event_attribute::<SwapDebt>(&response.events, "amount"),
+        Some(format!("20000{}", JUNO))
+    );
+    assert_eq!(
+        event_attribute::<SwapDebt>(&response.events, "borrowed"),
+        Some(format!("{}{}", atom_debt, ATOM))
+    );
+    assert_eq!(
+        event_attribute::<SettleDebtSwap>(&response.events, "account"),
+        Some(ACTOR.to_owned())
+    );
+
+    // Nothing is left on the credit agency
+    let ca = suite.credit_agency();
+    assert_eq!(suite.query_native_balance(ca.as_str(), JUNO).unwrap(), 0);
+    assert_eq!(suite.query_native_balance(ca.as_str(), ATOM).unwrap(), 0);
+}
+
+#[test]
+fn refinance_part_of_debt() {
+    let mut suite = swap_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    suite
+        .swap_debt(ACTOR, juno.clone(), atom.clone(), 5_000u128, 21_000u128)
+        .unwrap();
+
+    // Swap may buy a bit more than requested, which is repaid as well
+    let juno_debt = debt_of(&suite, &juno);
+    assert!(juno_debt <= Uint128::new(15_000));
+    assert!(juno_debt > Uint128::new(14_990));
+    assert!(debt_of(&suite, &atom) > Uint128::new(20_000));
+
+    let ca = suite.credit_agency();
+    assert_eq!(suite.query_native_balance(ca.as_str(), JUNO).unwrap(), 0);
+}
+
+#[test]
+fn surplus_over_debt_is_returned() {
+    let mut suite = swap_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    // `ACTOR` holds the 20k JUNO borrowed in the suite
+    assert_eq!(suite.query_native_balance(ACTOR, JUNO).unwrap(), 20_000);
+
+    suite
+        .swap_debt(ACTOR, juno.clone(), atom, 20_000u128, 82_000u128)
+        .unwrap();
+
+    // Whole debt is repaid, whatever was bought over it goes back to `ACTOR`
+    assert_eq!(debt_of(&suite, &juno), Uint128::zero());
+    assert!(suite.query_native_balance(ACTOR, JUNO).unwrap() >= 20_000);
+    let ca = suite.credit_agency();
+    assert_eq!(suite.query_native_balance(ca.as_str(), JUNO).unwrap(), 0);
+}
+
+#[test]
+fn new_debt_over_limit() {
+    let mut suite = swap_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    let err = suite
+        .swap_debt(ACTOR, juno.clone(), atom.clone(), 20_000u128, 80_000u128)
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::NewDebtOverLimit { max_new_debt, .. } if max_new_debt == Uint128::new(80_000)
+    ));
+
+    assert_eq!(debt_of(&suite, &juno), Uint128::new(20_000));
+    assert_eq!(debt_of(&suite, &atom), Uint128::zero());
+}
+
+#[test]
+fn debt_has_to_stay_under_borrow_limit() {
+    let mut suite = swap_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    // Whole borrow limit is used - 50k JUNO is 100k common
+    suite
+        .borrow_tokens_from_market(ACTOR, juno.clone().into_coin(30_000u128))
+        .unwrap();
+
+    // Swap fees increase the debt
+    let err = suite
+        .swap_debt(ACTOR, juno, atom, 50_000u128, 210_000u128)
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast().unwrap(),
+        ContractError::DebtSwapOverBorrowLimit { borrow_limit, .. } if borrow_limit == Uint128::new(100_000)
+    ));
+}
+
+#[test]
+fn invalid_params() {
+    let mut suite = swap_suite();
+    let juno = Token::Native(JUNO.to_owned());
+    let atom = Token::Native(ATOM.to_owned());
+
+    let err = suite
+        .swap_debt(ACTOR, juno.clone(), juno.clone(), 1_000u128, 1_000u128)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::SwapSameDenom(JUNO.to_owned()),
+        err.downcast().unwrap()
+    );
+
+    let err = suite
+        .swap_debt(ACTOR, atom.clone(), juno, 1_000u128, 1_000u128)
+        .unwrap_err();
+    assert_eq!(ContractError::NoDebt(atom.denom()), err.downcast().unwrap());
+}
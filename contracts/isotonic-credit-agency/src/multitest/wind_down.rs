@@ -0,0 +1,159 @@
+use cosmwasm_std::{Decimal, Uint128};
+use isotonic_market::ContractError as MarketContractError;
+use utils::token::Token;
+
+use super::suite::{SuiteBuilder, ACTOR, COMMON, DEBTOR, GOVERNANCE, JUNO, LIQUIDATOR};
+use crate::error::ContractError;
+use crate::msg::ParamChange;
+
+const DURATION: u64 = 3600;
+
+#[test]
+fn forced_liquidation_after_wind_down() {
+    let common_token = Token::Native(COMMON.to_owned());
+    let juno = Token::Native(JUNO.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_funds(LIQUIDATOR, &[juno.clone().into_coin(5_000u128)])
+        .with_funds(DEBTOR, &[juno.clone().into_coin(600u128)])
+        .with_liquidation_price(Decimal::percent(92))
+        .with_pool(
+            1,
+            (
+                common_token.into_coin(100u128),
+                juno.clone().into_coin(100u128),
+            ),
+        )
+        .build();
+    suite
+        .create_market_quick(
+            GOVERNANCE,
+            JUNO,
+            juno.clone(),
+            Decimal::percent(80),
+            None,
+            None,
+        )
+        .unwrap();
+
+    suite
+        .deposit_tokens_on_market(DEBTOR, juno.clone().into_coin(500u128))
+        .unwrap();
+    suite
+        .borrow_tokens_from_market(DEBTOR, juno.clone().into_coin(200u128))
+        .unwrap();
+
+    // Healthy account cannot be liquidated
+    let err = suite
+        .liquidate(
+            LIQUIDATOR,
+            DEBTOR,
+            &[juno.clone().into_coin(100u128).try_into().unwrap()],
+            juno.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::LiquidationNotAllowed {},
+        err.downcast().unwrap()
+    );
+
+    let market = suite.query_market(juno.clone()).unwrap().market;
+    suite
+        .propose_param_change(
+            GOVERNANCE,
+            ParamChange::WindDownMarket {
+                market: market.to_string(),
+                duration: DURATION,
+                force_liquidation: true,
+            },
+        )
+        .unwrap();
+    let wind_down = suite.query_market_wind_down(juno.clone()).unwrap();
+    assert!(wind_down.wind_down.is_some());
+    assert!(!wind_down.force_liquidation);
+
+    let err = suite
+        .deposit_tokens_on_market(DEBTOR, juno.clone().into_coin(100u128))
+        .unwrap_err();
+    assert_eq!(
+        MarketContractError::MarketWindingDown {},
+        err.downcast().unwrap()
+    );
+
+    suite.advance_seconds(DURATION);
+    let wind_down = suite.query_market_wind_down(juno.clone()).unwrap();
+    assert_eq!(wind_down.collateral_ratio, Decimal::zero());
+    assert!(wind_down.force_liquidation);
+
+    let debt_before = suite.query_total_credit_line(DEBTOR).unwrap().debt;
+    suite
+        .liquidate(
+            LIQUIDATOR,
+            DEBTOR,
+            &[juno.clone().into_coin(100u128).try_into().unwrap()],
+            juno,
+        )
+        .unwrap();
+    let debt_after = suite.query_total_credit_line(DEBTOR).unwrap().debt;
+    assert!(debt_after.amount < debt_before.amount);
+}
+
+#[test]
+fn remove_empty_market() {
+    let juno = Token::Native(JUNO.to_owned());
+    let mut suite = SuiteBuilder::new()
+        .with_gov(GOVERNANCE)
+        .with_funds(ACTOR, &[juno.clone().into_coin(100u128)])
+        .build();
+    suite
+        .create_market_quick(GOVERNANCE, JUNO, juno.clone(), None, None, None)
+        .unwrap();
+    let old_market = suite.query_market(juno.clone()).unwrap().market;
+    suite
+        .deposit_tokens_on_market(ACTOR, juno.clone().into_coin(100u128))
+        .unwrap();
+
+    let err = suite.remove_market(ACTOR, juno.clone()).unwrap_err();
+    assert_eq!(
+        ContractError::MarketNotWindingDown(JUNO.to_owned()),
+        err.downcast().unwrap()
+    );
+
+    suite
+        .propose_param_change(
+            GOVERNANCE,
+            ParamChange::WindDownMarket {
+                market: old_market.to_string(),
+                duration: DURATION,
+                force_liquidation: false,
+            },
+        )
+        .unwrap();
+
+    let err = suite.remove_market(ACTOR, juno.clone()).unwrap_err();
+    assert_eq!(
+        ContractError::MarketNotEmpty(JUNO.to_owned()),
+        err.downcast().unwrap()
+    );
+
+    suite
+        .withdraw_tokens_from_market(ACTOR, juno.clone().into_coin(100u128))
+        .unwrap();
+    assert_eq!(
+        suite
+            .query_tokens_balance(juno.clone(), ACTOR)
+            .unwrap()
+            .collateral
+            .amount,
+        Uint128::zero()
+    );
+    suite.remove_market(ACTOR, juno.clone()).unwrap();
+    suite.query_market(juno.clone()).unwrap_err();
+
+    // The token is free for a new market
+    suite
+        .create_market_quick(GOVERNANCE, JUNO, juno.clone(), None, None, None)
+        .unwrap();
+    assert_ne!(suite.query_market(juno).unwrap().market, old_market);
+}
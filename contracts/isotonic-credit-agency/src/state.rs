@@ -3,9 +3,11 @@ use std::collections::BTreeSet;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Decimal};
+use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
 use cw_storage_plus::{Item, Map};
-use utils::token::Token;
+use utils::{interest::Interest, timelock::Timelock, token::Token};
+
+use crate::msg::{ParamChange, StewardChange};
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema, Debug)]
 pub struct Config {
@@ -26,34 +28,199 @@ pub struct Config {
     /// This is used to prevent borrowers from being liquidated (almost) immediately after borrowing,
     /// because they maxed out their credit limit.
     pub borrow_limit_ratio: Decimal,
+    /// If set, liquidation discount is auctioned instead of using fixed `liquidation_price`
+    #[serde(default)]
+    pub liquidation_auction: Option<LiquidationAuction>,
+    /// Portion of the repaid value charged by `Deleverage`, paid in collateral to the
+    /// governance contract
+    #[serde(default)]
+    pub deleverage_fee: Decimal,
+    /// Time in seconds between proposing a parameter change and executing it
+    #[serde(default)]
+    pub param_change_delay: u64,
+    /// If set, can adjust risk parameters of markets within its bounds without the delay
+    #[serde(default)]
+    pub risk_steward: Option<RiskSteward>,
+}
+
+impl Config {
+    /// The lowest liquidation price which may be applied to any account
+    pub fn min_liquidation_price(&self) -> Decimal {
+        match &self.liquidation_auction {
+            Some(auction) => Decimal::one() - auction.max_discount,
+            None => self.liquidation_price,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema, Debug)]
+/// Dutch auction of the liquidation discount. Once the account becomes liquidatable, the discount
+/// on its collateral grows linearly from `min_discount` to `max_discount` over `duration` seconds.
+pub struct LiquidationAuction {
+    pub min_discount: Decimal,
+    pub max_discount: Decimal,
+    /// Duration of the auction in seconds
+    pub duration: u64,
+}
+
+impl LiquidationAuction {
+    /// Liquidation price (1 - discount) at `now` for the auction started at `start`
+    pub fn liquidation_price(&self, start: Timestamp, now: Timestamp) -> Decimal {
+        let elapsed = now
+            .seconds()
+            .saturating_sub(start.seconds())
+            .min(self.duration);
+        let discount = self.min_discount
+            + (self.max_discount - self.min_discount) * Decimal::from_ratio(elapsed, self.duration);
+        Decimal::one() - discount
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema, Debug)]
+/// Secondary role which can adjust market caps, interest curves and collateral ratios through
+/// `StewardAdjust`, as long as new values stay within `bounds` set by the governance
+pub struct RiskSteward {
+    pub address: Addr,
+    pub bounds: StewardBounds,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema, Debug)]
+pub struct StewardBounds {
+    pub min_collateral_ratio: Decimal,
+    pub max_collateral_ratio: Decimal,
+    /// Highest market cap the steward can set, valued in the common token at the current market
+    /// price so the bound means the same in markets of different tokens. The steward cannot
+    /// remove the cap.
+    pub max_market_cap_common: Uint128,
+    /// Highest interest rate at full utilisation the steward can set
+    pub max_interest_rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema, Debug)]
+/// Entry of the steward audit log
+pub struct StewardAction {
+    pub steward: Addr,
+    pub market: Addr,
+    pub change: StewardChange,
+    pub time: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema, Debug)]
+/// Governance transfer waiting for `AcceptGovernance`
+pub struct PendingGovernance {
+    pub new_governance: Addr,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema, Debug)]
+/// Risk parameters approved by the governance for markets anyone can create with
+/// `CreateMarketFromTemplate`
+pub struct MarketTemplate {
+    pub interest_rate: Interest,
+    /// Interest charge period in seconds
+    pub interest_charge_period: u64,
+    /// Range the creator picks the collateral ratio of the market from
+    pub min_collateral_ratio: Decimal,
+    pub max_collateral_ratio: Decimal,
+    pub reserve_factor: Decimal,
+    /// Cap on total number of tokens deposited into the market
+    pub market_cap: Uint128,
+    /// Highest credit line, in common token, the collateral of the market gives a single account
+    /// while the market is in isolation
+    pub isolated_credit_cap: Uint128,
+    /// Price oracle used by the markets, the Wynd DEX pool of the market token has to be
+    /// registered in it
+    pub price_oracle: Addr,
 }
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema, Debug)]
-/// Possible states of a market
+/// State of a market. Markets are created with `WasmMsg::Instantiate2`, so their address is
+/// known as soon as the creation is requested.
 pub enum MarketState {
-    /// Represents a maket that is being created.
-    Instantiating,
-    /// Represents a market that has already been created.
-    Ready(Addr),
+    Ready(Addr, MarketCode),
 }
 
 impl MarketState {
-    pub fn to_addr(self) -> Option<Addr> {
+    pub fn to_addr(self) -> Addr {
         match self {
-            MarketState::Instantiating => None,
-            MarketState::Ready(addr) => Some(addr),
+            MarketState::Ready(addr, _) => addr,
         }
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema, Debug)]
+/// Code the market runs, updated when the market is created or migrated by the credit agency
+pub struct MarketCode {
+    pub code_id: u64,
+    /// Contract version from cw2
+    pub version: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema, Debug)]
+/// Reward budget of a single market, released linearly until `end`
+pub struct Emission {
+    /// Amount of `reward_token` not yet emitted
+    pub remaining: Uint128,
+    /// Time of the last emission
+    pub last_emitted: Timestamp,
+    /// Time at which the whole `remaining` amount is released
+    pub end: Timestamp,
+    /// Portion of emitted rewards going to cToken holders, rest goes to borrowers (0 <= x <= 1)
+    pub supplier_share: Decimal,
+}
+
+impl Emission {
+    /// Releases the part of `remaining` accrued since the last emission and moves
+    /// `last_emitted` forward. Returns the released amount.
+    pub fn release(&mut self, now: Timestamp) -> Uint128 {
+        if now <= self.last_emitted {
+            return Uint128::zero();
+        }
+
+        let released = if now >= self.end {
+            self.remaining
+        } else {
+            let elapsed = now.seconds() - self.last_emitted.seconds();
+            let period = self.end.seconds() - self.last_emitted.seconds();
+            self.remaining.multiply_ratio(elapsed, period)
+        };
+
+        self.remaining -= released;
+        self.last_emitted = now;
+        released
+    }
+}
+
 pub const CONFIG: Item<Config> = Item::new("config");
-/// A map of reply_id -> market_token, used to tell which base asset
-/// a given instantiating contract will handle
-pub const REPLY_IDS: Map<u64, Token> = Map::new("reply_ids");
+/// A map of reply_id -> (market_token, market_id), used to tell which market
+/// a given migrated contract is
+pub const REPLY_IDS: Map<u64, (Token, u32)> = Map::new("reply_ids");
 /// The next unused reply ID
 pub const NEXT_REPLY_ID: Item<u64> = Item::new("next_reply_id");
-/// A map of market asset -> market contract address
-pub const MARKETS: Map<&Token, MarketState> = Map::new("market");
+/// Market used when only the market asset is given
+pub const DEFAULT_MARKET_ID: u32 = 0;
+/// A map of (market asset, market id) -> market contract address. An asset may have several
+/// markets, eg. with different risk parameters.
+pub const MARKETS: Map<(&Token, u32), MarketState> = Map::new("markets");
+/// Salt nonce of the next market of (market asset, market id), bumped when a market is removed
+/// so the new market gets a new address
+pub const MARKET_NONCES: Map<(&Token, u32), u32> = Map::new("market_nonces");
+/// Templates for `CreateMarketFromTemplate`, by template name
+pub const MARKET_TEMPLATES: Map<&str, MarketTemplate> = Map::new("market_templates");
+/// Credit line cap of markets in isolation, by market address. Removed when the governance
+/// promotes the market.
+pub const ISOLATED_MARKETS: Map<&Addr, Uint128> = Map::new("isolated_markets");
 /// A set of "entered markets" for each account, as in markets in which the account is
-/// actively participating.
+/// actively participating. Accounts exiting their last market are removed.
 pub const ENTERED_MARKETS: Map<&Addr, BTreeSet<Addr>> = Map::new("entered_martkets");
+/// Reward emission schedule of each market, by market address
+pub const EMISSIONS: Map<&Addr, Emission> = Map::new("emissions");
+/// Start of the liquidation auction of each liquidatable account. Restarted by every liquidation.
+pub const LIQUIDATION_AUCTIONS: Map<&Addr, Timestamp> = Map::new("liquidation_auctions");
+/// Parameter changes waiting for `param_change_delay` to pass
+pub const PARAM_CHANGES: Timelock<ParamChange> =
+    Timelock::new("param_changes", "next_param_change_id");
+/// Audit log of all `StewardAdjust` actions, by consecutive ids
+pub const STEWARD_LOG: Map<u64, StewardAction> = Map::new("steward_log");
+/// The next unused steward log id
+pub const NEXT_STEWARD_ACTION_ID: Item<u64> = Item::new("next_steward_action_id");
+pub const PENDING_GOVERNANCE: Item<PendingGovernance> = Item::new("pending_governance");
@@ -1,27 +1,31 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, Coin as StdCoin, Decimal, Deps, DepsMut, Env, MessageInfo, Reply,
-    Response, StdError, StdResult, SubMsg, Timestamp, Uint128, WasmMsg,
+    to_binary, Addr, Binary, Coin as StdCoin, Decimal, Deps, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult, Timestamp, Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
 
-use utils::wyndex::{ExecuteMsg::ExecuteSwapOperations, SwapOperation};
+use utils::wyndex::ExecuteMsg::ExecuteSwapOperations;
 
 use crate::error::ContractError;
 use crate::msg::{
-    ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, QueryTotalCreditLine, TotalDebtResponse,
-    TransferableAmountResponse,
+    DebtRewardsResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, PendingGovernanceResponse,
+    QueryMsg, QueryTotalCreditLine, TotalDebtResponse, TransferableAmountResponse,
+};
+use crate::state::{
+    debt, Config, CONFIG, CTOKEN_SALT_SEED, PENDING_GOVERNANCE, RISK_CONFIG, WIND_DOWN,
 };
-use crate::state::{debt, Config, CONFIG};
 
+use utils::events::{self, json};
+use utils::instantiate2::{predict_address, token_salt};
 use utils::token::Token;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:isotonic-market";
-const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
-
-const CTOKEN_INIT_REPLY_ID: u64 = 1;
+/// Version of the market code built with this crate, recorded by the credit agency for the
+/// markets it creates
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -39,18 +43,21 @@ pub fn instantiate(
         controller: env.contract.address.to_string(),
         distributed_token: msg.distributed_token.clone(),
     };
-    let ctoken_instantiate = WasmMsg::Instantiate {
+    let salt = token_salt(&msg.market_token, CTOKEN_SALT_SEED);
+    let ctoken_contract =
+        predict_address(deps.as_ref(), msg.token_id, &env.contract.address, &salt)?;
+    let ctoken_instantiate = WasmMsg::Instantiate2 {
         admin: Some(env.contract.address.to_string()),
         code_id: msg.token_id,
         msg: to_binary(&ctoken_msg)?,
         funds: vec![],
         label: format!("ctoken_contract_{}", env.contract.address),
+        salt,
     };
     debt::init(deps.storage)?;
 
     let cfg = Config {
-        // those will be overwritten in a response
-        ctoken_contract: Addr::unchecked(""),
+        ctoken_contract: ctoken_contract.clone(),
         governance_contract: deps.api.addr_validate(&msg.gov_contract)?,
         name: msg.name,
         symbol: msg.symbol,
@@ -68,56 +75,15 @@ pub fn instantiate(
         credit_agency: info.sender.clone(),
         reserve_factor: msg.reserve_factor,
         borrow_limit_ratio: msg.borrow_limit_ratio,
+        param_change_delay: msg.param_change_delay,
     };
     CONFIG.save(deps.storage, &cfg)?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
         .add_attribute("owner", info.sender)
-        .add_submessage(SubMsg::reply_on_success(
-            ctoken_instantiate,
-            CTOKEN_INIT_REPLY_ID,
-        )))
-}
-
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
-    match msg.id {
-        CTOKEN_INIT_REPLY_ID => reply::token_instantiate_reply(deps, env, msg),
-        _ => Err(ContractError::UnrecognisedReply(msg.id)),
-    }
-}
-
-mod reply {
-    use super::*;
-
-    use cw_utils::parse_reply_instantiate_data;
-
-    pub fn token_instantiate_reply(
-        deps: DepsMut,
-        _env: Env,
-        msg: Reply,
-    ) -> Result<Response, ContractError> {
-        let id = msg.id;
-        let res =
-            parse_reply_instantiate_data(msg).map_err(|err| ContractError::ReplyParseFailure {
-                id,
-                err: err.to_string(),
-            })?;
-
-        let mut response = Response::new();
-
-        let addr = deps.api.addr_validate(&res.contract_address)?;
-        if id == CTOKEN_INIT_REPLY_ID {
-            CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
-                config.ctoken_contract = addr.clone();
-                response = Response::new().add_attribute("ctoken", addr);
-                Ok(config)
-            })?;
-        }
-
-        Ok(response)
-    }
+        .add_attribute("ctoken", ctoken_contract)
+        .add_message(ctoken_instantiate))
 }
 
 /// Execution entry point
@@ -132,7 +98,13 @@ pub fn execute(
     match msg {
         Deposit {} => {
             let received_tokens = require_single_denom(&info.funds)?;
-            execute::deposit(deps, env, info.sender.into_string(), received_tokens)
+            execute::deposit(
+                deps,
+                env,
+                info.sender.clone(),
+                info.sender.into_string(),
+                received_tokens,
+            )
         }
         Withdraw { amount } => execute::withdraw(deps, env, info, amount),
         Borrow { amount } => execute::borrow(deps, env, info, amount),
@@ -145,6 +117,19 @@ pub fn execute(
             let repay_tokens = require_single_denom(&info.funds)?;
             execute::repay_to(deps, env, info.sender, repay_tokens, account)
         }
+        DepositTo { account } => {
+            let account = deps.api.addr_validate(&account)?;
+            let received_tokens = require_single_denom(&info.funds)?;
+            execute::deposit_to(deps, env, info.sender, account, received_tokens)
+        }
+        WithdrawFrom { account, amount } => {
+            let account = deps.api.addr_validate(&account)?;
+            execute::withdraw_from(deps, env, info.sender, account, amount)
+        }
+        BorrowFor { account, amount } => {
+            let account = deps.api.addr_validate(&account)?;
+            execute::borrow_for(deps, env, info.sender, account, amount)
+        }
         TransferFrom {
             source,
             destination,
@@ -169,6 +154,20 @@ pub fn execute(
         AdjustCommonToken { new_token } => {
             execute::adjust_common_token(deps, info.sender, new_token)
         }
+        DistributeDebtRewards {} => {
+            let rewards = info
+                .funds
+                .iter()
+                .map(|coin| {
+                    utils::coin::Coin::new(coin.amount.u128(), Token::Native(coin.denom.clone()))
+                })
+                .collect();
+            execute::distribute_debt_rewards(deps, info.sender, rewards)
+        }
+        ClaimRewards { account } => {
+            let account = deps.api.addr_validate(&account)?;
+            execute::claim_rewards(deps, info, account)
+        }
         // TODO: should allow cw20?
         SwapWithdrawFrom {
             account,
@@ -181,16 +180,30 @@ pub fn execute(
             buy,
             sell_limit,
         ),
-        AdjustCollateralRatio { new_ratio } => {
-            restricted::adjust_collateral_ratio(deps, info, new_ratio)
+        ProposeParamChange { change } => {
+            restricted::propose_param_change(deps, env, info, change)
+        }
+        ExecuteParamChange { id } => restricted::execute_param_change(deps, env, info, id),
+        CancelParamChange { id } => restricted::cancel_param_change(deps, info, id),
+        StewardParamChange { change } => {
+            restricted::steward_param_change(deps, env, info, change)
         }
-        AdjustReserveFactor { new_factor } => {
-            restricted::adjust_reserve_factor(deps, info, new_factor)
+        ProposeNewGovernance { new_governance } => {
+            restricted::propose_new_governance(deps, info, new_governance)
         }
-        AdjustPriceOracle { new_oracle } => restricted::adjust_price_oracle(deps, info, new_oracle),
-        AdjustMarketCap { new_cap } => restricted::adjust_market_cap(deps, info, new_cap),
-        AdjustInterestRates { new_interest_rates } => {
-            restricted::adjust_interest_rates(deps, env, info, new_interest_rates)
+        AcceptGovernance {} => restricted::accept_governance(deps, info),
+        WindDown {
+            duration,
+            force_liquidation,
+        } => restricted::wind_down(deps, env, info, duration, force_liquidation),
+        AdjustCTokenMarketing {
+            project,
+            description,
+            marketing,
+        } => restricted::adjust_ctoken_marketing(deps, info, project, description, marketing),
+        AdjustCTokenLogo { logo } => restricted::adjust_ctoken_logo(deps, info, logo),
+        AddCTokenDistributedToken { token } => {
+            restricted::add_ctoken_distributed_token(deps, info, token)
         }
         Receive(msg) => execute::receive_cw20_message(deps, env, info, msg),
     }
@@ -263,6 +276,17 @@ mod cr_utils {
         Ok(available_local)
     }
 
+    /// Amount of collateral in base tokens seized for `amount` of common tokens repaid during
+    /// liquidation. Used by both `TransferFrom` and `QueryMsg::LiquidationSeize`.
+    pub fn seized_collateral(
+        deps: Deps,
+        amount: Uint128,
+        liquidation_price: Decimal,
+    ) -> Result<Uint128, ContractError> {
+        let price_rate = query::price_market_local_per_common(deps)?.rate_sell_per_buy;
+        divide(amount, price_rate * liquidation_price).map_err(|_| ContractError::ZeroPrice {})
+    }
+
     /// Helper that determines if an address can borrow the specified amount.
     pub fn can_borrow(
         deps: Deps,
@@ -274,12 +298,32 @@ mod cr_utils {
         Ok(amount <= available)
     }
 
+    /// Collateral ratio of the market, lowered towards zero while it winds down
+    pub fn collateral_ratio(
+        deps: Deps,
+        env: &Env,
+        config: &Config,
+    ) -> Result<Decimal, ContractError> {
+        let ratio = match WIND_DOWN.may_load(deps.storage)? {
+            Some(wind_down) => wind_down.collateral_ratio(env.block.time),
+            None => config.collateral_ratio,
+        };
+        Ok(ratio)
+    }
+
     /// Helper returning amount of tokens available to transfer/withdraw
     pub fn transferable_amount(
         deps: Deps,
+        env: &Env,
         config: &Config,
         account: impl Into<String>,
     ) -> Result<Uint128, ContractError> {
+        let collateral_ratio = collateral_ratio(deps, env, config)?;
+        if collateral_ratio.is_zero() && WIND_DOWN.may_load(deps.storage)?.is_some() {
+            // Collateral doesn't back any debt anymore
+            return Ok(Uint128::MAX);
+        }
+
         let account = account.into();
         let credit: CreditLineResponse = deps.querier.query_wasm_smart(
             &config.credit_agency,
@@ -290,7 +334,7 @@ mod cr_utils {
         let credit = credit.validate(&config.common_token.clone())?;
 
         let available = query_borrowable_tokens_with_creditvalues(deps, &credit)?;
-        let mut can_transfer = divide(available, config.collateral_ratio)
+        let mut can_transfer = divide(available, collateral_ratio)
             .map_err(|_| ContractError::ZeroCollateralRatio {})?;
         if credit.debt.u128() == 0 {
             let multiplier = query_ctoken_multiplier(deps, config)?;
@@ -417,6 +461,17 @@ mod execute {
         }
     }
 
+    // Repaid debt could make the account healthy again, so its liquidation auction is rechecked
+    fn update_liquidation_auction(cfg: &Config, account: &Addr) -> StdResult<WasmMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: cfg.credit_agency.to_string(),
+            msg: to_binary(&CreditAgencyExecuteMsg::UpdateLiquidationAuction {
+                account: account.to_string(),
+            })?,
+            funds: vec![],
+        })
+    }
+
     // Register the account into Credit Agency as a depositor.
     fn enter_market<T>(cfg: &Config, account: &Addr) -> StdResult<SubMsg<T>> {
         let msg = to_binary(&CreditAgencyExecuteMsg::EnterMarket {
@@ -430,12 +485,20 @@ mod execute {
         }))
     }
 
+    /// Value of `amount` of market tokens in the common token, for events. Missing price doesn't
+    /// fail the action.
+    fn common_value(deps: Deps, cfg: &Config, amount: Uint128) -> Option<Coin> {
+        let price = query::price_market_local_per_common(deps).ok()?;
+        Some(cfg.common_token.amount(amount * price.rate_sell_per_buy))
+    }
+
     /// Handler for `ExecuteMsg::Deposit`
     /// This function checks the validity of sent funds and if they increase the deposit over the
     /// max allowed. Both native and cw20 tokens are managed.
     pub fn deposit(
         mut deps: DepsMut,
         env: Env,
+        sender: Addr,
         address: String,
         received_tokens: utils::coin::Coin,
     ) -> Result<Response, ContractError> {
@@ -444,6 +507,9 @@ mod execute {
         if received_tokens.denom != cfg.market_token {
             return Err(ContractError::InvalidDenom(cfg.market_token.to_string()));
         }
+        if WIND_DOWN.may_load(deps.storage)?.is_some() {
+            return Err(ContractError::MarketWindingDown {});
+        }
 
         let mut response = Response::new();
 
@@ -477,9 +543,19 @@ mod execute {
             funds: vec![],
         });
 
+        let event = events::Deposit {
+            sender,
+            account: address.clone(),
+            amount_common: common_value(deps.as_ref(), &cfg, received_tokens.amount),
+            amount: received_tokens,
+            ctoken_ratio: charge_msgs.ctoken_ratio,
+            debt_ratio: charge_msgs.debt_ratio,
+        };
+
         response = response
             .add_attribute("action", "deposit")
             .add_attribute("sender", address.to_string())
+            .add_event(event.into())
             .add_submessage(wrapped_msg)
             .add_submessage(enter_market(&cfg, &address)?);
         Ok(response)
@@ -487,20 +563,64 @@ mod execute {
 
     /// Handler for `ExecuteMsg::Withdraw`
     pub fn withdraw(
-        mut deps: DepsMut,
+        deps: DepsMut,
         env: Env,
         info: MessageInfo,
         amount: Uint128,
     ) -> Result<Response, ContractError> {
         let cfg = CONFIG.load(deps.storage)?;
 
-        if cr_utils::transferable_amount(deps.as_ref(), &cfg, &info.sender)? < amount {
+        if cr_utils::transferable_amount(deps.as_ref(), &env, &cfg, &info.sender)? < amount {
             return Err(ContractError::CannotWithdraw {
                 account: info.sender.to_string(),
                 amount,
             });
         }
 
+        let response = redeem(deps, env, &cfg, &info.sender, &info.sender, amount)?;
+        Ok(response
+            .add_attribute("action", "withdraw")
+            .add_attribute("sender", info.sender))
+    }
+
+    /// Handler for `ExecuteMsg::WithdrawFrom`
+    pub fn withdraw_from(
+        deps: DepsMut,
+        env: Env,
+        sender: Addr,
+        account: Addr,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        if cfg.credit_agency != sender {
+            return Err(ContractError::RequiresCreditAgency {});
+        }
+
+        let response = redeem(deps, env, &cfg, &account, &sender, amount)?;
+        Ok(response
+            .add_attribute("action", "withdraw_from")
+            .add_attribute("sender", sender)
+            .add_attribute("owner", account))
+    }
+
+    /// Burns cTokens of `account` and sends base asset to `recipient`, which is always the sender
+    /// of the message. Credit line of the account is not checked here.
+    fn redeem(
+        mut deps: DepsMut,
+        env: Env,
+        cfg: &Config,
+        account: &Addr,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        let available = query::available_liquidity(deps.as_ref(), &env, cfg)?;
+        if available < amount {
+            return Err(ContractError::NotEnoughLiquidity {
+                available,
+                needed: amount,
+            });
+        }
+
         let mut response = Response::new();
 
         // Create rebase messagess for tokens based on interest and supply
@@ -511,7 +631,7 @@ mod execute {
 
         // Burn the C tokens
         let burn_msg = to_binary(&isotonic_token::msg::ExecuteMsg::BurnBaseFrom {
-            owner: info.sender.to_string(),
+            owner: account.to_string(),
             amount,
         })?;
         let wrapped_msg = SubMsg::new(WasmMsg::Execute {
@@ -520,20 +640,45 @@ mod execute {
             funds: vec![],
         });
 
-        // Send the base assets from contract to lender
-        let send_msg = cfg.market_token.send_msg(&info.sender, amount)?;
+        // Send the base assets from contract to the recipient
+        let send_msg = cfg.market_token.send_msg(recipient, amount)?;
+
+        let event = events::Withdraw {
+            sender: recipient.clone(),
+            account: account.clone(),
+            recipient: recipient.clone(),
+            amount: cfg.market_token.amount(amount),
+            amount_common: common_value(deps.as_ref(), cfg, amount),
+            ctoken_ratio: charge_msgs.ctoken_ratio,
+            debt_ratio: charge_msgs.debt_ratio,
+        };
 
         response = response
-            .add_attribute("action", "withdraw")
-            .add_attribute("sender", info.sender)
+            .add_event(event.into())
             .add_submessage(wrapped_msg)
             .add_message(send_msg);
         Ok(response)
     }
 
+    /// Handler for `ExecuteMsg::DepositTo`
+    pub fn deposit_to(
+        deps: DepsMut,
+        env: Env,
+        sender: Addr,
+        account: Addr,
+        received_tokens: utils::coin::Coin,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        if cfg.credit_agency != sender {
+            return Err(ContractError::RequiresCreditAgency {});
+        }
+
+        deposit(deps, env, sender, account.into_string(), received_tokens)
+    }
+
     /// Handler for `ExecuteMsg::Borrow`
     pub fn borrow(
-        mut deps: DepsMut,
+        deps: DepsMut,
         env: Env,
         info: MessageInfo,
         amount: Uint128,
@@ -547,6 +692,54 @@ mod execute {
             });
         }
 
+        let response = lend(deps, env, &cfg, &info.sender, &info.sender, amount)?;
+        Ok(response
+            .add_attribute("action", "borrow")
+            .add_attribute("sender", info.sender))
+    }
+
+    /// Handler for `ExecuteMsg::BorrowFor`
+    pub fn borrow_for(
+        deps: DepsMut,
+        env: Env,
+        sender: Addr,
+        account: Addr,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        if cfg.credit_agency != sender {
+            return Err(ContractError::RequiresCreditAgency {});
+        }
+
+        let response = lend(deps, env, &cfg, &account, &sender, amount)?;
+        Ok(response
+            .add_attribute("action", "borrow_for")
+            .add_attribute("sender", sender)
+            .add_attribute("debtor", account))
+    }
+
+    /// Increases debt of `account` and sends borrowed tokens to `recipient`, which is always the
+    /// sender of the message. Credit line of the account is not checked here.
+    fn lend(
+        mut deps: DepsMut,
+        env: Env,
+        cfg: &Config,
+        account: &Addr,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        if WIND_DOWN.may_load(deps.storage)?.is_some() {
+            return Err(ContractError::MarketWindingDown {});
+        }
+
+        let available = query::available_liquidity(deps.as_ref(), &env, cfg)?;
+        if available < amount {
+            return Err(ContractError::NotEnoughLiquidity {
+                available,
+                needed: amount,
+            });
+        }
+
         let mut response = Response::new();
 
         // Create rebase messagess for tokens based on interest and supply
@@ -555,15 +748,24 @@ mod execute {
             response = response.add_submessages(charge_msgs.messages);
         }
 
-        debt::increase(deps.storage, &info.sender, amount)?;
+        debt::increase(deps.storage, account, amount)?;
+
+        // Sent borrowed tokens to the recipient
+        let send_msg = cfg.market_token.send_msg(recipient, amount)?;
 
-        // Sent tokens to sender's account
-        let send_msg = cfg.market_token.send_msg(&info.sender, amount)?;
+        let event = events::Borrow {
+            sender: recipient.clone(),
+            account: account.clone(),
+            recipient: recipient.clone(),
+            amount: cfg.market_token.amount(amount),
+            amount_common: common_value(deps.as_ref(), cfg, amount),
+            ctoken_ratio: charge_msgs.ctoken_ratio,
+            debt_ratio: charge_msgs.debt_ratio,
+        };
 
         response = response
-            .add_attribute("action", "borrow")
-            .add_attribute("sender", info.sender.clone())
-            .add_submessage(enter_market(&cfg, &info.sender)?)
+            .add_event(event.into())
+            .add_submessage(enter_market(cfg, account)?)
             .add_message(send_msg);
         Ok(response)
     }
@@ -592,10 +794,22 @@ mod execute {
         }
 
         let send_back = debt::decrease(deps.storage, &sender, repay_tokens.amount)?;
+        let repaid = repay_tokens.amount - send_back;
+
+        let event = events::Repay {
+            sender: sender.clone(),
+            account: sender.clone(),
+            amount: cfg.market_token.amount(repaid),
+            amount_common: common_value(deps.as_ref(), &cfg, repaid),
+            ctoken_ratio: charge_msgs.ctoken_ratio,
+            debt_ratio: charge_msgs.debt_ratio,
+        };
 
         response = response
             .add_attribute("action", "repay")
-            .add_attribute("sender", sender.clone());
+            .add_attribute("sender", sender.clone())
+            .add_event(event.into())
+            .add_message(update_liquidation_auction(&cfg, &sender)?);
 
         // Return surplus of sent tokens
         if !send_back.is_zero() {
@@ -641,12 +855,24 @@ mod execute {
             response = response.add_submessages(charge_msgs.messages);
         }
 
+        // The credit agency rechecks the liquidation auction itself, once the whole operation
+        // repaying the debt is executed
         debt::decrease(deps.storage, &account, repay_tokens.amount)?;
 
+        let event = events::Repay {
+            sender: sender.clone(),
+            account: account.clone(),
+            amount_common: common_value(deps.as_ref(), &cfg, repay_tokens.amount),
+            amount: repay_tokens,
+            ctoken_ratio: charge_msgs.ctoken_ratio,
+            debt_ratio: charge_msgs.debt_ratio,
+        };
+
         response = response
             .add_attribute("action", "repay_to")
             .add_attribute("sender", sender)
-            .add_attribute("debtor", account);
+            .add_attribute("debtor", account)
+            .add_event(event.into());
         Ok(response)
     }
 
@@ -676,10 +902,7 @@ mod execute {
         }
 
         // calculate repaid value
-        let price_rate = query::price_market_local_per_common(deps.as_ref())?.rate_sell_per_buy;
-
-        let repaid_value = cr_utils::divide(amount, price_rate * liquidation_price)
-            .map_err(|_| ContractError::ZeroPrice {})?;
+        let repaid_value = cr_utils::seized_collateral(deps.as_ref(), amount, liquidation_price)?;
 
         // transfer claimed amount of repaid value in ctokens from account source to destination
         // using base message here, since the rebase messages from `charge_interest` are not applied yet,
@@ -695,15 +918,103 @@ mod execute {
             funds: vec![],
         });
 
+        let event = events::SeizeCollateral {
+            sender: info.sender,
+            account: source.clone(),
+            recipient: destination.clone(),
+            amount: cfg.market_token.amount(repaid_value),
+            amount_common: cfg.common_token.amount(amount),
+            liquidation_price,
+            ctoken_ratio: charge_msgs.ctoken_ratio,
+            debt_ratio: charge_msgs.debt_ratio,
+        };
+
         response = response
             .add_submessage(enter_market(&cfg, &destination)?)
             .add_attribute("action", "transfer_from")
             .add_attribute("from", source)
             .add_attribute("to", destination)
+            .add_event(event.into())
             .add_submessage(transfer_msg);
         Ok(response)
     }
 
+    /// Handler for `ExecuteMsg::DistributeDebtRewards`
+    /// Requires sender to be a Credit Agency, otherwise fails
+    pub fn distribute_debt_rewards(
+        deps: DepsMut,
+        sender: Addr,
+        rewards: Vec<Coin>,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        if cfg.credit_agency != sender {
+            return Err(ContractError::RequiresCreditAgency {});
+        }
+        if rewards.is_empty() {
+            return Err(ContractError::NoFundsSent {});
+        }
+
+        let event = events::DistributeRewards {
+            sender,
+            rewards: rewards.clone(),
+        };
+        let mut response = Response::new()
+            .add_attribute("action", "distribute_debt_rewards")
+            .add_event(event.into());
+        for coin in rewards {
+            debt::distribute_rewards(deps.storage, &coin.denom, coin.amount)?;
+            response = response.add_attribute("amount", coin.amount);
+            response = match coin.denom {
+                Token::Native(denom) => response.add_attribute("denom", denom),
+                Token::Cw20(address) => response.add_attribute("cw20_address", address),
+            };
+        }
+        Ok(response)
+    }
+
+    /// Handler for `ExecuteMsg::ClaimRewards`
+    /// Requires sender to be a Credit Agency, otherwise fails
+    pub fn claim_rewards(
+        deps: DepsMut,
+        info: MessageInfo,
+        account: Addr,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        if cfg.credit_agency != info.sender {
+            return Err(ContractError::RequiresCreditAgency {});
+        }
+
+        // Rewards of cToken holders are kept by the cToken contract
+        let withdraw_msg = to_binary(&isotonic_token::msg::ExecuteMsg::WithdrawFundsFor {
+            owner: account.to_string(),
+        })?;
+        let withdraw_msg = SubMsg::new(WasmMsg::Execute {
+            contract_addr: cfg.ctoken_contract.to_string(),
+            msg: withdraw_msg,
+            funds: vec![],
+        });
+
+        let rewards = debt::withdraw_rewards(deps.storage, &account)?;
+        let send_msgs = rewards
+            .iter()
+            .map(|coin| coin.denom.send_msg(&account, coin.amount))
+            .collect::<StdResult<Vec<CosmosMsg>>>()?;
+
+        let event = events::ClaimRewards {
+            sender: info.sender,
+            account: account.clone(),
+            markets: vec![],
+            rewards,
+        };
+
+        Ok(Response::new()
+            .add_attribute("action", "claim_rewards")
+            .add_attribute("account", account)
+            .add_event(event.into())
+            .add_submessage(withdraw_msg)
+            .add_messages(send_msgs))
+    }
+
     /// Handler for `ExecuteMsg::AdjustCommonToken`
     pub fn adjust_common_token(
         deps: DepsMut,
@@ -716,10 +1027,16 @@ mod execute {
             return Err(ContractError::Unauthorized {});
         }
 
-        cfg.common_token = new_token;
+        cfg.common_token = new_token.clone();
 
         CONFIG.save(deps.storage, &cfg)?;
-        Ok(Response::new())
+        Ok(Response::new().add_event(
+            events::AdjustCommonToken {
+                sender,
+                common_token: new_token,
+            }
+            .into(),
+        ))
     }
 
     /// Returns the addrress associated to the pool for the two given `Token`s.
@@ -751,41 +1068,43 @@ mod execute {
             return Err(ContractError::RequiresCreditAgency {});
         }
 
-        let send_msg = dbg!(buy.denom.send_msg(sender, buy.amount)?);
+        let send_msg = buy.denom.send_msg(&sender, buy.amount)?;
+        let event = events::SwapWithdraw {
+            sender,
+            account: deps.api.addr_validate(&account)?,
+            amount: cfg.market_token.amount(buy.amount),
+            bought: buy.clone(),
+        };
 
-        // if swap is between same denoms, only send tokens
+        // If swap is between same denoms, only withdraw tokens. The bought amount is still
+        // burned from the account collateral, as it is for the swap below.
         if cfg.market_token == buy.denom {
-            return Ok(Response::new().add_message(send_msg));
-        }
+            if buy.amount > sell_limit {
+                return Err(ContractError::EstimateHigherThanLimit {
+                    estimate: buy.amount,
+                    sell_limit,
+                });
+            }
+
+            let burn_msg = to_binary(&isotonic_token::msg::ExecuteMsg::BurnBaseFrom {
+                owner: account,
+                amount: buy.amount,
+            })?;
+            let burn_msg = SubMsg::new(WasmMsg::Execute {
+                contract_addr: cfg.ctoken_contract.to_string(),
+                msg: burn_msg,
+                funds: vec![],
+            });
 
-        // variable to store the swap to perform.
-        let mut operations: Vec<SwapOperation> = vec![];
+            return Ok(Response::new()
+                .add_event(event.into())
+                .add_submessage(burn_msg)
+                .add_message(send_msg));
+        }
 
-        // If the market token is the common token we can directly swap it for the buy token.
-        if cfg.market_token == cfg.common_token {
-            operations.push(SwapOperation::WyndexSwap {
-                offer_asset_info: cfg.common_token.clone().into(),
-                ask_asset_info: buy.denom.into(),
-            })
-        // If buy token is the common token we can directly swap it with the market token.
-        } else if cfg.common_token == buy.denom {
-            operations.push(SwapOperation::WyndexSwap {
-                offer_asset_info: cfg.market_token.clone().into(),
-                ask_asset_info: buy.denom.into(),
-            })
-        // Else we need two swaps.
-        } else {
-            operations.extend(vec![
-                SwapOperation::WyndexSwap {
-                    offer_asset_info: cfg.market_token.clone().into(),
-                    ask_asset_info: cfg.common_token.clone().into(),
-                },
-                SwapOperation::WyndexSwap {
-                    offer_asset_info: cfg.common_token.clone().into(),
-                    ask_asset_info: buy.denom.into(),
-                },
-            ])
-        };
+        let operations = cfg
+            .market_token
+            .swap_operations(&buy.denom, &cfg.common_token);
 
         // Compute an estimate of market tokens required to complete the buy.
         let swap_response: SimulateSwapOperationsResponse = deps.querier.query_wasm_smart(
@@ -818,9 +1137,22 @@ mod execute {
             .querier
             .query_wasm_smart(cfg.price_oracle, &OracleQueryMsg::Config {})?;
 
-        let swap_msg = dbg!(cfg.market_token.swap_msg(oracle_config.multi_hop, operations, Some(buy.amount), sell_limit)?);
+        // Only burned collateral is sold - offering `sell_limit` would sell collateral the
+        // account still owns
+        let swap_msg = cfg.market_token.swap_msg(
+            oracle_config.multi_hop,
+            operations,
+            Some(buy.amount),
+            estimate,
+        )?;
+
+        let event = events::SwapWithdraw {
+            amount: cfg.market_token.amount(estimate),
+            ..event
+        };
 
         Ok(Response::new()
+            .add_event(event.into())
             .add_submessage(burn_msg)
             .add_message(swap_msg)
             .add_message(send_msg))
@@ -835,15 +1167,19 @@ mod execute {
         use ReceiveMsg::*;
         // TODO: make functions accept or Addr or String
         match from_binary(&msg.msg)? {
-            Deposit => deposit(
-                deps,
-                env,
-                msg.sender,
-                utils::coin::Coin {
-                    denom: Token::Cw20(info.sender.to_string()),
-                    amount: msg.amount,
-                },
-            ),
+            Deposit => {
+                let sender = deps.api.addr_validate(msg.sender.as_str())?;
+                deposit(
+                    deps,
+                    env,
+                    sender,
+                    msg.sender,
+                    utils::coin::Coin {
+                        denom: Token::Cw20(info.sender.to_string()),
+                        amount: msg.amount,
+                    },
+                )
+            }
             Repay => {
                 let sender = deps.api.addr_validate(msg.sender.as_str())?;
                 repay(
@@ -870,6 +1206,17 @@ mod execute {
                     account,
                 )
             }
+            DistributeDebtRewards {} => {
+                let sender = deps.api.addr_validate(msg.sender.as_str())?;
+                distribute_debt_rewards(
+                    deps,
+                    sender,
+                    vec![utils::coin::Coin {
+                        denom: Token::Cw20(info.sender.to_string()),
+                        amount: msg.amount,
+                    }],
+                )
+            }
         }
     }
 }
@@ -882,7 +1229,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
         TokensBalance { account } => to_binary(&query::tokens_balance(deps, env, account)?)?,
         TransferableAmount { token, account } => {
             let token = deps.api.addr_validate(&token)?;
-            to_binary(&query::transferable_amount(deps, token, account)?)?
+            to_binary(&query::transferable_amount(deps, env, token, account)?)?
         }
         Withdrawable { account } => to_binary(&query::withdrawable(deps, env, account)?)?,
         Borrowable { account } => to_binary(&query::borrowable(deps, env, account)?)?,
@@ -898,6 +1245,38 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
             let (total, multiplier) = debt::total(deps.storage)?;
             to_binary(&TotalDebtResponse { total, multiplier })?
         }
+        DebtRewards { account } => {
+            let account = deps.api.addr_validate(&account)?;
+            let rewards = debt::withdrawable_rewards(deps.storage, &account)?;
+            to_binary(&DebtRewardsResponse { rewards })?
+        }
+        RepayableDebt { account } => {
+            // `RepayTo` checks the debt before charging interest
+            let account = deps.api.addr_validate(&account)?;
+            let cfg = CONFIG.load(deps.storage)?;
+            to_binary(&cfg.market_token.amount(debt::of(deps.storage, &account)?))?
+        }
+        LiquidationSeize {
+            account,
+            amount,
+            liquidation_price,
+        } => to_binary(&query::liquidation_seize(
+            deps,
+            env,
+            account,
+            amount,
+            liquidation_price,
+        )?)?,
+        RiskConfig {} => to_binary(&RISK_CONFIG.may_load(deps.storage)?.unwrap_or_default())?,
+        PendingParamChanges { start_after, limit } => {
+            to_binary(&query::pending_param_changes(deps, start_after, limit)?)?
+        }
+        PendingGovernance {} => to_binary(&PendingGovernanceResponse {
+            new_governance: PENDING_GOVERNANCE
+                .may_load(deps.storage)?
+                .map(|pending| pending.new_governance),
+        })?,
+        WindDown {} => to_binary(&query::wind_down(deps, env)?)?,
     };
     Ok(res)
 }
@@ -908,6 +1287,7 @@ mod query {
     use cosmwasm_std::{Decimal, Deps, Uint128};
     use cw20::BalanceResponse;
     use isotonic_token::msg::{QueryMsg as TokenQueryMsg, TokenInfoResponse};
+    use utils::amount::{base_to_token, token_to_base};
     use utils::coin::Coin;
     use utils::credit_line::{CreditLineResponse, CreditLineValues};
     use utils::price::{coin_times_price_rate, PriceRate};
@@ -915,9 +1295,15 @@ mod query {
     use wyndex::pair::SpotPriceResponse;
     use wyndex_oracle::msg::QueryMsg as OracleQueryMsg;
 
-    use crate::interest::{calculate_interest, epochs_passed, utilisation};
-    use crate::msg::{ApyResponse, InterestResponse, ReserveResponse, TokensBalanceResponse};
-    use crate::state::{debt, SECONDS_IN_YEAR};
+    use crate::interest::{calculate_interest, epochs_passed, query_ctoken_multiplier, utilisation};
+    use crate::msg::{
+        ApyResponse, InterestResponse, LiquidationSeizeResponse, PendingParamChangeResponse,
+        PendingParamChangesResponse, ReserveResponse, TokensBalanceResponse, WindDownResponse,
+    };
+    use crate::state::{debt, PARAM_CHANGES, SECONDS_IN_YEAR};
+
+    const MAX_LIMIT: u32 = 30;
+    const DEFAULT_LIMIT: u32 = 10;
 
     fn token_balance(
         deps: Deps,
@@ -957,12 +1343,27 @@ mod query {
             .amount(base_balance(deps, &config.ctoken_contract, account.to_string())?.balance))
     }
 
+    /// Amount of market tokens which can be lent or withdrawn - the contract balance without
+    /// rewards reserved for borrowers.
+    pub fn available_liquidity(
+        deps: Deps,
+        env: &Env,
+        cfg: &Config,
+    ) -> Result<Uint128, ContractError> {
+        let balance = cfg
+            .market_token
+            .query_balance(deps, env.contract.address.clone())?;
+        let reserved = debt::reserved_rewards(deps.storage, &cfg.market_token)?;
+        Ok(Uint128::new(balance).saturating_sub(reserved))
+    }
+
     /// Handler for `QueryMsg::Config`
     pub fn config(deps: Deps, env: Env) -> Result<Config, ContractError> {
         let mut config = CONFIG.load(deps.storage)?;
 
-        let unhandled_charge_period = epochs_passed(&config, env)?;
+        let unhandled_charge_period = epochs_passed(&config, env.clone())?;
         config.last_charged += unhandled_charge_period * config.interest_charge_period;
+        config.collateral_ratio = cr_utils::collateral_ratio(deps, &env, &config)?;
 
         Ok(config)
     }
@@ -989,15 +1390,44 @@ mod query {
         Ok(TokensBalanceResponse { collateral, debt })
     }
 
+    /// Handler for `QueryMsg::LiquidationSeize`
+    pub fn liquidation_seize(
+        deps: Deps,
+        env: Env,
+        account: String,
+        amount: Uint128,
+        liquidation_price: Decimal,
+    ) -> Result<LiquidationSeizeResponse, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let seized = cr_utils::seized_collateral(deps, amount, liquidation_price)?;
+        let ctoken_balance = ctoken_balance(deps, &config, &account)?.amount;
+
+        // Interest is charged before the transfer, which rebases cTokens
+        let mut multiplier = query_ctoken_multiplier(deps, &config)?;
+        if let Some(update) = calculate_interest(deps, epochs_passed(&config, env)?)? {
+            multiplier = multiplier * (update.ctoken_ratio + Decimal::one());
+        }
+
+        Ok(LiquidationSeizeResponse {
+            seized: config.market_token.amount(seized),
+            seized_ctokens: base_to_token(seized, multiplier),
+            ctoken_balance,
+            collateral: config
+                .market_token
+                .amount(token_to_base(ctoken_balance, multiplier)),
+        })
+    }
+
     /// Handler for `QueryMsg::TransferableAmount`
     pub fn transferable_amount(
         deps: Deps,
+        env: Env,
         token: Addr,
         account: String,
     ) -> Result<TransferableAmountResponse, ContractError> {
         let config = CONFIG.load(deps.storage)?;
         if token == config.ctoken_contract {
-            let transferable = cr_utils::transferable_amount(deps, &config, account)?;
+            let transferable = cr_utils::transferable_amount(deps, &env, &config, account)?;
             Ok(TransferableAmountResponse { transferable })
         } else {
             Err(ContractError::UnrecognisedToken(token.to_string()))
@@ -1010,15 +1440,10 @@ mod query {
 
         let cfg = CONFIG.load(deps.storage)?;
 
-        let transferable = cr_utils::transferable_amount(deps, &cfg, &account)?;
+        let transferable = cr_utils::transferable_amount(deps, &env, &cfg, &account)?;
         let ctoken_balance = ctoken_base_balance(deps, &cfg, &account)?;
         let allowed_to_withdraw = min(transferable, ctoken_balance.amount);
-        let withdrawable = min(
-            allowed_to_withdraw,
-            cfg.market_token
-                .query_balance(deps, env.contract.address)?
-                .into(),
-        );
+        let withdrawable = min(allowed_to_withdraw, available_liquidity(deps, &env, &cfg)?);
 
         Ok(cfg.market_token.amount(withdrawable))
     }
@@ -1030,12 +1455,7 @@ mod query {
         let cfg = CONFIG.load(deps.storage)?;
 
         let borrowable = cr_utils::query_borrowable_tokens(deps, &cfg, account)?;
-        let borrowable = min(
-            borrowable,
-            cfg.market_token
-                .query_balance(deps, env.contract.address)?
-                .into(),
-        );
+        let borrowable = min(borrowable, available_liquidity(deps, &env, &cfg)?);
 
         Ok(cfg.market_token.amount(borrowable))
     }
@@ -1107,7 +1527,7 @@ mod query {
         };
 
         // Simulate charging interest for any periods `charge_interest` wasn't called for yet
-        if let Some(update) = calculate_interest(deps, epochs_passed(&config, env)?)? {
+        if let Some(update) = calculate_interest(deps, epochs_passed(&config, env.clone())?)? {
             collateral.amount += collateral.amount * update.ctoken_ratio;
             debt.amount += debt.amount * update.debt_ratio;
         }
@@ -1119,7 +1539,7 @@ mod query {
         let price_ratio = price_market_local_per_common(deps)?;
         let collateral = coin_times_price_rate(&collateral, &price_ratio)?;
         let debt = coin_times_price_rate(&debt, &price_ratio)?.amount;
-        let credit_line = collateral.amount * config.collateral_ratio;
+        let credit_line = collateral.amount * cr_utils::collateral_ratio(deps, &env, &config)?;
         let borrow_limit = credit_line * config.borrow_limit_ratio;
         Ok(
             CreditLineValues::new(collateral.amount, credit_line, borrow_limit, debt)
@@ -1127,6 +1547,25 @@ mod query {
         )
     }
 
+    /// Handler for `QueryMsg::WindDown`
+    pub fn wind_down(deps: Deps, env: Env) -> Result<WindDownResponse, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let wind_down = WIND_DOWN.may_load(deps.storage)?;
+        let collateral_ratio = cr_utils::collateral_ratio(deps, &env, &config)?;
+        let force_liquidation = wind_down
+            .as_ref()
+            .map_or(false, |w| w.force_liquidation && collateral_ratio.is_zero());
+        let empty = ctoken_info(deps, &config)?.total_supply.is_zero()
+            && debt::total(deps.storage)?.0.is_zero();
+
+        Ok(WindDownResponse {
+            wind_down,
+            collateral_ratio,
+            force_liquidation,
+            empty,
+        })
+    }
+
     /// Handler for `QueryMsg::Reserve`
     pub fn reserve(deps: Deps, env: Env) -> Result<ReserveResponse, ContractError> {
         let config = CONFIG.load(deps.storage)?;
@@ -1157,12 +1596,36 @@ mod query {
 
         Ok(ApyResponse { borrower, lender })
     }
+
+    /// Handler for `QueryMsg::PendingParamChanges`
+    pub fn pending_param_changes(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<PendingParamChangesResponse, ContractError> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let changes = PARAM_CHANGES
+            .range(deps.storage, start_after, limit)?
+            .into_iter()
+            .map(|(id, pending)| PendingParamChangeResponse {
+                id,
+                change: pending.change,
+                executable_at: pending.executable_at,
+            })
+            .collect();
+        Ok(PendingParamChangesResponse { changes })
+    }
 }
 
 mod restricted {
     use super::*;
 
-    use utils::interest::Interest;
+    use crate::msg::ParamChange;
+    use crate::state::{
+        ParamLimit, PendingGovernance, WindDown, LAST_RISK_CHANGES, PARAM_CHANGES,
+    };
+    use cosmwasm_std::Storage;
+    use utils::interest::ValidatedInterest;
 
     pub fn ensure_governance(cfg: &Config, info: &MessageInfo) -> Result<(), ContractError> {
         if cfg.governance_contract != info.sender {
@@ -1171,76 +1634,423 @@ mod restricted {
         Ok(())
     }
 
-    pub fn adjust_collateral_ratio(
+    /// Handler for `ExecuteMsg::ProposeParamChange`
+    pub fn propose_param_change(
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
-        new_ratio: Decimal,
+        change: ParamChange,
     ) -> Result<Response, ContractError> {
-        let mut cfg = CONFIG.load(deps.storage)?;
+        let cfg = CONFIG.load(deps.storage)?;
         ensure_governance(&cfg, &info)?;
-        cfg.collateral_ratio = new_ratio;
-        CONFIG.save(deps.storage, &cfg)?;
-        Ok(Response::new())
+        validate_param_change(&change)?;
+        let event = events::ParamChange {
+            sender: info.sender,
+            status: "applied",
+            id: None,
+            executable_at: None,
+            change: Some(json(&change)?),
+        };
+
+        if cfg.param_change_delay == 0 || is_risk_reducing(&cfg, &change) {
+            let response = apply_param_change(deps, env, change)?;
+            return Ok(response
+                .add_attribute("action", "apply_param_change")
+                .add_event(event.into()));
+        }
+
+        let executable_at = env.block.time.plus_seconds(cfg.param_change_delay);
+        let id = PARAM_CHANGES.propose(deps.storage, change, executable_at)?;
+        let event = events::ParamChange {
+            status: "proposed",
+            id: Some(id),
+            executable_at: Some(executable_at),
+            ..event
+        };
+        Ok(Response::new()
+            .add_attribute("action", "propose_param_change")
+            .add_attribute("id", id.to_string())
+            .add_attribute("executable_at", executable_at.seconds().to_string())
+            .add_event(event.into()))
     }
 
-    pub fn adjust_reserve_factor(
+    /// Handler for `ExecuteMsg::ExecuteParamChange`
+    pub fn execute_param_change(
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
-        new_factor: Decimal,
+        id: u64,
     ) -> Result<Response, ContractError> {
-        let mut cfg = CONFIG.load(deps.storage)?;
+        let cfg = CONFIG.load(deps.storage)?;
         ensure_governance(&cfg, &info)?;
-        cfg.reserve_factor = new_factor;
-        CONFIG.save(deps.storage, &cfg)?;
-        Ok(Response::new())
+        let change = PARAM_CHANGES.take_ready(deps.storage, &env.block, id)?;
+        let event = events::ParamChange {
+            sender: info.sender,
+            status: "applied",
+            id: Some(id),
+            executable_at: None,
+            change: Some(json(&change)?),
+        };
+        let response = apply_param_change(deps, env, change)?;
+        Ok(response
+            .add_attribute("action", "execute_param_change")
+            .add_attribute("id", id.to_string())
+            .add_event(event.into()))
     }
 
-    pub fn adjust_price_oracle(
+    /// Handler for `ExecuteMsg::CancelParamChange`
+    pub fn cancel_param_change(
         deps: DepsMut,
         info: MessageInfo,
-        new_oracle: String,
+        id: u64,
     ) -> Result<Response, ContractError> {
-        let mut cfg = CONFIG.load(deps.storage)?;
+        let cfg = CONFIG.load(deps.storage)?;
         ensure_governance(&cfg, &info)?;
-        cfg.price_oracle = new_oracle;
-        CONFIG.save(deps.storage, &cfg)?;
-        Ok(Response::new())
+        PARAM_CHANGES.cancel(deps.storage, id)?;
+        let event = events::ParamChange {
+            sender: info.sender,
+            status: "cancelled",
+            id: Some(id),
+            executable_at: None,
+            change: None,
+        };
+        Ok(Response::new()
+            .add_attribute("action", "cancel_param_change")
+            .add_attribute("id", id.to_string())
+            .add_event(event.into()))
+    }
+
+    /// Handler for `ExecuteMsg::StewardParamChange`
+    /// Steward role and its bounds are verified by the credit agency
+    pub fn steward_param_change(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        change: ParamChange,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        if cfg.credit_agency != info.sender {
+            return Err(ContractError::RequiresCreditAgency {});
+        }
+        validate_param_change(&change)?;
+        let event = events::ParamChange {
+            sender: info.sender,
+            status: "applied",
+            id: None,
+            executable_at: None,
+            change: Some(json(&change)?),
+        };
+        let response = apply_param_change(deps, env, change)?;
+        Ok(response
+            .add_attribute("action", "steward_param_change")
+            .add_event(event.into()))
+    }
+
+    /// Handler for `ExecuteMsg::ProposeNewGovernance`
+    pub fn propose_new_governance(
+        deps: DepsMut,
+        info: MessageInfo,
+        new_governance: String,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        let by_credit_agency = cfg.credit_agency == info.sender;
+        if !by_credit_agency {
+            ensure_governance(&cfg, &info)?;
+        }
+
+        let new_governance = deps.api.addr_validate(&new_governance)?;
+        PENDING_GOVERNANCE.save(
+            deps.storage,
+            &PendingGovernance {
+                new_governance: new_governance.clone(),
+                by_credit_agency,
+            },
+        )?;
+        let event = events::GovernanceTransfer {
+            sender: info.sender,
+            status: "proposed",
+            governance: new_governance.clone(),
+        };
+        Ok(Response::new()
+            .add_attribute("action", "propose_new_governance")
+            .add_attribute("new_governance", new_governance)
+            .add_event(event.into()))
     }
 
-    pub fn adjust_market_cap(
+    /// Handler for `ExecuteMsg::WindDown`
+    pub fn wind_down(
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
-        new_cap: Option<Uint128>,
+        duration: u64,
+        force_liquidation: bool,
     ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        if cfg.credit_agency != info.sender {
+            return Err(ContractError::RequiresCreditAgency {});
+        }
+        if WIND_DOWN.may_load(deps.storage)?.is_some() {
+            return Err(ContractError::MarketWindingDown {});
+        }
+
+        WIND_DOWN.save(
+            deps.storage,
+            &WindDown {
+                start: env.block.time,
+                duration,
+                initial_collateral_ratio: cfg.collateral_ratio,
+                force_liquidation,
+            },
+        )?;
+        let event = events::WindDown {
+            sender: info.sender,
+            duration,
+            force_liquidation,
+            collateral_ratio: cfg.collateral_ratio,
+        };
+        Ok(Response::new()
+            .add_attribute("action", "wind_down")
+            .add_attribute("duration", duration.to_string())
+            .add_event(event.into()))
+    }
+
+    /// Handler for `ExecuteMsg::AcceptGovernance`
+    pub fn accept_governance(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
         let mut cfg = CONFIG.load(deps.storage)?;
-        ensure_governance(&cfg, &info)?;
-        cfg.market_cap = new_cap;
+        let pending = PENDING_GOVERNANCE
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoPendingGovernance {})?;
+        if pending.new_governance != info.sender
+            && !(pending.by_credit_agency && cfg.credit_agency == info.sender)
+        {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        PENDING_GOVERNANCE.remove(deps.storage);
+        cfg.governance_contract = pending.new_governance;
         CONFIG.save(deps.storage, &cfg)?;
-        Ok(Response::new())
+        let event = events::GovernanceTransfer {
+            sender: info.sender,
+            status: "accepted",
+            governance: cfg.governance_contract.clone(),
+        };
+        Ok(Response::new()
+            .add_attribute("action", "accept_governance")
+            .add_attribute("governance", cfg.governance_contract)
+            .add_event(event.into()))
+    }
+
+    fn validate_param_change(change: &ParamChange) -> Result<(), ContractError> {
+        if let ParamChange::InterestRates { new_interest_rates } = change {
+            new_interest_rates.clone().validate()?;
+        }
+        Ok(())
+    }
+
+    /// Changes which only make the market safer don't have to wait for the delay. Lowering the
+    /// collateral ratio is not one of them - it shrinks credit lines of existing accounts, so they
+    /// get the delay to react.
+    fn is_risk_reducing(cfg: &Config, change: &ParamChange) -> bool {
+        match change {
+            ParamChange::MarketCap { new_cap: Some(cap) } => {
+                cfg.market_cap.map_or(true, |current| *cap <= current)
+            }
+            ParamChange::ParamChangeDelay { new_delay } => *new_delay >= cfg.param_change_delay,
+            _ => false,
+        }
     }
 
-    pub fn adjust_interest_rates(
+    fn apply_param_change(
         mut deps: DepsMut,
         env: Env,
-        info: MessageInfo,
-        new_interest_rates: Interest,
+        change: ParamChange,
     ) -> Result<Response, ContractError> {
         let mut cfg = CONFIG.load(deps.storage)?;
-        ensure_governance(&cfg, &info)?;
-        let charge_msgs = execute::charge_interest(deps.branch(), env)?;
+        let risk_cfg = RISK_CONFIG.may_load(deps.storage)?.unwrap_or_default();
         let mut response = Response::new();
-        if !charge_msgs.is_unchanged() {
-            response = response.add_submessages(charge_msgs.messages);
+        match change {
+            ParamChange::CollateralRatio { new_ratio } => {
+                let step = abs_diff(cfg.collateral_ratio, new_ratio);
+                check_risk_limit(
+                    deps.storage,
+                    &env,
+                    "collateral_ratio",
+                    &risk_cfg.collateral_ratio,
+                    step,
+                )?;
+                cfg.collateral_ratio = new_ratio;
+            }
+            ParamChange::ReserveFactor { new_factor } => {
+                let step = abs_diff(cfg.reserve_factor, new_factor);
+                check_risk_limit(
+                    deps.storage,
+                    &env,
+                    "reserve_factor",
+                    &risk_cfg.reserve_factor,
+                    step,
+                )?;
+                cfg.reserve_factor = new_factor;
+            }
+            ParamChange::PriceOracle { new_oracle } => cfg.price_oracle = new_oracle,
+            ParamChange::MarketCap { new_cap } => cfg.market_cap = new_cap,
+            ParamChange::InterestRates { new_interest_rates } => {
+                let new_rates = new_interest_rates.validate()?;
+                let step = interest_rates_step(&cfg.rates, &new_rates);
+                check_risk_limit(
+                    deps.storage,
+                    &env,
+                    "interest_rates",
+                    &risk_cfg.interest_rates,
+                    step,
+                )?;
+                let charge_msgs = execute::charge_interest(deps.branch(), env)?;
+                if !charge_msgs.is_unchanged() {
+                    response = response.add_submessages(charge_msgs.messages);
+                }
+                cfg.rates = new_rates;
+            }
+            ParamChange::ParamChangeDelay { new_delay } => cfg.param_change_delay = new_delay,
+            ParamChange::RiskConfig { new_config } => RISK_CONFIG.save(deps.storage, &new_config)?,
         }
-        let interest_rates = new_interest_rates.validate()?;
-        cfg.rates = interest_rates;
         CONFIG.save(deps.storage, &cfg)?;
         Ok(response)
     }
+
+    fn abs_diff(a: Decimal, b: Decimal) -> Decimal {
+        if a > b {
+            a - b
+        } else {
+            b - a
+        }
+    }
+
+    /// Largest difference between the interest rates, sampled every 10% of utilisation and at
+    /// the breakpoints of both rates, where the difference of piecewise linear rates peaks
+    fn interest_rates_step(old: &ValidatedInterest, new: &ValidatedInterest) -> Decimal {
+        (0..=10u64)
+            .map(|i| Decimal::percent(i * 10))
+            .chain(old.optimal_utilisation())
+            .chain(new.optimal_utilisation())
+            .map(|utilisation| {
+                abs_diff(
+                    old.calculate_interest_rate(utilisation),
+                    new.calculate_interest_rate(utilisation),
+                )
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Verifies the change of `param` against its limit and records the time of the change
+    fn check_risk_limit(
+        storage: &mut dyn Storage,
+        env: &Env,
+        param: &str,
+        limit: &Option<ParamLimit>,
+        step: Decimal,
+    ) -> Result<(), ContractError> {
+        let limit = match limit {
+            Some(limit) => limit,
+            None => return Ok(()),
+        };
+        if step > limit.max_step {
+            return Err(ContractError::RiskParamStepTooLarge {
+                param: param.to_owned(),
+                max_step: limit.max_step,
+            });
+        }
+        if let Some(last_change) = LAST_RISK_CHANGES.may_load(storage, param)? {
+            let next_change = last_change.plus_seconds(limit.min_interval);
+            if env.block.time < next_change {
+                return Err(ContractError::RiskParamChangeTooSoon {
+                    param: param.to_owned(),
+                    next_change,
+                });
+            }
+        }
+        LAST_RISK_CHANGES.save(storage, param, &env.block.time)?;
+        Ok(())
+    }
+
+    pub fn adjust_ctoken_marketing(
+        deps: DepsMut,
+        info: MessageInfo,
+        project: Option<String>,
+        description: Option<String>,
+        marketing: Option<String>,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        ensure_governance(&cfg, &info)?;
+        let msg = to_binary(&isotonic_token::msg::ExecuteMsg::UpdateMarketing {
+            project,
+            description,
+            marketing,
+        })?;
+        let event = events::AdjustCToken {
+            sender: info.sender,
+            ctoken: cfg.ctoken_contract.clone(),
+            update: "marketing",
+            token: None,
+        };
+        Ok(Response::new()
+            .add_event(event.into())
+            .add_message(WasmMsg::Execute {
+                contract_addr: cfg.ctoken_contract.to_string(),
+                msg,
+                funds: vec![],
+            }))
+    }
+
+    pub fn adjust_ctoken_logo(
+        deps: DepsMut,
+        info: MessageInfo,
+        logo: cw20::Logo,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        ensure_governance(&cfg, &info)?;
+        let msg = to_binary(&isotonic_token::msg::ExecuteMsg::UploadLogo(logo))?;
+        let event = events::AdjustCToken {
+            sender: info.sender,
+            ctoken: cfg.ctoken_contract.clone(),
+            update: "logo",
+            token: None,
+        };
+        Ok(Response::new()
+            .add_event(event.into())
+            .add_message(WasmMsg::Execute {
+                contract_addr: cfg.ctoken_contract.to_string(),
+                msg,
+                funds: vec![],
+            }))
+    }
+
+    pub fn add_ctoken_distributed_token(
+        deps: DepsMut,
+        info: MessageInfo,
+        token: Token,
+    ) -> Result<Response, ContractError> {
+        let cfg = CONFIG.load(deps.storage)?;
+        ensure_governance(&cfg, &info)?;
+        let event = events::AdjustCToken {
+            sender: info.sender,
+            ctoken: cfg.ctoken_contract.clone(),
+            update: "distributed_token",
+            token: Some(token.clone()),
+        };
+        let msg = to_binary(&isotonic_token::msg::ExecuteMsg::AddDistributedToken { token })?;
+        Ok(Response::new()
+            .add_event(event.into())
+            .add_message(WasmMsg::Execute {
+                contract_addr: cfg.ctoken_contract.to_string(),
+                msg,
+                funds: vec![],
+            }))
+    }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     CONFIG.update::<_, StdError>(deps.storage, |mut cfg| {
         if let Some(token_id) = msg.isotonic_token_id {
             cfg.token_id = token_id;
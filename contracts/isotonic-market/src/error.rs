@@ -1,6 +1,7 @@
-use cosmwasm_std::{OverflowError, StdError, Uint128};
+use cosmwasm_std::{Decimal, OverflowError, StdError, Timestamp, Uint128};
 use thiserror::Error;
 use utils::interest::InterestError;
+use utils::timelock::TimelockError;
 
 use utils::credit_line::InvalidCommonTokenDenom;
 use utils::price::PriceError;
@@ -19,15 +20,9 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
-    #[error("Unrecognised reply id: {0}")]
-    UnrecognisedReply(u64),
-
     #[error("Unrecognised token: {0}")]
     UnrecognisedToken(String),
 
-    #[error("Invalid reply from submessage {id}, {err}")]
-    ReplyParseFailure { id: u64, err: String },
-
     #[error("No funds sent")]
     NoFundsSent {},
 
@@ -61,12 +56,27 @@ pub enum ContractError {
     #[error("Unauthorized - requires sender to be a Market's Credit Agency")]
     RequiresCreditAgency {},
 
+    #[error("No governance transfer is pending")]
+    NoPendingGovernance {},
+
+    #[error("Market is winding down, no new deposits or borrows are accepted")]
+    MarketWindingDown {},
+
     #[error("{0}")]
     InvalidCommonTokenDenom(#[from] InvalidCommonTokenDenom),
 
     #[error("{0}")]
     InterestError(#[from] InterestError),
 
+    #[error("{0}")]
+    Timelock(#[from] TimelockError),
+
+    #[error("Change of {param} is larger than the allowed step {max_step}")]
+    RiskParamStepTooLarge { param: String, max_step: Decimal },
+
+    #[error("{param} cannot be changed again before {next_change}")]
+    RiskParamChangeTooSoon { param: String, next_change: Timestamp },
+
     #[error("Cannot deposit {attempted_deposit} tokens - market cap is {cap} and there are already {ctoken_base_supply} tokens present")]
     DepositOverCap {
         attempted_deposit: Uint128,
@@ -77,6 +87,12 @@ pub enum ContractError {
     #[error("Cw20 tokens are not supported yet")]
     Cw20TokensNotSupported,
 
+    #[error("No borrowers, noone to distribute rewards to")]
+    NoBorrowersToDistributeTo {},
+
+    #[error("Not enough liquidity in the market, {available} tokens available, {needed} needed")]
+    NotEnoughLiquidity { available: Uint128, needed: Uint128 },
+
     #[error(
         "Osmosis returned SwapAmount::Out in response for estimate - something went wrong, abort"
     )]
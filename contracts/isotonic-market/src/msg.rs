@@ -1,8 +1,10 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Decimal, Timestamp, Uint128};
+use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
 
-use cw20::Cw20ReceiveMsg;
+use cw20::{Cw20ReceiveMsg, Logo};
 use utils::interest::Interest;
+
+use crate::state::{RiskConfig, WindDown};
 use utils::{coin::Coin, token::Token};
 
 #[cw_serde]
@@ -39,6 +41,8 @@ pub struct InstantiateMsg {
     pub borrow_limit_ratio: Decimal,
     /// Address of the governance contract that controls this market
     pub gov_contract: String,
+    /// Time in seconds between proposing a parameter change and executing it
+    pub param_change_delay: u64,
 }
 
 #[cw_serde]
@@ -62,6 +66,27 @@ pub enum ExecuteMsg {
     RepayTo {
         account: String,
     },
+    /// Deposits tokens sent along with this message on behalf of the account.
+    /// Sender must be a Credit Agency
+    DepositTo {
+        account: String,
+    },
+    /// Burns cTokens of the account and sends the withdrawn base asset to the sender. Skips the
+    /// credit line check - the credit agency ensures the account is healthy once the whole
+    /// operation is done.
+    /// Sender must be a Credit Agency
+    WithdrawFrom {
+        account: String,
+        amount: Uint128,
+    },
+    /// Increases debt of the account and sends borrowed tokens to the sender. Skips the credit
+    /// line check - the credit agency ensures the account is healthy once the whole operation
+    /// is done.
+    /// Sender must be a Credit Agency
+    BorrowFor {
+        account: String,
+        amount: Uint128,
+    },
     /// Helper to allow transfering Ctokens from account source to account destination.
     /// Sender must be a Credit Agency
     TransferFrom {
@@ -73,6 +98,16 @@ pub enum ExecuteMsg {
     AdjustCommonToken {
         new_token: Token,
     },
+    /// Distributes native tokens sent along with this message between borrowers, weighted by
+    /// their debt.
+    /// Sender must be a Credit Agency
+    DistributeDebtRewards {},
+    /// Withdraws all rewards of `account` - distributed both to cToken holders and to borrowers.
+    /// Rewards are always sent to the `account`.
+    /// Sender must be a Credit Agency
+    ClaimRewards {
+        account: String,
+    },
     /// Withdraw some base asset, by burning C Tokens and swapping it for `buy` amount.
     /// The bought tokens are transferred to the sender.
     /// Only callable by the credit agency. Skips the credit line check.
@@ -81,35 +116,85 @@ pub enum ExecuteMsg {
         buy: Coin,
         sell_limit: Uint128,
     },
+    /// Queues the parameter change, executable after `param_change_delay`. Risk-reducing
+    /// changes - lowering the market cap or raising the delay - and all changes if there is no
+    /// delay, are applied immediately.
     /// Sender must be the Governance Contract
-    AdjustCollateralRatio {
-        new_ratio: Decimal,
+    ProposeParamChange {
+        change: ParamChange,
     },
+    /// Applies the pending parameter change once its delay passed.
     /// Sender must be the Governance Contract
-    AdjustReserveFactor {
-        new_factor: Decimal,
+    ExecuteParamChange {
+        id: u64,
     },
+    /// Removes the pending parameter change.
     /// Sender must be the Governance Contract
-    AdjustPriceOracle {
-        new_oracle: String,
+    CancelParamChange {
+        id: u64,
     },
+    /// Applies the parameter change of the risk steward immediately, lowering the collateral
+    /// ratio included. Risk limits still apply.
+    /// Sender must be a Credit Agency
+    StewardParamChange {
+        change: ParamChange,
+    },
+    /// Starts the transfer of the market governance to `new_governance`, which has to accept it
+    /// with `AcceptGovernance`. Replaces the previous proposal.
+    /// Sender must be the Governance Contract or a Credit Agency
+    ProposeNewGovernance {
+        new_governance: String,
+    },
+    /// Finishes the governance transfer.
+    /// Sender must be the proposed governance, or a Credit Agency if it proposed the transfer
+    AcceptGovernance {},
+    /// Starts retiring the market. New deposits and borrows are rejected and the collateral
+    /// ratio drops to zero over `duration` seconds.
+    /// Sender must be a Credit Agency
+    WindDown {
+        duration: u64,
+        force_liquidation: bool,
+    },
+    /// Updates marketing info of the cToken. Fields set to `Some("")` are cleared.
+    /// Sender must be the Governance Contract
+    AdjustCTokenMarketing {
+        project: Option<String>,
+        description: Option<String>,
+        marketing: Option<String>,
+    },
+    /// Uploads new logo of the cToken.
     /// Sender must be the Governance Contract
-    AdjustMarketCap {
-        new_cap: Option<Uint128>,
+    AdjustCTokenLogo {
+        logo: Logo,
     },
+    /// Adds another reward token distributed to cToken holders.
     /// Sender must be the Governance Contract
-    AdjustInterestRates {
-        new_interest_rates: Interest,
+    AddCTokenDistributedToken {
+        token: Token,
     },
     /// Handles contract's logics that involves receiving CW20 tokens.
     Receive(Cw20ReceiveMsg),
 }
 
+/// Market parameters governance can change through `ProposeParamChange`
+#[cw_serde]
+pub enum ParamChange {
+    CollateralRatio { new_ratio: Decimal },
+    ReserveFactor { new_factor: Decimal },
+    PriceOracle { new_oracle: String },
+    MarketCap { new_cap: Option<Uint128> },
+    InterestRates { new_interest_rates: Interest },
+    ParamChangeDelay { new_delay: u64 },
+    RiskConfig { new_config: RiskConfig },
+}
+
 #[cw_serde]
 pub enum ReceiveMsg {
     Deposit,
     Repay,
     RepayTo { account: String },
+    /// Cw20 version of `ExecuteMsg::DistributeDebtRewards`
+    DistributeDebtRewards {},
 }
 
 #[cw_serde]
@@ -154,6 +239,35 @@ pub enum QueryMsg {
     /// Return type: `TokenInfoResponse`.
     #[returns(TotalDebtResponse)]
     TotalDebt {},
+    /// Returns rewards distributed to the account as a borrower, not yet claimed
+    #[returns(DebtRewardsResponse)]
+    DebtRewards { account: String },
+    /// Returns the maximum amount `RepayTo` accepts for the account
+    #[returns(Coin)]
+    RepayableDebt { account: String },
+    /// Returns collateral `TransferFrom` would seize from the account for `amount` of common
+    /// tokens repaid
+    #[returns(LiquidationSeizeResponse)]
+    LiquidationSeize {
+        account: String,
+        amount: Uint128,
+        liquidation_price: Decimal,
+    },
+    /// Returns limits of risk parameter changes
+    #[returns(crate::state::RiskConfig)]
+    RiskConfig {},
+    /// Returns parameter changes waiting for their delay, ordered by id
+    #[returns(PendingParamChangesResponse)]
+    PendingParamChanges {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the governance proposed with `ProposeNewGovernance`, not accepted yet
+    #[returns(PendingGovernanceResponse)]
+    PendingGovernance {},
+    /// Returns the wind-down of the market, if started
+    #[returns(WindDownResponse)]
+    WindDown {},
 }
 
 #[cw_serde]
@@ -184,6 +298,51 @@ pub struct TransferableAmountResponse {
     pub transferable: Uint128,
 }
 
+#[cw_serde]
+pub struct LiquidationSeizeResponse {
+    /// Collateral seized, in market token
+    pub seized: Coin,
+    /// Amount of cTokens transferred from the account
+    pub seized_ctokens: Uint128,
+    /// cTokens owned by the account
+    pub ctoken_balance: Uint128,
+    /// Collateral of the account at the time of transfer, after charging interest
+    pub collateral: Coin,
+}
+
+#[cw_serde]
+pub struct PendingParamChangeResponse {
+    pub id: u64,
+    pub change: ParamChange,
+    pub executable_at: Timestamp,
+}
+
+#[cw_serde]
+pub struct PendingParamChangesResponse {
+    pub changes: Vec<PendingParamChangeResponse>,
+}
+
+#[cw_serde]
+pub struct WindDownResponse {
+    pub wind_down: Option<WindDown>,
+    /// Current collateral ratio, lowered by the wind-down
+    pub collateral_ratio: Decimal,
+    /// Debt of the market can be liquidated regardless of the account health
+    pub force_liquidation: bool,
+    /// There are no cTokens nor debt left
+    pub empty: bool,
+}
+
+#[cw_serde]
+pub struct PendingGovernanceResponse {
+    pub new_governance: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct DebtRewardsResponse {
+    pub rewards: Vec<Coin>,
+}
+
 #[cw_serde]
 pub struct ReserveResponse {
     pub reserve: Uint128,
@@ -196,6 +355,8 @@ pub enum CreditAgencyExecuteMsg {
     /// Ensures a given account has entered a market. Meant to be called by a specific
     /// market contract - so the sender of the msg would be the market
     EnterMarket { account: String },
+    /// Resets the liquidation auction of the account if it is healthy again
+    UpdateLiquidationAuction { account: String },
 }
 
 #[cw_serde]
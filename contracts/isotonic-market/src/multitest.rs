@@ -1,12 +1,18 @@
 mod credit_line;
 mod apy;
 mod deposit;
+mod events;
+mod governance;
 mod borrow_repay;
 mod interest;
 mod common;
 mod migration;
+mod param_change;
 mod reserve;
 mod restricted;
+mod rewards;
+mod risk_config;
+mod wind_down;
 mod withdraw;
 mod swap_withdraw_from;
 
@@ -907,4 +907,57 @@ fn query_borrowable_cw20_with_limited_liquidity() {
     // in the market.
     suite.assert_borrowable(BORROWER, 20u128);
     suite.attempt_borrow_max(BORROWER).unwrap();
-} 
\ No newline at end of file
+} 
+#[test]
+fn borrow_for_requires_credit_agency() {
+    let market_token = Token::Native(MARKET_TOKEN.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_contract_funds(market_token.clone().into_coin(150u128))
+        .with_market_token(market_token)
+        .build();
+
+    let err = suite.borrow_for(LENDER, BORROWER, 100).unwrap_err();
+    assert_eq!(
+        ContractError::RequiresCreditAgency {},
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn borrow_for_skips_credit_line() {
+    let market_token = Token::Native(MARKET_TOKEN.to_owned());
+    let common_token = Token::Native(COMMON.to_string());
+
+    let mut suite = SuiteBuilder::new()
+        .with_contract_funds(market_token.clone().into_coin(150u128))
+        .with_market_token(market_token.clone())
+        .build();
+
+    suite.create_pool_with_liquidity_and_twap_price(
+        common_token.into_coin(1_000_000_000_000u128),
+        market_token.clone().into_coin(1_000_000_000_000u128),
+        PairType::Xyk {},
+    );
+
+    // Borrower has no credit line, but the credit agency borrows on its behalf anyway
+    let ca = suite.credit_agency();
+    suite.borrow_for(&ca, BORROWER, 100).unwrap();
+
+    assert_eq!(suite.query_debt(BORROWER).unwrap().u128(), 100);
+    assert_eq!(suite.query_contract_asset_balance().unwrap(), 50);
+    assert_eq!(
+        suite.query_asset_balance(&ca, market_token.denom()).unwrap(),
+        100
+    );
+
+    // Still can't borrow more than liquidity
+    let err = suite.borrow_for(&ca, BORROWER, 100).unwrap_err();
+    assert_eq!(
+        ContractError::NotEnoughLiquidity {
+            available: Uint128::new(50),
+            needed: Uint128::new(100),
+        },
+        err.downcast().unwrap()
+    );
+}
@@ -24,6 +24,8 @@ pub enum ExecuteMsg {
     SetCreditLine { credit_line: CreditLineResponse },
     /// Stud
     EnterMarket { account: String },
+    /// Stud
+    UpdateLiquidationAuction { account: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,6 +56,7 @@ fn execute(
             })?;
         }
         ExecuteMsg::EnterMarket { .. } => {}
+        ExecuteMsg::UpdateLiquidationAuction { .. } => {}
     }
 
     Ok(Response::new())
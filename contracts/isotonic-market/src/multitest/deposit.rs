@@ -397,4 +397,44 @@ fn cannot_deposit_cw20_over_cap() {
         },
         err.downcast().unwrap()
     );
-}
\ No newline at end of file
+}
+#[test]
+fn deposit_to_requires_credit_agency() {
+    let market_token = Token::Native(MARKET_TOKEN.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_funds(LENDER, &[market_token.clone().into_coin(100u128)])
+        .with_market_token(market_token.clone())
+        .build();
+
+    let err = suite
+        .deposit_to(LENDER, LENDER, market_token.into_coin(100u128))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::RequiresCreditAgency {},
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn deposit_to_native() {
+    let market_token = Token::Native(MARKET_TOKEN.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_agency_funds(market_token.clone().into_coin(100u128))
+        .with_market_token(market_token.clone())
+        .build();
+
+    let ca = suite.credit_agency();
+    suite
+        .deposit_to(&ca, LENDER, market_token.into_coin(100u128))
+        .unwrap();
+
+    // cTokens are minted for the account, not for the credit agency
+    assert_eq!(suite.query_contract_asset_balance().unwrap(), 100);
+    assert_eq!(
+        suite.ctokens_to_base(suite.query_ctoken_balance(LENDER).unwrap()),
+        100
+    );
+    assert_eq!(suite.query_ctoken_balance(&ca).unwrap().u128(), 0);
+}
@@ -0,0 +1,99 @@
+use cosmwasm_std::Addr;
+use utils::events::{Borrow, Deposit, GovernanceTransfer, Repay};
+use utils::tests::{assert_event, event_attribute, find_events};
+use utils::token::Token;
+use wyndex::factory::PairType;
+
+use super::suite::{SuiteBuilder, BORROWER, COMMON, GOVERNANCE, LENDER, MARKET_TOKEN};
+
+#[test]
+fn deposit_emits_event() {
+    let market_token = Token::Native(MARKET_TOKEN.to_owned());
+    let mut suite = SuiteBuilder::new()
+        .with_funds(LENDER, &[market_token.clone().into_coin(100u128)])
+        .with_market_token(market_token.clone())
+        .build();
+
+    let response = suite.deposit(LENDER, market_token, 100u128).unwrap();
+    assert_eq!(find_events::<Deposit>(&response.events).len(), 1);
+    assert_eq!(
+        event_attribute::<Deposit>(&response.events, "sender"),
+        Some(LENDER.to_owned())
+    );
+    assert_eq!(
+        event_attribute::<Deposit>(&response.events, "account"),
+        Some(LENDER.to_owned())
+    );
+    assert_eq!(
+        event_attribute::<Deposit>(&response.events, "amount"),
+        Some(format!("100{}", MARKET_TOKEN))
+    );
+}
+
+#[test]
+fn borrow_and_repay_emit_events() {
+    let market_token = Token::Native(MARKET_TOKEN.to_owned());
+    let common_token = Token::Native(COMMON.to_owned());
+    let mut suite = SuiteBuilder::new()
+        .with_contract_funds(market_token.clone().into_coin(150u128))
+        .with_funds(BORROWER, &[market_token.clone().into_coin(50u128)])
+        .with_market_token(market_token.clone())
+        .build();
+    suite.create_pool_with_liquidity_and_twap_price(
+        common_token.into_coin(1_000_000_000_000u128),
+        market_token.clone().into_coin(1_000_000_000_000u128),
+        PairType::Xyk {},
+    );
+    suite.set_high_credit_line(BORROWER).unwrap();
+
+    let response = suite.borrow(BORROWER, 100).unwrap();
+    assert_eq!(
+        event_attribute::<Borrow>(&response.events, "recipient"),
+        Some(BORROWER.to_owned())
+    );
+    assert_eq!(
+        event_attribute::<Borrow>(&response.events, "amount"),
+        Some(format!("100{}", MARKET_TOKEN))
+    );
+    assert!(event_attribute::<Borrow>(&response.events, "amount_common").is_some());
+
+    let response = suite
+        .repay(BORROWER, market_token.into_coin(50u128))
+        .unwrap();
+    assert_eq!(
+        event_attribute::<Repay>(&response.events, "account"),
+        Some(BORROWER.to_owned())
+    );
+    assert_eq!(
+        event_attribute::<Repay>(&response.events, "amount"),
+        Some(format!("50{}", MARKET_TOKEN))
+    );
+}
+
+#[test]
+fn governance_transfer_emits_events() {
+    const NEW_GOVERNANCE: &str = "new_governance";
+    let mut suite = SuiteBuilder::new().build();
+
+    let response = suite
+        .propose_new_governance(GOVERNANCE, NEW_GOVERNANCE)
+        .unwrap();
+    assert_event(
+        &response.events,
+        &GovernanceTransfer {
+            sender: Addr::unchecked(GOVERNANCE),
+            status: "proposed",
+            governance: Addr::unchecked(NEW_GOVERNANCE),
+        },
+    );
+
+    let response = suite.accept_governance(NEW_GOVERNANCE).unwrap();
+    assert_event(
+        &response.events,
+        &GovernanceTransfer {
+            sender: Addr::unchecked(NEW_GOVERNANCE),
+            status: "accepted",
+            governance: Addr::unchecked(NEW_GOVERNANCE),
+        },
+    );
+}
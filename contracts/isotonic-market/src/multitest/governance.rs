@@ -0,0 +1,77 @@
+use cosmwasm_std::{Addr, Decimal};
+
+use super::suite::{SuiteBuilder, GOVERNANCE, LENDER};
+use crate::error::ContractError;
+use crate::msg::ParamChange;
+
+const NEW_GOVERNANCE: &str = "new_governance";
+
+#[test]
+fn two_step_transfer() {
+    let mut suite = SuiteBuilder::new().build();
+    let ca = suite.credit_agency();
+
+    let err = suite
+        .propose_new_governance(LENDER, NEW_GOVERNANCE)
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    suite
+        .propose_new_governance(GOVERNANCE, NEW_GOVERNANCE)
+        .unwrap();
+    assert_eq!(
+        suite.query_pending_governance().unwrap(),
+        Some(Addr::unchecked(NEW_GOVERNANCE))
+    );
+    // Nothing changes until accepted
+    assert_eq!(
+        suite.query_config().unwrap().governance_contract,
+        Addr::unchecked(GOVERNANCE)
+    );
+
+    let err = suite.accept_governance(LENDER).unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    let err = suite.accept_governance(&ca).unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    suite.accept_governance(NEW_GOVERNANCE).unwrap();
+    assert_eq!(
+        suite.query_config().unwrap().governance_contract,
+        Addr::unchecked(NEW_GOVERNANCE)
+    );
+    assert_eq!(suite.query_pending_governance().unwrap(), None);
+
+    // Only the new governance is in control now
+    let change = ParamChange::ReserveFactor {
+        new_factor: Decimal::percent(10),
+    };
+    let err = suite
+        .propose_param_change(GOVERNANCE, change.clone())
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    suite.propose_param_change(NEW_GOVERNANCE, change).unwrap();
+}
+
+#[test]
+fn transfer_through_credit_agency() {
+    let mut suite = SuiteBuilder::new().build();
+    let ca = suite.credit_agency();
+
+    suite.propose_new_governance(&ca, NEW_GOVERNANCE).unwrap();
+    suite.accept_governance(&ca).unwrap();
+    assert_eq!(
+        suite.query_config().unwrap().governance_contract,
+        Addr::unchecked(NEW_GOVERNANCE)
+    );
+}
+
+#[test]
+fn nothing_to_accept() {
+    let mut suite = SuiteBuilder::new().build();
+
+    let err = suite.accept_governance(NEW_GOVERNANCE).unwrap_err();
+    assert_eq!(
+        ContractError::NoPendingGovernance {},
+        err.downcast().unwrap()
+    );
+}
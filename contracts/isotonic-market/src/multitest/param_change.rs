@@ -0,0 +1,215 @@
+use cosmwasm_std::{Decimal, Uint128};
+use utils::interest::{Interest, InterestError};
+use utils::timelock::TimelockError;
+
+use super::suite::{SuiteBuilder, GOVERNANCE, LENDER};
+use crate::error::ContractError;
+use crate::msg::ParamChange;
+
+const DELAY: u64 = 3600;
+
+#[test]
+fn change_waits_for_delay() {
+    let mut suite = SuiteBuilder::new()
+        .with_collateral_ratio(Decimal::percent(50))
+        .with_param_change_delay(DELAY)
+        .build();
+    let change = ParamChange::CollateralRatio {
+        new_ratio: Decimal::percent(60),
+    };
+
+    suite
+        .propose_param_change(GOVERNANCE, change.clone())
+        .unwrap();
+    assert_eq!(
+        suite.query_config().unwrap().collateral_ratio,
+        Decimal::percent(50)
+    );
+
+    let pending = suite.query_pending_param_changes().unwrap().changes;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].change, change);
+    let id = pending[0].id;
+
+    let err = suite.execute_param_change(GOVERNANCE, id).unwrap_err();
+    assert_eq!(
+        ContractError::Timelock(TimelockError::NotReady {
+            id,
+            executable_at: pending[0].executable_at,
+        }),
+        err.downcast().unwrap()
+    );
+
+    suite.advance_seconds(DELAY);
+    let err = suite.execute_param_change(LENDER, id).unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    suite.execute_param_change(GOVERNANCE, id).unwrap();
+    assert_eq!(
+        suite.query_config().unwrap().collateral_ratio,
+        Decimal::percent(60)
+    );
+    assert_eq!(suite.query_pending_param_changes().unwrap().changes, vec![]);
+}
+
+#[test]
+fn risk_reducing_changes_skip_delay() {
+    let mut suite = SuiteBuilder::new()
+        .with_collateral_ratio(Decimal::percent(50))
+        .with_param_change_delay(DELAY)
+        .build();
+
+    suite
+        .propose_param_change(
+            GOVERNANCE,
+            ParamChange::MarketCap {
+                new_cap: Some(Uint128::new(1_000)),
+            },
+        )
+        .unwrap();
+    suite
+        .propose_param_change(
+            GOVERNANCE,
+            ParamChange::ParamChangeDelay {
+                new_delay: 2 * DELAY,
+            },
+        )
+        .unwrap();
+
+    let cfg = suite.query_config().unwrap();
+    assert_eq!(cfg.market_cap, Some(Uint128::new(1_000)));
+    assert_eq!(cfg.param_change_delay, 2 * DELAY);
+    assert_eq!(suite.query_pending_param_changes().unwrap().changes, vec![]);
+
+    // Removing the cap is not risk-reducing
+    suite
+        .propose_param_change(GOVERNANCE, ParamChange::MarketCap { new_cap: None })
+        .unwrap();
+    assert_eq!(
+        suite.query_config().unwrap().market_cap,
+        Some(Uint128::new(1_000))
+    );
+    assert_eq!(
+        suite.query_pending_param_changes().unwrap().changes.len(),
+        1
+    );
+}
+
+#[test]
+fn lower_collateral_ratio_waits_for_delay() {
+    let mut suite = SuiteBuilder::new()
+        .with_collateral_ratio(Decimal::percent(50))
+        .with_param_change_delay(DELAY)
+        .build();
+
+    // Lowering the ratio makes accounts liquidatable, so they get the delay to react
+    suite
+        .propose_param_change(
+            GOVERNANCE,
+            ParamChange::CollateralRatio {
+                new_ratio: Decimal::percent(40),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        suite.query_config().unwrap().collateral_ratio,
+        Decimal::percent(50)
+    );
+    let id = suite.query_pending_param_changes().unwrap().changes[0].id;
+
+    suite.advance_seconds(DELAY);
+    suite.execute_param_change(GOVERNANCE, id).unwrap();
+    assert_eq!(
+        suite.query_config().unwrap().collateral_ratio,
+        Decimal::percent(40)
+    );
+}
+
+#[test]
+fn cancel_change() {
+    let mut suite = SuiteBuilder::new().with_param_change_delay(DELAY).build();
+
+    suite
+        .propose_param_change(
+            GOVERNANCE,
+            ParamChange::PriceOracle {
+                new_oracle: "new_oracle".to_owned(),
+            },
+        )
+        .unwrap();
+    let id = suite.query_pending_param_changes().unwrap().changes[0].id;
+
+    let err = suite.cancel_param_change(LENDER, id).unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    suite.cancel_param_change(GOVERNANCE, id).unwrap();
+    assert_eq!(suite.query_pending_param_changes().unwrap().changes, vec![]);
+
+    suite.advance_seconds(DELAY);
+    let err = suite.execute_param_change(GOVERNANCE, id).unwrap_err();
+    assert_eq!(
+        ContractError::Timelock(TimelockError::NotFound(id)),
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn invalid_change_rejected_on_proposal() {
+    let mut suite = SuiteBuilder::new().with_param_change_delay(DELAY).build();
+
+    let err = suite
+        .propose_param_change(
+            LENDER,
+            ParamChange::ReserveFactor {
+                new_factor: Decimal::percent(10),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    let err = suite
+        .propose_param_change(
+            GOVERNANCE,
+            ParamChange::InterestRates {
+                new_interest_rates: Interest::PiecewiseLinear {
+                    base: Decimal::percent(1),
+                    slope1: Decimal::percent(5),
+                    slope2: Decimal::percent(50),
+                    optimal_utilisation: Decimal::one(),
+                },
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InterestError(InterestError::InvalidOptimalUtilisation(Decimal::one())),
+        err.downcast().unwrap()
+    );
+    assert_eq!(suite.query_pending_param_changes().unwrap().changes, vec![]);
+}
+
+#[test]
+fn steward_change_skips_delay() {
+    let mut suite = SuiteBuilder::new()
+        .with_collateral_ratio(Decimal::percent(50))
+        .with_param_change_delay(DELAY)
+        .build();
+    let change = ParamChange::CollateralRatio {
+        new_ratio: Decimal::percent(60),
+    };
+
+    let err = suite
+        .steward_param_change(GOVERNANCE, change.clone())
+        .unwrap_err();
+    assert_eq!(
+        ContractError::RequiresCreditAgency {},
+        err.downcast().unwrap()
+    );
+
+    let ca = suite.credit_agency();
+    suite.steward_param_change(&ca, change).unwrap();
+    assert_eq!(
+        suite.query_config().unwrap().collateral_ratio,
+        Decimal::percent(60)
+    );
+    assert_eq!(suite.query_pending_param_changes().unwrap().changes, vec![]);
+}
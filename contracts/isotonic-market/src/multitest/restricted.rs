@@ -314,4 +314,42 @@ fn adjust_interest_rates_cw20() {
         suite.ctokens_to_base(suite.query_ctoken_info().unwrap().total_supply),
         3299u128
     );
-}
\ No newline at end of file
+}
+#[test]
+fn adjust_ctoken_marketing() {
+    let mut suite = SuiteBuilder::new().build();
+
+    suite
+        .sudo_adjust_ctoken_marketing(
+            "https://wynddao.com".to_owned(),
+            "Lent market token".to_owned(),
+        )
+        .unwrap();
+
+    let info = suite.query_ctoken_marketing_info().unwrap();
+    assert_eq!(info.project.as_deref(), Some("https://wynddao.com"));
+    assert_eq!(info.description.as_deref(), Some("Lent market token"));
+
+    // Empty string clears the field
+    suite
+        .sudo_adjust_ctoken_marketing(None, "".to_owned())
+        .unwrap();
+
+    let info = suite.query_ctoken_marketing_info().unwrap();
+    assert_eq!(info.project.as_deref(), Some("https://wynddao.com"));
+    assert_eq!(info.description, None);
+}
+
+#[test]
+fn add_ctoken_distributed_token() {
+    let mut suite = SuiteBuilder::new().build();
+
+    let partner = Token::Native("partner".to_owned());
+    suite
+        .sudo_add_ctoken_distributed_token(partner.clone())
+        .unwrap();
+
+    let funds = suite.query_ctoken_distributed_funds().unwrap();
+    assert_eq!(funds.len(), 2);
+    assert!(funds.contains(&partner.amount(0u128)));
+}
@@ -0,0 +1,136 @@
+use cosmwasm_std::Uint128;
+use utils::{coin::Coin, token::Token};
+use wyndex::factory::PairType;
+
+use super::suite::{SuiteBuilder, BORROWER, COMMON, DISTRIBUTION_TOKEN, MARKET_TOKEN, USER};
+use crate::error::ContractError;
+
+fn distribution_coin(amount: u128) -> Coin {
+    Coin::new(amount, Token::Native(DISTRIBUTION_TOKEN.to_owned()))
+}
+
+#[test]
+fn debt_rewards_split_by_debt() {
+    let market_token = Token::Native(MARKET_TOKEN.to_owned());
+    let common_token = Token::Native(COMMON.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_contract_funds(market_token.clone().into_coin(1000u128))
+        .with_agency_funds(distribution_coin(400))
+        .with_market_token(market_token.clone())
+        .build();
+
+    suite.create_pool_with_liquidity_and_twap_price(
+        common_token.into_coin(1_000_000_000_000u128),
+        market_token.into_coin(1_000_000_000_000u128),
+        PairType::Xyk {},
+    );
+
+    suite.set_high_credit_line(BORROWER).unwrap();
+    suite.set_high_credit_line(USER).unwrap();
+    suite.borrow(BORROWER, 100).unwrap();
+    suite.borrow(USER, 300).unwrap();
+
+    let ca = suite.credit_agency();
+    suite
+        .distribute_debt_rewards(&ca, &[distribution_coin(400)])
+        .unwrap();
+
+    assert_eq!(
+        suite.query_debt_rewards(BORROWER).unwrap(),
+        vec![distribution_coin(100)]
+    );
+    assert_eq!(
+        suite.query_debt_rewards(USER).unwrap(),
+        vec![distribution_coin(300)]
+    );
+
+    // Debt taken after the distribution doesn't change already distributed rewards
+    suite.borrow(BORROWER, 200).unwrap();
+    assert_eq!(
+        suite.query_debt_rewards(BORROWER).unwrap(),
+        vec![distribution_coin(100)]
+    );
+
+    suite.claim_rewards(BORROWER).unwrap();
+    assert_eq!(
+        suite
+            .query_asset_balance(BORROWER, DISTRIBUTION_TOKEN.to_owned())
+            .unwrap(),
+        100
+    );
+    assert_eq!(
+        suite.query_debt_rewards(BORROWER).unwrap(),
+        vec![distribution_coin(0)]
+    );
+    assert_eq!(
+        suite.query_debt_rewards(USER).unwrap(),
+        vec![distribution_coin(300)]
+    );
+}
+
+#[test]
+fn distribute_debt_rewards_requires_credit_agency() {
+    let mut suite = SuiteBuilder::new()
+        .with_funds(USER, &[distribution_coin(100)])
+        .build();
+
+    let err = suite
+        .distribute_debt_rewards(USER, &[distribution_coin(100)])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::RequiresCreditAgency {},
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn distribute_debt_rewards_without_borrowers() {
+    let mut suite = SuiteBuilder::new()
+        .with_agency_funds(distribution_coin(100))
+        .build();
+
+    let ca = suite.credit_agency();
+    let err = suite
+        .distribute_debt_rewards(&ca, &[distribution_coin(100)])
+        .unwrap_err();
+    assert_eq!(
+        ContractError::NoBorrowersToDistributeTo {},
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn rewards_in_market_token_are_not_lent() {
+    let market_token = Token::Native(MARKET_TOKEN.to_owned());
+    let common_token = Token::Native(COMMON.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_contract_funds(market_token.clone().into_coin(100u128))
+        .with_agency_funds(market_token.clone().into_coin(50u128))
+        .with_market_token(market_token.clone())
+        .build();
+
+    suite.create_pool_with_liquidity_and_twap_price(
+        common_token.into_coin(1_000_000_000_000u128),
+        market_token.clone().into_coin(1_000_000_000_000u128),
+        PairType::Xyk {},
+    );
+
+    suite.set_high_credit_line(BORROWER).unwrap();
+    suite.borrow(BORROWER, 50).unwrap();
+    let ca = suite.credit_agency();
+    suite
+        .distribute_debt_rewards(&ca, &[market_token.into_coin(50u128)])
+        .unwrap();
+
+    // 100 tokens are on the contract, but 50 of them are reserved for rewards
+    let err = suite.borrow(BORROWER, 60).unwrap_err();
+    assert_eq!(
+        ContractError::NotEnoughLiquidity {
+            available: Uint128::new(50),
+            needed: Uint128::new(60),
+        },
+        err.downcast().unwrap()
+    );
+}
@@ -0,0 +1,159 @@
+use cosmwasm_std::Decimal;
+use utils::interest::Interest;
+
+use super::suite::{Suite, SuiteBuilder, GOVERNANCE};
+use crate::error::ContractError;
+use crate::msg::ParamChange;
+use crate::state::{ParamLimit, RiskConfig};
+
+const DAY: u64 = 24 * 3600;
+
+fn risk_config() -> RiskConfig {
+    RiskConfig {
+        collateral_ratio: Some(ParamLimit {
+            max_step: Decimal::percent(5),
+            min_interval: DAY,
+        }),
+        interest_rates: Some(ParamLimit {
+            max_step: Decimal::percent(2),
+            min_interval: DAY,
+        }),
+        reserve_factor: None,
+    }
+}
+
+fn limited_suite() -> Suite {
+    let mut suite = SuiteBuilder::new()
+        .with_collateral_ratio(Decimal::percent(50))
+        .with_interest(3, 20)
+        .build();
+    suite
+        .propose_param_change(
+            GOVERNANCE,
+            ParamChange::RiskConfig {
+                new_config: risk_config(),
+            },
+        )
+        .unwrap();
+    suite
+}
+
+#[test]
+fn no_limits_by_default() {
+    let mut suite = SuiteBuilder::new()
+        .with_collateral_ratio(Decimal::percent(50))
+        .build();
+    assert_eq!(suite.query_risk_config().unwrap(), RiskConfig::default());
+
+    suite.sudo_adjust_collateral_ratio(10).unwrap();
+    suite.sudo_adjust_collateral_ratio(70).unwrap();
+    assert_eq!(
+        suite.query_config().unwrap().collateral_ratio,
+        Decimal::percent(70)
+    );
+}
+
+#[test]
+fn collateral_ratio_decrease_in_steps() {
+    let mut suite = limited_suite();
+    assert_eq!(suite.query_risk_config().unwrap(), risk_config());
+
+    let err = suite.sudo_adjust_collateral_ratio(40).unwrap_err();
+    assert_eq!(
+        ContractError::RiskParamStepTooLarge {
+            param: "collateral_ratio".to_owned(),
+            max_step: Decimal::percent(5),
+        },
+        err.downcast().unwrap()
+    );
+
+    suite.sudo_adjust_collateral_ratio(45).unwrap();
+    let changed_at = suite.app().block_info().time;
+
+    let err = suite.sudo_adjust_collateral_ratio(40).unwrap_err();
+    assert_eq!(
+        ContractError::RiskParamChangeTooSoon {
+            param: "collateral_ratio".to_owned(),
+            next_change: changed_at.plus_seconds(DAY),
+        },
+        err.downcast().unwrap()
+    );
+
+    suite.advance_seconds(DAY);
+    suite.sudo_adjust_collateral_ratio(40).unwrap();
+    assert_eq!(
+        suite.query_config().unwrap().collateral_ratio,
+        Decimal::percent(40)
+    );
+}
+
+#[test]
+fn interest_rates_step() {
+    let mut suite = limited_suite();
+
+    // Rate at full utilisation grows from 23% to 26%
+    let err = suite
+        .sudo_adjust_interest_rates(Interest::Linear {
+            base: Decimal::percent(3),
+            slope: Decimal::percent(23),
+        })
+        .unwrap_err();
+    assert_eq!(
+        ContractError::RiskParamStepTooLarge {
+            param: "interest_rates".to_owned(),
+            max_step: Decimal::percent(2),
+        },
+        err.downcast().unwrap()
+    );
+
+    suite
+        .sudo_adjust_interest_rates(Interest::Linear {
+            base: Decimal::percent(4),
+            slope: Decimal::percent(21),
+        })
+        .unwrap();
+}
+
+#[test]
+fn interest_rates_step_at_breakpoint() {
+    let mut suite = limited_suite();
+
+    // Rate at 85% utilisation grows from 20% to 22.1%, while at 80% and 90% it changes by less
+    // than 2%
+    let err = suite
+        .sudo_adjust_interest_rates(Interest::PiecewiseLinear {
+            base: Decimal::percent(3),
+            slope1: Decimal::permille(191),
+            slope2: Decimal::permille(9),
+            optimal_utilisation: Decimal::percent(85),
+        })
+        .unwrap_err();
+    assert_eq!(
+        ContractError::RiskParamStepTooLarge {
+            param: "interest_rates".to_owned(),
+            max_step: Decimal::percent(2),
+        },
+        err.downcast().unwrap()
+    );
+
+    suite
+        .sudo_adjust_interest_rates(Interest::PiecewiseLinear {
+            base: Decimal::percent(3),
+            slope1: Decimal::percent(19),
+            slope2: Decimal::percent(1),
+            optimal_utilisation: Decimal::percent(85),
+        })
+        .unwrap();
+}
+
+#[test]
+fn unlimited_param() {
+    let mut suite = limited_suite();
+
+    suite.sudo_adjust_reserve_factor(50).unwrap();
+    suite.sudo_adjust_reserve_factor(0).unwrap();
+    assert_eq!(
+        suite.query_config().unwrap().reserve_factor,
+        Decimal::zero()
+    );
+}
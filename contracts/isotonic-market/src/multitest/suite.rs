@@ -30,12 +30,13 @@ use super::ca_mock::{
     self, contract as contract_credit_agency, ExecuteMsg as CAExecuteMsg,
     InstantiateMsg as CAInstantiateMsg,
 };
-use crate::state::Config;
+use crate::state::{Config, RiskConfig};
 use crate::{
     msg::{
-        ApyResponse, ExecuteMsg, InstantiateMsg, InterestResponse, MigrateMsg, QueryMsg,
-        ReceiveMsg, ReserveResponse, TokensBalanceResponse, TotalDebtResponse,
-        TransferableAmountResponse,
+        ApyResponse, DebtRewardsResponse, ExecuteMsg, InstantiateMsg, InterestResponse, MigrateMsg,
+        ParamChange, PendingGovernanceResponse, PendingParamChangesResponse, QueryMsg, ReceiveMsg,
+        ReserveResponse, TokensBalanceResponse, TotalDebtResponse, TransferableAmountResponse,
+        WindDownResponse,
     },
     state::debt::init,
 };
@@ -138,6 +139,8 @@ pub struct SuiteBuilder {
     pools: HashMap<u64, (utils::coin::Coin, utils::coin::Coin)>,
     initial_cw20: HashMap<String, Vec<Cw20Coin>>,
     credit_agency_funds: Option<Coin>,
+    /// Time in seconds between proposing a parameter change and executing it
+    param_change_delay: u64,
 }
 
 impl SuiteBuilder {
@@ -161,6 +164,7 @@ impl SuiteBuilder {
             pools: HashMap::new(),
             initial_cw20: HashMap::new(),
             credit_agency_funds: None,
+            param_change_delay: 0,
         }
     }
 
@@ -241,6 +245,11 @@ impl SuiteBuilder {
         self
     }
 
+    pub fn with_param_change_delay(mut self, delay: u64) -> Self {
+        self.param_change_delay = delay;
+        self
+    }
+
     /// Sets initial pools. Only pools between `Token::Native` can be created this way.
     pub fn with_pool(mut self, id: u64, pool: (utils::coin::Coin, utils::coin::Coin)) -> Self {
         if pool.0.denom.is_cw20() || pool.1.denom.is_cw20() {
@@ -390,6 +399,7 @@ impl SuiteBuilder {
                     reserve_factor: self.reserve_factor,
                     borrow_limit_ratio: self.borrow_limit_ratio,
                     gov_contract: GOVERNANCE.to_string(),
+                    param_change_delay: self.param_change_delay,
                 },
                 &[],
                 "market",
@@ -839,6 +849,22 @@ impl Suite {
         }
     }
 
+    pub fn deposit_to(
+        &mut self,
+        sender: &str,
+        account: &str,
+        funds: utils::coin::Coin,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.contract.clone(),
+            &ExecuteMsg::DepositTo {
+                account: account.to_owned(),
+            },
+            &[funds.try_into()?],
+        )
+    }
+
     pub fn execute_deposit_through_cw20(
         &mut self,
         sender: &str,
@@ -963,6 +989,40 @@ impl Suite {
         )
     }
 
+    pub fn withdraw_from(
+        &mut self,
+        sender: &str,
+        account: &str,
+        amount: u128,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.contract.clone(),
+            &ExecuteMsg::WithdrawFrom {
+                account: account.to_owned(),
+                amount: amount.into(),
+            },
+            &[],
+        )
+    }
+
+    pub fn borrow_for(
+        &mut self,
+        sender: &str,
+        account: &str,
+        amount: u128,
+    ) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.contract.clone(),
+            &ExecuteMsg::BorrowFor {
+                account: account.to_owned(),
+                amount: amount.into(),
+            },
+            &[],
+        )
+    }
+
     /// Attempts to borrow the full "borrowable" amount (as determined by the borrowable query),
     /// then performs a couple checks to make sure nothing more than that could be borrowed.
     pub fn attempt_borrow_max(&mut self, sender: &str) -> AnyResult<()> {
@@ -1225,6 +1285,15 @@ impl Suite {
             .map_err(|err| anyhow!(err))
     }
 
+    /// Queries ctoken contract for marketing info
+    pub fn query_ctoken_marketing_info(&self) -> AnyResult<cw20::MarketingInfoResponse> {
+        let ctoken = self.ctoken_contract.clone();
+        self.app
+            .wrap()
+            .query_wasm_smart(ctoken, &isotonic_token::msg::QueryMsg::MarketingInfo {})
+            .map_err(|err| anyhow!(err))
+    }
+
     /// Helper to convert the given amount of c-tokens to the equivalent amount of base tokens
     pub fn ctokens_to_base(&self, amount: Uint128) -> u128 {
         let token_info = self.query_ctoken_info().unwrap();
@@ -1323,72 +1392,267 @@ impl Suite {
             .migrate_contract(owner, self.contract.clone(), msg, new_code_id)
     }
 
-    /// Changes collateral ratio parmeter in config through sudo. Pass new ratio as percentage.
-    pub fn sudo_adjust_collateral_ratio(&mut self, new_ratio: u64) -> AnyResult<AppResponse> {
+    pub fn propose_param_change(
+        &mut self,
+        sender: &str,
+        change: ParamChange,
+    ) -> AnyResult<AppResponse> {
         let contract = self.contract.clone();
         self.app.execute_contract(
-            Addr::unchecked(GOVERNANCE),
+            Addr::unchecked(sender),
             contract,
-            &ExecuteMsg::AdjustCollateralRatio {
-                new_ratio: Decimal::percent(new_ratio),
-            },
+            &ExecuteMsg::ProposeParamChange { change },
             &[],
         )
     }
 
-    /// Changes reserve factor parmeter in config through sudo. Pass new ratio as percentage.
-    pub fn sudo_adjust_reserve_factor(&mut self, new_factor: u64) -> AnyResult<AppResponse> {
+    pub fn execute_param_change(&mut self, sender: &str, id: u64) -> AnyResult<AppResponse> {
         let contract = self.contract.clone();
         self.app.execute_contract(
-            Addr::unchecked(GOVERNANCE),
+            Addr::unchecked(sender),
             contract,
-            &ExecuteMsg::AdjustReserveFactor {
-                new_factor: Decimal::percent(new_factor),
+            &ExecuteMsg::ExecuteParamChange { id },
+            &[],
+        )
+    }
+
+    pub fn cancel_param_change(&mut self, sender: &str, id: u64) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            contract,
+            &ExecuteMsg::CancelParamChange { id },
+            &[],
+        )
+    }
+
+    pub fn steward_param_change(
+        &mut self,
+        sender: &str,
+        change: ParamChange,
+    ) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            contract,
+            &ExecuteMsg::StewardParamChange { change },
+            &[],
+        )
+    }
+
+    pub fn propose_new_governance(
+        &mut self,
+        sender: &str,
+        new_governance: &str,
+    ) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            contract,
+            &ExecuteMsg::ProposeNewGovernance {
+                new_governance: new_governance.to_owned(),
             },
             &[],
         )
     }
 
-    pub fn sudo_adjust_price_oracle(&mut self, new_oracle: &str) -> AnyResult<AppResponse> {
+    pub fn accept_governance(&mut self, sender: &str) -> AnyResult<AppResponse> {
         let contract = self.contract.clone();
         self.app.execute_contract(
-            Addr::unchecked(GOVERNANCE),
+            Addr::unchecked(sender),
             contract,
-            &ExecuteMsg::AdjustPriceOracle {
-                new_oracle: new_oracle.to_owned(),
+            &ExecuteMsg::AcceptGovernance {},
+            &[],
+        )
+    }
+
+    pub fn query_pending_governance(&self) -> AnyResult<Option<Addr>> {
+        let response: PendingGovernanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(self.contract.clone(), &QueryMsg::PendingGovernance {})?;
+        Ok(response.new_governance)
+    }
+
+    pub fn wind_down(
+        &mut self,
+        sender: &str,
+        duration: u64,
+        force_liquidation: bool,
+    ) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            contract,
+            &ExecuteMsg::WindDown {
+                duration,
+                force_liquidation,
             },
             &[],
         )
     }
 
+    pub fn query_wind_down(&self) -> AnyResult<WindDownResponse> {
+        let response: WindDownResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(self.contract.clone(), &QueryMsg::WindDown {})?;
+        Ok(response)
+    }
+
+    pub fn query_risk_config(&self) -> AnyResult<RiskConfig> {
+        let response: RiskConfig = self
+            .app
+            .wrap()
+            .query_wasm_smart(self.contract.clone(), &QueryMsg::RiskConfig {})?;
+        Ok(response)
+    }
+
+    pub fn query_pending_param_changes(&self) -> AnyResult<PendingParamChangesResponse> {
+        let response: PendingParamChangesResponse = self.app.wrap().query_wasm_smart(
+            self.contract.clone(),
+            &QueryMsg::PendingParamChanges {
+                start_after: None,
+                limit: None,
+            },
+        )?;
+        Ok(response)
+    }
+
+    /// Changes collateral ratio parmeter in config through sudo. Pass new ratio as percentage.
+    pub fn sudo_adjust_collateral_ratio(&mut self, new_ratio: u64) -> AnyResult<AppResponse> {
+        self.propose_param_change(
+            GOVERNANCE,
+            ParamChange::CollateralRatio {
+                new_ratio: Decimal::percent(new_ratio),
+            },
+        )
+    }
+
+    /// Changes reserve factor parmeter in config through sudo. Pass new ratio as percentage.
+    pub fn sudo_adjust_reserve_factor(&mut self, new_factor: u64) -> AnyResult<AppResponse> {
+        self.propose_param_change(
+            GOVERNANCE,
+            ParamChange::ReserveFactor {
+                new_factor: Decimal::percent(new_factor),
+            },
+        )
+    }
+
+    pub fn sudo_adjust_price_oracle(&mut self, new_oracle: &str) -> AnyResult<AppResponse> {
+        self.propose_param_change(
+            GOVERNANCE,
+            ParamChange::PriceOracle {
+                new_oracle: new_oracle.to_owned(),
+            },
+        )
+    }
+
     pub fn sudo_adjust_market_cap(
         &mut self,
         new_cap: impl Into<Option<Uint128>>,
+    ) -> AnyResult<AppResponse> {
+        self.propose_param_change(
+            GOVERNANCE,
+            ParamChange::MarketCap {
+                new_cap: new_cap.into(),
+            },
+        )
+    }
+
+    pub fn sudo_adjust_interest_rates(
+        &mut self,
+        new_interest_rates: Interest,
+    ) -> AnyResult<AppResponse> {
+        self.propose_param_change(GOVERNANCE, ParamChange::InterestRates { new_interest_rates })
+    }
+
+    pub fn sudo_adjust_ctoken_marketing(
+        &mut self,
+        project: impl Into<Option<String>>,
+        description: impl Into<Option<String>>,
     ) -> AnyResult<AppResponse> {
         let contract = self.contract.clone();
         self.app.execute_contract(
             Addr::unchecked(GOVERNANCE),
             contract,
-            &ExecuteMsg::AdjustMarketCap {
-                new_cap: new_cap.into(),
+            &ExecuteMsg::AdjustCTokenMarketing {
+                project: project.into(),
+                description: description.into(),
+                marketing: None,
             },
             &[],
         )
     }
 
-    pub fn sudo_adjust_interest_rates(
+    pub fn sudo_add_ctoken_distributed_token(&mut self, token: Token) -> AnyResult<AppResponse> {
+        let contract = self.contract.clone();
+        self.app.execute_contract(
+            Addr::unchecked(GOVERNANCE),
+            contract,
+            &ExecuteMsg::AddCTokenDistributedToken { token },
+            &[],
+        )
+    }
+
+    /// Queries ctoken contract for funds distributed to its holders
+    pub fn query_ctoken_distributed_funds(&self) -> AnyResult<Vec<utils::coin::Coin>> {
+        let ctoken = self.ctoken_contract.clone();
+        let resp: isotonic_token::msg::FundsResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(ctoken, &isotonic_token::msg::QueryMsg::DistributedFunds {})
+            .map_err(|err| anyhow!(err))?;
+        Ok(resp.funds)
+    }
+
+    /// Distributes funds between borrowers
+    pub fn distribute_debt_rewards(
         &mut self,
-        new_interest_rates: Interest,
+        sender: &str,
+        funds: &[utils::coin::Coin],
     ) -> AnyResult<AppResponse> {
+        let funds: Vec<Coin> = funds
+            .iter()
+            .map(|c| Coin::try_from(c.clone()).unwrap())
+            .collect();
         let contract = self.contract.clone();
         self.app.execute_contract(
-            Addr::unchecked(GOVERNANCE),
+            Addr::unchecked(sender),
             contract,
-            &ExecuteMsg::AdjustInterestRates { new_interest_rates },
+            &ExecuteMsg::DistributeDebtRewards {},
+            &funds,
+        )
+    }
+
+    /// Claims all rewards of the account, sent from the credit agency
+    pub fn claim_rewards(&mut self, account: &str) -> AnyResult<AppResponse> {
+        let ca = self.ca_contract.clone();
+        let contract = self.contract.clone();
+        self.app.execute_contract(
+            ca,
+            contract,
+            &ExecuteMsg::ClaimRewards {
+                account: account.to_owned(),
+            },
             &[],
         )
     }
 
+    pub fn query_debt_rewards(&self, account: &str) -> AnyResult<Vec<utils::coin::Coin>> {
+        let resp: DebtRewardsResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                self.contract.clone(),
+                &QueryMsg::DebtRewards {
+                    account: account.to_owned(),
+                },
+            )
+            .map_err(|err| anyhow!(err))?;
+        Ok(resp.rewards)
+    }
+
     pub fn assert_ctoken_balance(&self, account: impl ToString, amount: impl Into<Uint128>) {
         let balance = self.query_tokens_balance(account).unwrap();
         assert_eq!(balance.collateral.amount, amount.into());
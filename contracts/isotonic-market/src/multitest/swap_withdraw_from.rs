@@ -147,6 +147,11 @@ fn same_denom() {
         suite.query_contract_asset_balance().unwrap(),
         500_000
     ));
+    // Withdrawn tokens are taken from the account collateral
+    assert_eq!(
+        suite.query_tokens_balance(USER).unwrap().collateral,
+        market_token.amount(500_000u128)
+    );
 }
 
 /*
@@ -0,0 +1,94 @@
+use cosmwasm_std::Decimal;
+use utils::token::Token;
+use wyndex::factory::PairType;
+
+use super::suite::{Suite, SuiteBuilder, COMMON, LENDER, MARKET_TOKEN};
+use crate::error::ContractError;
+
+const DAY: u64 = 24 * 3600;
+
+/// Market with collateral ratio 0.5 and 100 tokens deposited by `LENDER`
+fn deposited_suite() -> Suite {
+    let market_token = Token::Native(MARKET_TOKEN.to_owned());
+    let common_token = Token::Native(COMMON.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_funds(LENDER, &[market_token.clone().into_coin(200u128)])
+        .with_market_token(market_token.clone())
+        .with_collateral_ratio(Decimal::percent(50))
+        .build();
+    suite.create_pool_with_liquidity_and_twap_price(
+        common_token.into_coin(1_000_000_000_000u128),
+        market_token.clone().into_coin(1_000_000_000_000u128),
+        PairType::Xyk {},
+    );
+    suite.set_high_credit_line(LENDER).unwrap();
+    suite.deposit(LENDER, market_token, 100u128).unwrap();
+    suite
+}
+
+#[test]
+fn only_credit_agency_starts_wind_down() {
+    let mut suite = deposited_suite();
+
+    let err = suite.wind_down(LENDER, DAY, false).unwrap_err();
+    assert_eq!(
+        ContractError::RequiresCreditAgency {},
+        err.downcast().unwrap()
+    );
+    assert_eq!(suite.query_wind_down().unwrap().wind_down, None);
+
+    let ca = suite.credit_agency();
+    suite.wind_down(&ca, DAY, false).unwrap();
+    let err = suite.wind_down(&ca, DAY, false).unwrap_err();
+    assert_eq!(ContractError::MarketWindingDown {}, err.downcast().unwrap());
+}
+
+#[test]
+fn deposits_and_borrows_rejected() {
+    let mut suite = deposited_suite();
+    let ca = suite.credit_agency();
+    suite.wind_down(&ca, DAY, false).unwrap();
+
+    let err = suite
+        .deposit(LENDER, Token::Native(MARKET_TOKEN.to_owned()), 10u128)
+        .unwrap_err();
+    assert_eq!(ContractError::MarketWindingDown {}, err.downcast().unwrap());
+    let err = suite.borrow(LENDER, 10).unwrap_err();
+    assert_eq!(ContractError::MarketWindingDown {}, err.downcast().unwrap());
+
+    // Leaving the market is still possible
+    suite.withdraw(LENDER, 100).unwrap();
+    assert!(suite.query_wind_down().unwrap().empty);
+}
+
+#[test]
+fn collateral_ratio_drops_to_zero() {
+    let mut suite = deposited_suite();
+    let ca = suite.credit_agency();
+    suite.wind_down(&ca, DAY, true).unwrap();
+    assert!(!suite.query_wind_down().unwrap().empty);
+
+    suite.advance_seconds(DAY / 2);
+    assert_eq!(
+        suite.query_config().unwrap().collateral_ratio,
+        Decimal::percent(25)
+    );
+    let credit_line = suite.query_credit_line(LENDER).unwrap();
+    assert_eq!(
+        credit_line.credit_line.amount,
+        credit_line.collateral.amount * Decimal::percent(25)
+    );
+    assert!(!suite.query_wind_down().unwrap().force_liquidation);
+
+    suite.advance_seconds(DAY / 2);
+    let wind_down = suite.query_wind_down().unwrap();
+    assert_eq!(wind_down.collateral_ratio, Decimal::zero());
+    assert!(wind_down.force_liquidation);
+    assert!(suite
+        .query_credit_line(LENDER)
+        .unwrap()
+        .credit_line
+        .amount
+        .is_zero());
+}
@@ -643,3 +643,67 @@ fn query_cw20_withdrawable_not_enough_liquid() {
     suite.assert_withdrawable(LENDER, 60u128);
     suite.attempt_withdraw_max(LENDER).unwrap();
 }
+
+#[test]
+fn withdraw_from_requires_credit_agency() {
+    let market_token = Token::Native(MARKET_TOKEN.to_owned());
+
+    let mut suite = SuiteBuilder::new()
+        .with_funds(LENDER, &[market_token.clone().into_coin(100u128)])
+        .with_market_token(market_token.clone())
+        .build();
+
+    suite.deposit(LENDER, market_token, 100u128).unwrap();
+
+    let err = suite.withdraw_from(BORROWER, LENDER, 40).unwrap_err();
+    assert_eq!(
+        ContractError::RequiresCreditAgency {},
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn withdraw_from_skips_credit_line() {
+    let market_token = Token::Native(MARKET_TOKEN.to_owned());
+    let common_token = Token::Native(COMMON.to_string());
+
+    let mut suite = SuiteBuilder::new()
+        .with_funds(LENDER, &[market_token.clone().into_coin(100u128)])
+        .with_market_token(market_token.clone())
+        .build();
+
+    suite.create_pool_with_liquidity_and_twap_price(
+        common_token.into_coin(1_000_000_000_000u128),
+        market_token.clone().into_coin(1_000_000_000_000u128),
+        PairType::Xyk {},
+    );
+
+    suite.deposit(LENDER, market_token, 100u128).unwrap();
+    // Lender's whole credit line is used, so it can't withdraw by itself
+    suite
+        .set_credit_line(
+            LENDER,
+            CreditLineValues {
+                collateral: Uint128::new(100),
+                credit_line: Uint128::new(50),
+                borrow_limit: Uint128::new(50),
+                debt: Uint128::new(50),
+            },
+        )
+        .unwrap();
+    suite.withdraw(LENDER, 40).unwrap_err();
+
+    let ca = suite.credit_agency();
+    suite.withdraw_from(&ca, LENDER, 40).unwrap();
+
+    // Withdrawn tokens are sent to the credit agency
+    assert_eq!(
+        suite.query_asset_balance(&ca, MARKET_TOKEN.to_owned()).unwrap(),
+        40
+    );
+    assert_eq!(suite.query_asset_balance(LENDER, MARKET_TOKEN.to_owned()).unwrap(), 0);
+    assert_eq!(
+        suite.ctokens_to_base(suite.query_ctoken_balance(LENDER).unwrap()),
+        60
+    );
+}
@@ -1,12 +1,17 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Decimal, Uint128};
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
+use cw_storage_plus::{Item, Map};
 
-use utils::{interest::ValidatedInterest, token::Token};
+use utils::{interest::ValidatedInterest, timelock::Timelock, token::Token};
+
+use crate::msg::ParamChange;
 
 pub const SECONDS_IN_YEAR: u128 = 365 * 24 * 3600;
+/// Seed of the ctoken salt, which together with the market token makes the ctoken address known
+/// before it is instantiated
+pub const CTOKEN_SALT_SEED: &[u8] = b"ctoken";
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema, Debug)]
 pub struct Config {
@@ -37,18 +42,82 @@ pub struct Config {
     /// Address of Credit Agency
     pub credit_agency: Addr,
     pub reserve_factor: Decimal,
+    /// Time in seconds between proposing a parameter change and executing it
+    #[serde(default)]
+    pub param_change_delay: u64,
+}
+
+/// Limits of a single change of a risk parameter
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema, Debug)]
+pub struct ParamLimit {
+    /// Largest allowed difference between the current and the new value
+    pub max_step: Decimal,
+    /// Minimal time in seconds between two changes
+    pub min_interval: u64,
+}
+
+/// Limits of risk parameter changes, `None` leaves the parameter unlimited
+#[derive(Serialize, Deserialize, Clone, Default, Eq, PartialEq, JsonSchema, Debug)]
+pub struct RiskConfig {
+    pub collateral_ratio: Option<ParamLimit>,
+    /// Step is the largest difference of the interest rate over the whole utilisation range
+    pub interest_rates: Option<ParamLimit>,
+    pub reserve_factor: Option<ParamLimit>,
+}
+
+/// Retirement of the market started by the credit agency
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema, Debug)]
+pub struct WindDown {
+    pub start: Timestamp,
+    /// Collateral ratio drops linearly from `initial_collateral_ratio` to zero over `duration`
+    /// seconds
+    pub duration: u64,
+    pub initial_collateral_ratio: Decimal,
+    /// Once the collateral ratio is zero, debt left in the market can be liquidated regardless
+    /// of the account health
+    pub force_liquidation: bool,
+}
+
+impl WindDown {
+    pub fn collateral_ratio(&self, now: Timestamp) -> Decimal {
+        let elapsed = now.seconds().saturating_sub(self.start.seconds());
+        if elapsed >= self.duration {
+            return Decimal::zero();
+        }
+        self.initial_collateral_ratio * Decimal::from_ratio(self.duration - elapsed, self.duration)
+    }
+}
+
+/// Governance transfer waiting for `AcceptGovernance`
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, JsonSchema, Debug)]
+pub struct PendingGovernance {
+    pub new_governance: Addr,
+    /// The credit agency proposed the transfer, so it can also accept it
+    pub by_credit_agency: bool,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
+/// Not set until the first `ParamChange::RiskConfig`, which means no limits
+pub const RISK_CONFIG: Item<RiskConfig> = Item::new("risk_config");
+/// Time of the last change of each limited risk parameter
+pub const LAST_RISK_CHANGES: Map<&str, Timestamp> = Map::new("last_risk_changes");
+/// Parameter changes waiting for `param_change_delay` to pass
+pub const PARAM_CHANGES: Timelock<ParamChange> =
+    Timelock::new("param_changes", "next_param_change_id");
+pub const PENDING_GOVERNANCE: Item<PendingGovernance> = Item::new("pending_governance");
+/// Set once the market starts winding down, never removed
+pub const WIND_DOWN: Item<WindDown> = Item::new("wind_down");
 
 pub mod debt {
     use super::*;
 
     use crate::ContractError;
     use cosmwasm_schema::cw_serde;
-    use cosmwasm_std::{StdResult, Storage};
+    use cosmwasm_std::{Order, StdResult, Storage};
     use cw_storage_plus::Map;
+    use isotonic_token::state::{Distribution, WithdrawAdjustment};
     use utils::amount::{base_to_token, token_to_base};
+    use utils::coin::Coin;
 
     #[cw_serde]
     struct DebtInfo {
@@ -61,6 +130,12 @@ pub mod debt {
     /// Total amount of debt per user
     const DEBT: Map<&Addr, Uint128> = Map::new("debt");
     const DEBT_INFO: Item<DebtInfo> = Item::new("debt_info");
+    /// Rewards distributed to borrowers per reward token. Works like the cw2222 distribution of
+    /// `isotonic-token`, but debt points are used as weights.
+    const REWARDS: Map<&Token, Distribution> = Map::new("debt_rewards");
+    /// Rewards withdraw adjustments by (account, reward token)
+    const REWARDS_ADJUSTMENT: Map<(&Addr, &Token), WithdrawAdjustment> =
+        Map::new("debt_rewards_adjustment");
 
     pub fn init(storage: &mut dyn Storage) -> StdResult<()> {
         DEBT_INFO.save(
@@ -152,11 +227,113 @@ pub mod debt {
         info.total_points = change(info.total_points, info.multiplier);
         DEBT_INFO.save(storage, &info)?;
 
-        DEBT.update(storage, account, |debt| -> StdResult<_> {
-            let new_debt = change(debt.unwrap_or_default(), info.multiplier);
-            Ok(new_debt)
-        })?;
+        let old_debt = DEBT.may_load(storage, account)?.unwrap_or_default();
+        let new_debt = change(old_debt, info.multiplier);
+        DEBT.save(storage, account, &new_debt)?;
+
+        apply_rewards_correction(
+            storage,
+            account,
+            new_debt.u128() as i128 - old_debt.u128() as i128,
+        )?;
+
+        Ok(())
+    }
+
+    /// Distributes `amount` of `token` between all borrowers, weighted by their debt points.
+    pub fn distribute_rewards(
+        storage: &mut dyn Storage,
+        token: &Token,
+        amount: Uint128,
+    ) -> Result<(), ContractError> {
+        let total_points = DEBT_INFO.load(storage)?.total_points;
+        if total_points.is_zero() {
+            return Err(ContractError::NoBorrowersToDistributeTo {});
+        }
+
+        // Same calculation as in `isotonic-token` distribution, debt points are the weight
+        let mut distribution = REWARDS.may_load(storage, token)?.unwrap_or_default();
+        distribution.distribute(amount, total_points)?;
+        REWARDS.save(storage, token, &distribution)?;
+
+        Ok(())
+    }
+
+    /// Returns rewards not yet withdrawn by `account`, one entry per reward token
+    pub fn withdrawable_rewards(storage: &dyn Storage, account: &Addr) -> StdResult<Vec<Coin>> {
+        let points = DEBT.may_load(storage, account)?.unwrap_or_default();
+        REWARDS
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| {
+                let (token, distribution) = item?;
+                let adjustment = REWARDS_ADJUSTMENT
+                    .may_load(storage, (account, &token))?
+                    .unwrap_or_default();
+                let amount = distribution.withdrawable(points, &adjustment)?;
+                Ok(token.into_coin(amount))
+            })
+            .collect()
+    }
+
+    /// Marks all rewards of `account` as withdrawn and returns them. Tokens with nothing to
+    /// withdraw are skipped.
+    pub fn withdraw_rewards(storage: &mut dyn Storage, account: &Addr) -> StdResult<Vec<Coin>> {
+        let points = DEBT.may_load(storage, account)?.unwrap_or_default();
+        let rewards = REWARDS
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let mut withdrawn = vec![];
+        for (token, mut distribution) in rewards {
+            let mut adjustment = REWARDS_ADJUSTMENT
+                .may_load(storage, (account, &token))?
+                .unwrap_or_default();
+            let amount = distribution.withdrawable(points, &adjustment)?;
+            if amount.is_zero() {
+                continue;
+            }
+
+            adjustment.withdrawn_funds += amount;
+            REWARDS_ADJUSTMENT.save(storage, (account, &token), &adjustment)?;
+            distribution.withdrawable_total -= amount;
+            REWARDS.save(storage, &token, &distribution)?;
 
+            withdrawn.push(token.into_coin(amount));
+        }
+
+        Ok(withdrawn)
+    }
+
+    /// Rewards in `token` distributed to borrowers, but not yet withdrawn. Those are kept by the
+    /// market and cannot be used as liquidity.
+    pub fn reserved_rewards(storage: &dyn Storage, token: &Token) -> StdResult<Uint128> {
+        Ok(REWARDS
+            .may_load(storage, token)?
+            .map(|distribution| distribution.withdrawable_total)
+            .unwrap_or_default())
+    }
+
+    /// Applies rewards points correction for every reward token after `account` debt points
+    /// changed by `diff`.
+    fn apply_rewards_correction(
+        storage: &mut dyn Storage,
+        account: &Addr,
+        diff: i128,
+    ) -> StdResult<()> {
+        if diff == 0 {
+            return Ok(());
+        }
+
+        let rewards = REWARDS
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (token, distribution) in rewards {
+            REWARDS_ADJUSTMENT.update(storage, (account, &token), |old| -> StdResult<_> {
+                let mut old = old.unwrap_or_default();
+                old.apply_correction(distribution.points_per_token, diff)?;
+                Ok(old)
+            })?;
+        }
         Ok(())
     }
 }
@@ -1,22 +1,29 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdResult, SubMsg,
-    Uint128,
+    to_binary, Addr, Binary, BlockInfo, Decimal, Deps, DepsMut, Env, MessageInfo, Order, Response,
+    StdError, StdResult, Storage, SubMsg, Uint128,
 };
 use cw2::set_contract_version;
-use cw20::Cw20ReceiveMsg;
+use cw20::{
+    AllAllowancesResponse, AllowanceInfo, AllowanceResponse, Cw20ReceiveMsg, DownloadLogoResponse,
+    EmbeddedLogo, Expiration, Logo, LogoInfo, MarketingInfoResponse,
+};
+use cw_storage_plus::Bound;
 use utils::amount::{base_to_token, token_to_base};
 use utils::coin::Coin;
+use utils::events;
+use utils::token::Token;
 
 use crate::error::ContractError;
 use crate::msg::{
-    BalanceResponse, ControllerQuery, ExecuteMsg, FundsResponse, InstantiateMsg,
-    MultiplierResponse, QueryMsg, TokenInfoResponse, TransferableAmountResp,
+    AccountBalance, AllAccountsResponse, BalanceResponse, ControllerQuery, ExecuteMsg,
+    FundsResponse, InstantiateMsg, MultiplierResponse, QueryMsg, TokenInfoResponse,
+    TransferableAmountResp,
 };
 use crate::state::{
-    Distribution, TokenInfo, WithdrawAdjustment, BALANCES, CONTROLLER, DISTRIBUTION, MULTIPLIER,
-    POINTS_SCALE, TOKEN_INFO, TOTAL_SUPPLY, WITHDRAW_ADJUSTMENT,
+    Distribution, TokenInfo, WithdrawAdjustment, ALLOWANCES, BALANCES, CONTROLLER, DISTRIBUTION,
+    LOGO, MARKETING_INFO, MULTIPLIER, TOKEN_INFO, TOTAL_SUPPLY, WITHDRAW_ADJUSTMENT,
 };
 
 // version info for migration info
@@ -40,15 +47,11 @@ pub fn instantiate(
 
     TOKEN_INFO.save(deps.storage, &token_info)?;
 
-    let distribution = Distribution {
-        denom: msg.distributed_token,
-        points_per_token: Uint128::zero(),
-        points_leftover: Uint128::zero(),
-        distributed_total: Uint128::zero(),
-        withdrawable_total: Uint128::zero(),
-    };
-
-    DISTRIBUTION.save(deps.storage, &distribution)?;
+    DISTRIBUTION.save(
+        deps.storage,
+        &msg.distributed_token,
+        &Distribution::default(),
+    )?;
 
     TOTAL_SUPPLY.save(deps.storage, &Uint128::zero())?;
     CONTROLLER.save(deps.storage, &deps.api.addr_validate(&msg.controller)?)?;
@@ -82,6 +85,36 @@ fn can_transfer(
     }
 }
 
+/// Reduces allowance `spender` has on `owner` tokens by `amount`. Fails if there is no allowance
+/// or it is expired.
+fn deduct_allowance(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    spender: &Addr,
+    block: &BlockInfo,
+    amount: Uint128,
+) -> Result<AllowanceResponse, ContractError> {
+    ALLOWANCES.update(storage, (owner, spender), |current| match current {
+        Some(mut allowance) => {
+            if allowance.expires.is_expired(block) {
+                Err(ContractError::Expired {})
+            } else {
+                allowance.allowance = allowance
+                    .allowance
+                    .checked_sub(amount)
+                    .map_err(|_| ContractError::NoAllowance {})?;
+                Ok(allowance)
+            }
+        }
+        None => Err(ContractError::NoAllowance {}),
+    })
+}
+
+/// `amount` of this token, for events
+fn ctokens(env: &Env, amount: Uint128) -> Coin {
+    Token::Cw20(env.contract.address.to_string()).amount(amount)
+}
+
 /// Performs tokens transfer.
 fn transfer_tokens(
     mut deps: DepsMut,
@@ -93,8 +126,7 @@ fn transfer_tokens(
         return Err(ContractError::InvalidZeroAmount {});
     }
 
-    let distribution = DISTRIBUTION.load(deps.storage)?;
-    let ppt = distribution.points_per_token.u128();
+    let ppt = points_per_token(deps.storage)?;
     BALANCES.update(
         deps.storage,
         sender,
@@ -105,14 +137,14 @@ fn transfer_tokens(
                 .map_err(|_| ContractError::insufficient_tokens(balance, amount))
         },
     )?;
-    apply_points_correction(deps.branch(), sender, ppt, -(amount.u128() as i128))?;
+    apply_points_correction(deps.branch(), sender, &ppt, -(amount.u128() as i128))?;
 
     BALANCES.update(
         deps.storage,
         recipient,
         |balance: Option<Uint128>| -> StdResult<_> { Ok(balance.unwrap_or_default() + amount) },
     )?;
-    apply_points_correction(deps.branch(), recipient, ppt, amount.u128() as _)?;
+    apply_points_correction(deps.branch(), recipient, &ppt, amount.u128() as _)?;
 
     Ok(())
 }
@@ -129,35 +161,54 @@ fn transfer(
 
     transfer_tokens(deps, &info.sender, &recipient, amount)?;
 
+    let event = events::Transfer {
+        sender: info.sender.clone(),
+        account: info.sender.clone(),
+        recipient: recipient.clone(),
+        amount: ctokens(&env, amount),
+    };
     let res = Response::new()
         .add_attribute("action", "transfer")
         .add_attribute("from", info.sender)
         .add_attribute("to", recipient)
-        .add_attribute("amount", amount);
+        .add_attribute("amount", amount)
+        .add_event(event.into());
 
     Ok(res)
 }
 
 /// Handler for `ExecuteMsg::TransferFrom`
+/// Controller is allowed to transfer tokens freely, any other sender is spending the allowance
+/// given by the `owner`.
 fn transfer_from(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    sender: Addr,
+    owner: Addr,
     recipient: Addr,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
     let controller = CONTROLLER.load(deps.storage)?;
     if info.sender != controller {
-        return Err(ContractError::Unauthorized {});
+        deduct_allowance(deps.storage, &owner, &info.sender, &env.block, amount)?;
+        can_transfer(deps.as_ref(), &env, owner.to_string(), amount)?;
     }
 
-    transfer_tokens(deps, &sender, &recipient, amount)?;
+    transfer_tokens(deps, &owner, &recipient, amount)?;
 
+    let event = events::Transfer {
+        sender: info.sender.clone(),
+        account: owner.clone(),
+        recipient: recipient.clone(),
+        amount: ctokens(&env, amount),
+    };
     let res = Response::new()
-        .add_attribute("action", "transfer")
-        .add_attribute("from", sender)
+        .add_attribute("action", "transfer_from")
+        .add_attribute("from", owner)
         .add_attribute("to", recipient)
-        .add_attribute("amount", amount);
+        .add_attribute("by", info.sender)
+        .add_attribute("amount", amount)
+        .add_event(event.into());
 
     Ok(res)
 }
@@ -175,11 +226,18 @@ fn send(
 
     transfer_tokens(deps, &info.sender, &recipient, amount)?;
 
+    let event = events::Send {
+        sender: info.sender.clone(),
+        account: info.sender.clone(),
+        recipient: recipient.clone(),
+        amount: ctokens(&env, amount),
+    };
     let res = Response::new()
         .add_attribute("action", "send")
         .add_attribute("from", &info.sender)
         .add_attribute("to", &recipient)
         .add_attribute("amount", amount)
+        .add_event(event.into())
         .add_message(
             Cw20ReceiveMsg {
                 sender: info.sender.into(),
@@ -192,19 +250,157 @@ fn send(
     Ok(res)
 }
 
+/// Handler for `ExecuteMsg::SendFrom`
+fn send_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: Addr,
+    recipient: Addr,
+    amount: Uint128,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    deduct_allowance(deps.storage, &owner, &info.sender, &env.block, amount)?;
+    can_transfer(deps.as_ref(), &env, owner.to_string(), amount)?;
+
+    transfer_tokens(deps, &owner, &recipient, amount)?;
+
+    let event = events::Send {
+        sender: info.sender.clone(),
+        account: owner.clone(),
+        recipient: recipient.clone(),
+        amount: ctokens(&env, amount),
+    };
+    let res = Response::new()
+        .add_attribute("action", "send_from")
+        .add_attribute("from", &owner)
+        .add_attribute("to", &recipient)
+        .add_attribute("by", &info.sender)
+        .add_attribute("amount", amount)
+        .add_event(event.into())
+        .add_message(
+            Cw20ReceiveMsg {
+                sender: info.sender.into(),
+                amount,
+                msg,
+            }
+            .into_cosmos_msg(recipient)?,
+        );
+
+    Ok(res)
+}
+
+/// Handler for `ExecuteMsg::IncreaseAllowance`
+fn increase_allowance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    amount: Uint128,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let spender = deps.api.addr_validate(&spender)?;
+    if spender == info.sender {
+        return Err(ContractError::CannotSetOwnAccount {});
+    }
+
+    let allowance = ALLOWANCES.update(
+        deps.storage,
+        (&info.sender, &spender),
+        |allowance| -> Result<_, ContractError> {
+            let mut allowance: AllowanceResponse = allowance.unwrap_or_default();
+            if let Some(expires) = expires {
+                if expires.is_expired(&env.block) {
+                    return Err(ContractError::InvalidExpiration {});
+                }
+                allowance.expires = expires;
+            }
+            allowance.allowance += amount;
+            Ok(allowance)
+        },
+    )?;
+
+    let event = events::IncreaseAllowance {
+        sender: info.sender.clone(),
+        spender: spender.clone(),
+        amount: ctokens(&env, amount),
+        allowance: allowance.allowance,
+    };
+    let res = Response::new()
+        .add_attribute("action", "increase_allowance")
+        .add_attribute("owner", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("amount", amount)
+        .add_event(event.into());
+
+    Ok(res)
+}
+
+/// Handler for `ExecuteMsg::DecreaseAllowance`
+fn decrease_allowance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    amount: Uint128,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let spender = deps.api.addr_validate(&spender)?;
+    if spender == info.sender {
+        return Err(ContractError::CannotSetOwnAccount {});
+    }
+
+    let key = (&info.sender, &spender);
+    let mut allowance = ALLOWANCES
+        .may_load(deps.storage, key)?
+        .ok_or(ContractError::NoAllowance {})?;
+
+    if amount < allowance.allowance {
+        allowance.allowance -= amount;
+        if let Some(expires) = expires {
+            if expires.is_expired(&env.block) {
+                return Err(ContractError::InvalidExpiration {});
+            }
+            allowance.expires = expires;
+        }
+        ALLOWANCES.save(deps.storage, key, &allowance)?;
+    } else {
+        allowance.allowance = Uint128::zero();
+        ALLOWANCES.remove(deps.storage, key);
+    }
+
+    let event = events::DecreaseAllowance {
+        sender: info.sender.clone(),
+        spender: spender.clone(),
+        amount: ctokens(&env, amount),
+        allowance: allowance.allowance,
+    };
+    let res = Response::new()
+        .add_attribute("action", "decrease_allowance")
+        .add_attribute("owner", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("amount", amount)
+        .add_event(event.into());
+
+    Ok(res)
+}
+
 pub fn mint_base(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     recipient: String,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
     let multiplier = MULTIPLIER.load(deps.storage)?;
-    mint(deps, info, recipient, base_to_token(amount, multiplier))
+    let amount = base_to_token(amount, multiplier);
+    mint(deps, env, info, recipient, amount)
 }
 
 /// Handler for `ExecuteMsg::Mint`
 pub fn mint(
     mut deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     recipient: String,
     amount: Uint128,
@@ -219,7 +415,7 @@ pub fn mint(
         return Err(ContractError::InvalidZeroAmount {});
     }
 
-    let ppt = DISTRIBUTION.load(deps.storage)?.points_per_token.u128();
+    let ppt = points_per_token(deps.storage)?;
 
     let recipient_addr = deps.api.addr_validate(&recipient)?;
     BALANCES.update(
@@ -227,36 +423,51 @@ pub fn mint(
         &recipient_addr,
         |balance: Option<Uint128>| -> StdResult<_> { Ok(balance.unwrap_or_default() + amount) },
     )?;
-    apply_points_correction(deps.branch(), &recipient_addr, ppt, amount.u128() as _)?;
+    apply_points_correction(deps.branch(), &recipient_addr, &ppt, amount.u128() as _)?;
 
     TOTAL_SUPPLY.update(deps.storage, |supply| -> StdResult<_> {
         Ok(supply + amount)
     })?;
 
+    let event = events::Mint {
+        sender: info.sender,
+        recipient: recipient_addr,
+        amount: ctokens(&env, amount),
+    };
     let res = Response::new()
         .add_attribute("action", "mint")
         .add_attribute("to", recipient)
-        .add_attribute("amount", amount);
+        .add_attribute("amount", amount)
+        .add_event(event.into());
     Ok(res)
 }
 
 /// Handler for `ExecuteMsg::BurnBaseFrom`
 pub fn burn_base_from(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     owner: String,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
+    let controller = CONTROLLER.load(deps.storage)?;
+    if info.sender != controller {
+        return Err(ContractError::Unauthorized {});
+    }
+
     // convert amount from base to token amount
     let multiplier = MULTIPLIER.load(deps.storage)?;
     let amount = base_to_token(amount, multiplier);
 
-    burn_from(deps, info, owner, amount)
+    burn_from(deps, env, info, owner, amount)
 }
 
 /// Handler for `ExecuteMsg::BurnFrom`
+/// Controller is allowed to burn tokens freely, any other sender is spending the allowance given
+/// by the `owner`.
 pub fn burn_from(
     mut deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     owner: String,
     amount: Uint128,
@@ -264,15 +475,16 @@ pub fn burn_from(
     let controller = CONTROLLER.load(deps.storage)?;
     let owner = deps.api.addr_validate(&owner)?;
 
-    if info.sender != controller {
-        return Err(ContractError::Unauthorized {});
-    }
-
     if amount == Uint128::zero() {
         return Err(ContractError::InvalidZeroAmount {});
     }
 
-    let ppt = DISTRIBUTION.load(deps.storage)?.points_per_token;
+    if info.sender != controller {
+        deduct_allowance(deps.storage, &owner, &info.sender, &env.block, amount)?;
+        can_transfer(deps.as_ref(), &env, owner.to_string(), amount)?;
+    }
+
+    let ppt = points_per_token(deps.storage)?;
 
     BALANCES.update(
         deps.storage,
@@ -284,12 +496,7 @@ pub fn burn_from(
                 .map_err(|_| ContractError::insufficient_tokens(balance, amount))
         },
     )?;
-    apply_points_correction(
-        deps.branch(),
-        &owner,
-        ppt.u128() as _,
-        -(amount.u128() as i128),
-    )?;
+    apply_points_correction(deps.branch(), &owner, &ppt, -(amount.u128() as i128))?;
 
     TOTAL_SUPPLY.update(deps.storage, |supply| -> Result<_, ContractError> {
         supply
@@ -297,11 +504,17 @@ pub fn burn_from(
             .map_err(|_| ContractError::insufficient_tokens(supply, amount))
     })?;
 
+    let event = events::Burn {
+        sender: info.sender.clone(),
+        account: owner.clone(),
+        amount: ctokens(&env, amount),
+    };
     let res = Response::new()
         .add_attribute("action", "burn_from")
         .add_attribute("from", owner)
         .add_attribute("by", info.sender)
-        .add_attribute("amount", amount);
+        .add_attribute("amount", amount)
+        .add_event(event.into());
     Ok(res)
 }
 
@@ -312,13 +525,19 @@ pub fn rebase(deps: DepsMut, info: MessageInfo, ratio: Decimal) -> Result<Respon
         return Err(ContractError::Unauthorized {});
     }
 
-    MULTIPLIER.update(deps.storage, |multiplier: Decimal| -> StdResult<_> {
+    let multiplier = MULTIPLIER.update(deps.storage, |multiplier: Decimal| -> StdResult<_> {
         Ok(multiplier * ratio)
     })?;
 
+    let event = events::Rebase {
+        sender: info.sender,
+        ratio,
+        multiplier,
+    };
     let res = Response::new()
         .add_attribute("action", "rebase")
-        .add_attribute("ratio", ratio.to_string());
+        .add_attribute("ratio", ratio.to_string())
+        .add_event(event.into());
 
     Ok(res)
 }
@@ -336,92 +555,291 @@ pub fn distribute(
         return Err(ContractError::NoHoldersToDistributeTo {});
     }
 
+    // Only distributed tokens can be sent along
+    for coin in &info.funds {
+        if !DISTRIBUTION.has(deps.storage, &Token::Native(coin.denom.clone())) {
+            return Err(ContractError::TokenNotDistributed(coin.denom.clone()));
+        }
+    }
+
     let sender = sender
         .map(|sender| deps.api.addr_validate(&sender))
         .transpose()?
         .unwrap_or(info.sender);
 
-    let mut distribution = DISTRIBUTION.load(deps.storage)?;
+    let distributions = DISTRIBUTION
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut resp = Response::new()
+        .add_attribute("action", "distribute_tokens")
+        .add_attribute("sender", sender.as_str());
+    let mut rewards = vec![];
 
-    let withdrawable: u128 = distribution.withdrawable_total.into();
+    for (token, mut distribution) in distributions {
+        let withdrawable: u128 = distribution.withdrawable_total.into();
 
-    let balance = distribution
-        .denom
-        .query_balance(deps.as_ref(), env.contract.address)?;
+        let balance = token.query_balance(deps.as_ref(), env.contract.address.clone())?;
 
-    let amount = balance - withdrawable;
-    if amount == 0 {
-        return Ok(Response::new());
+        // Tokens already assigned to holders must still be on the contract
+        let amount = balance
+            .checked_sub(withdrawable)
+            .ok_or_else(|| ContractError::insufficient_tokens(balance, withdrawable))?;
+        if amount == 0 {
+            continue;
+        }
+
+        distribution.distribute(amount.into(), total_supply.into())?;
+        DISTRIBUTION.save(deps.storage, &token, &distribution)?;
+
+        resp = resp.add_attribute("amount", amount.to_string());
+        rewards.push(token.amount(amount));
+        resp = match token {
+            Token::Native(denom) => resp.add_attribute("denom", denom),
+            Token::Cw20(address) => resp.add_attribute("cw20_address", address),
+        };
     }
 
-    // Distribution calculation:
-    // 1. Distributed amount is turned into points by scalling them by POINTS_SCALE;
-    // 2. The leftover from any previous distribution is added to be distributed now;
-    // 3. Calculating how much points would be distributed to receivers per token they own;
-    // 4. It is very much possible, that non-whole points should be paid for single token. To
-    //    overcome this, we distribute as much points as it is possible without non-whole division,
-    //    and leftover is stored for next distribution.
-    // 5. Distributed points per token are accumulated;
-    let leftover: u128 = distribution.points_leftover.into();
-    let points = amount * POINTS_SCALE + leftover;
-    let points_per_token = points / total_supply;
-    distribution.points_leftover = (points % total_supply).into();
-
-    // Everything goes back to 128-bits/16-bytes
-    // Full amount is added here to total withdrawable, as it should not be considered on its own
-    // on future distributions - even if because of calculation offsets it is not fully
-    // distributed, the error is handled by leftover.
-    distribution.points_per_token += Uint128::from(points_per_token);
-    distribution.distributed_total += Uint128::from(amount);
-    distribution.withdrawable_total += Uint128::from(amount);
-
-    DISTRIBUTION.save(deps.storage, &distribution)?;
+    if rewards.is_empty() {
+        return Ok(Response::new());
+    }
 
-    let mut resp = Response::new()
-        .add_attribute("action", "distribute_tokens")
-        .add_attribute("sender", sender.as_str())
-        .add_attribute("amount", amount.to_string());
+    Ok(resp.add_event(events::DistributeRewards { sender, rewards }.into()))
+}
 
-    match distribution.denom {
-        utils::token::Token::Native(denom) => resp = resp.add_attribute("denom", denom),
-        utils::token::Token::Cw20(address) => resp = resp.add_attribute("cw20_address", address),
+/// Handler for `ExecuteMsg::WithdrawFundsFor`
+fn withdraw_funds_for(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: String,
+) -> Result<Response, ContractError> {
+    let controller = CONTROLLER.load(deps.storage)?;
+    if info.sender != controller {
+        return Err(ContractError::Unauthorized {});
     }
 
-    Ok(resp)
+    let owner = deps.api.addr_validate(&owner)?;
+    withdraw_funds(deps, info.sender, owner)
 }
 
 /// Handler for `ExecuteMsg::WithdrawFunds`
-fn withdraw_funds(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
-    let mut distribution = DISTRIBUTION.load(deps.storage)?;
-    let mut adjustment = WITHDRAW_ADJUSTMENT
-        .may_load(deps.storage, &info.sender)?
-        .unwrap_or_default();
+fn withdraw_funds(deps: DepsMut, sender: Addr, owner: Addr) -> Result<Response, ContractError> {
+    let distributions = DISTRIBUTION
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
 
-    let token = withdrawable_funds(deps.as_ref(), &info.sender, &distribution, &adjustment)?;
-    if token.amount.is_zero() {
+    let mut resp = Response::new()
+        .add_attribute("action", "withdraw_tokens")
+        .add_attribute("owner", owner.as_str());
+    let mut rewards = vec![];
+
+    for (token, mut distribution) in distributions {
+        let mut adjustment = WITHDRAW_ADJUSTMENT
+            .may_load(deps.storage, (&owner, &token))?
+            .unwrap_or_default();
+
+        let funds = withdrawable_funds(deps.as_ref(), &owner, &token, &distribution, &adjustment)?;
+        if funds.amount.is_zero() {
+            continue;
+        }
+
+        adjustment.withdrawn_funds += funds.amount;
+        WITHDRAW_ADJUSTMENT.save(deps.storage, (&owner, &token), &adjustment)?;
+        distribution.withdrawable_total -= funds.amount;
+        DISTRIBUTION.save(deps.storage, &token, &distribution)?;
+
+        resp = resp
+            .add_attribute("amount", funds.amount.to_string())
+            .add_submessage(SubMsg::new(token.send_msg(owner.clone(), funds.amount)?));
+        rewards.push(token.amount(funds.amount));
+        resp = match token {
+            Token::Native(denom) => resp.add_attribute("denom", denom),
+            Token::Cw20(address) => resp.add_attribute("cw20_address", address),
+        };
+    }
+
+    if rewards.is_empty() {
         // Just do nothing
         return Ok(Response::new());
     }
 
-    adjustment.withdrawn_funds += token.amount;
-    WITHDRAW_ADJUSTMENT.save(deps.storage, &info.sender, &adjustment)?;
-    distribution.withdrawable_total -= token.amount;
-    DISTRIBUTION.save(deps.storage, &distribution)?;
+    let event = events::ClaimRewards {
+        sender,
+        account: owner,
+        markets: vec![],
+        rewards,
+    };
+    Ok(resp.add_event(event.into()))
+}
 
-    let mut resp = Response::new()
-        .add_attribute("action", "withdraw_tokens")
-        .add_attribute("owner", info.sender.as_str())
-        .add_attribute("amount", token.amount.to_string())
-        .add_submessage(SubMsg::new(
-            token.denom.send_msg(info.sender, token.amount)?,
-        ));
+/// Handler for `ExecuteMsg::AddDistributedToken`
+fn add_distributed_token(
+    deps: DepsMut,
+    info: MessageInfo,
+    token: Token,
+) -> Result<Response, ContractError> {
+    let controller = CONTROLLER.load(deps.storage)?;
+    if info.sender != controller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if DISTRIBUTION.has(deps.storage, &token) {
+        return Err(ContractError::TokenAlreadyDistributed(token.to_string()));
+    }
+
+    // Nothing was distributed in this token yet, so no corrections are needed for current holders
+    DISTRIBUTION.save(deps.storage, &token, &Distribution::default())?;
+
+    let res = Response::new()
+        .add_attribute("action", "add_distributed_token")
+        .add_attribute("token", token.to_string())
+        .add_event(
+            events::AddDistributedToken {
+                sender: info.sender,
+                token,
+            }
+            .into(),
+        );
+
+    Ok(res)
+}
+
+const LOGO_SIZE_CAP: usize = 5 * 1024;
+
+/// Checks if data starts with XML preamble
+fn verify_xml_preamble(data: &[u8]) -> Result<(), ContractError> {
+    // The easiest way to perform this check would be just match on regex, however regex
+    // compilation is heavy and probably not worth it.
+
+    let preamble = data
+        .split_inclusive(|c| *c == b'>')
+        .next()
+        .ok_or(ContractError::InvalidXmlPreamble {})?;
+
+    const PREFIX: &[u8] = b"<?xml ";
+    const POSTFIX: &[u8] = b"?>";
+
+    if !(preamble.starts_with(PREFIX) && preamble.ends_with(POSTFIX)) {
+        Err(ContractError::InvalidXmlPreamble {})
+    } else {
+        Ok(())
+    }
+}
+
+/// Validates XML logo
+fn verify_xml_logo(logo: &[u8]) -> Result<(), ContractError> {
+    verify_xml_preamble(logo)?;
+
+    if logo.len() > LOGO_SIZE_CAP {
+        Err(ContractError::LogoTooBig {})
+    } else {
+        Ok(())
+    }
+}
+
+/// Validates png logo
+fn verify_png_logo(logo: &[u8]) -> Result<(), ContractError> {
+    // PNG header format:
+    // 0x89 - magic byte, out of ASCII table to fail on 7-bit systems
+    // "PNG" ascii representation
+    // [0x0d, 0x0a] - dos style line ending
+    // 0x1a - dos control character, stop displaying rest of the file
+    // 0x0a - unix style line ending
+    const HEADER: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+    if logo.len() > LOGO_SIZE_CAP {
+        Err(ContractError::LogoTooBig {})
+    } else if !logo.starts_with(&HEADER) {
+        Err(ContractError::InvalidPngHeader {})
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks if passed logo is correct, and if not, returns an error
+fn verify_logo(logo: &Logo) -> Result<(), ContractError> {
+    match logo {
+        Logo::Embedded(EmbeddedLogo::Svg(logo)) => verify_xml_logo(logo),
+        Logo::Embedded(EmbeddedLogo::Png(logo)) => verify_png_logo(logo),
+        // Any reasonable url validation would be regex based, probably not worth it
+        Logo::Url(_) => Ok(()),
+    }
+}
+
+/// Handler for `ExecuteMsg::UpdateMarketing`
+fn update_marketing(
+    deps: DepsMut,
+    info: MessageInfo,
+    project: Option<String>,
+    description: Option<String>,
+    marketing: Option<String>,
+) -> Result<Response, ContractError> {
+    let controller = CONTROLLER.load(deps.storage)?;
+    if info.sender != controller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut marketing_info = MARKETING_INFO.may_load(deps.storage)?.unwrap_or_default();
+
+    match project {
+        Some(empty) if empty.trim().is_empty() => marketing_info.project = None,
+        Some(project) => marketing_info.project = Some(project),
+        None => (),
+    }
+
+    match description {
+        Some(empty) if empty.trim().is_empty() => marketing_info.description = None,
+        Some(description) => marketing_info.description = Some(description),
+        None => (),
+    }
+
+    match marketing {
+        Some(empty) if empty.trim().is_empty() => marketing_info.marketing = None,
+        Some(marketing) => marketing_info.marketing = Some(deps.api.addr_validate(&marketing)?),
+        None => (),
+    }
 
-    match distribution.denom {
-        utils::token::Token::Native(denom) => resp = resp.add_attribute("denom", denom),
-        utils::token::Token::Cw20(address) => resp = resp.add_attribute("cw20_address", address),
+    MARKETING_INFO.save(deps.storage, &marketing_info)?;
+
+    let res = Response::new()
+        .add_attribute("action", "update_marketing")
+        .add_event(
+            events::UpdateMarketing {
+                sender: info.sender,
+            }
+            .into(),
+        );
+
+    Ok(res)
+}
+
+/// Handler for `ExecuteMsg::UploadLogo`
+fn upload_logo(deps: DepsMut, info: MessageInfo, logo: Logo) -> Result<Response, ContractError> {
+    let controller = CONTROLLER.load(deps.storage)?;
+    if info.sender != controller {
+        return Err(ContractError::Unauthorized {});
     }
 
-    Ok(resp)
+    verify_logo(&logo)?;
+
+    LOGO.save(deps.storage, &logo)?;
+
+    let mut marketing_info = MARKETING_INFO.may_load(deps.storage)?.unwrap_or_default();
+    marketing_info.logo = Some(match logo {
+        Logo::Url(url) => LogoInfo::Url(url),
+        Logo::Embedded(_) => LogoInfo::Embedded,
+    });
+    MARKETING_INFO.save(deps.storage, &marketing_info)?;
+
+    let res = Response::new()
+        .add_attribute("action", "upload_logo")
+        .add_event(
+            events::UploadLogo {
+                sender: info.sender,
+            }
+            .into(),
+        );
+
+    Ok(res)
 }
 
 /// Execution entry point
@@ -440,13 +858,13 @@ pub fn execute(
             transfer(deps, env, info, recipient, amount)
         }
         TransferFrom {
-            sender,
+            owner,
             recipient,
             amount,
         } => {
             let recipient = deps.api.addr_validate(&recipient)?;
-            let sender = deps.api.addr_validate(&sender)?;
-            transfer_from(deps, info, sender, recipient, amount)
+            let owner = deps.api.addr_validate(&owner)?;
+            transfer_from(deps, env, info, owner, recipient, amount)
         }
         TransferBaseFrom {
             sender,
@@ -463,7 +881,7 @@ pub fn execute(
             let sender = deps.api.addr_validate(&sender)?;
             let multiplier = MULTIPLIER.load(deps.storage)?;
             let amount = base_to_token(amount, multiplier);
-            transfer_from(deps, info, sender, recipient, amount)
+            transfer_from(deps, env, info, sender, recipient, amount)
         }
         Send {
             contract,
@@ -473,13 +891,41 @@ pub fn execute(
             let recipient = deps.api.addr_validate(&contract)?;
             send(deps, env, info, recipient, amount, msg)
         }
-        Mint { recipient, amount } => mint(deps, info, recipient, amount),
-        MintBase { recipient, amount } => mint_base(deps, info, recipient, amount),
-        BurnFrom { owner, amount } => burn_from(deps, info, owner, amount),
-        BurnBaseFrom { owner, amount } => burn_base_from(deps, info, owner, amount),
+        SendFrom {
+            owner,
+            contract,
+            amount,
+            msg,
+        } => {
+            let owner = deps.api.addr_validate(&owner)?;
+            let recipient = deps.api.addr_validate(&contract)?;
+            send_from(deps, env, info, owner, recipient, amount, msg)
+        }
+        IncreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => increase_allowance(deps, env, info, spender, amount, expires),
+        DecreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => decrease_allowance(deps, env, info, spender, amount, expires),
+        Mint { recipient, amount } => mint(deps, env, info, recipient, amount),
+        MintBase { recipient, amount } => mint_base(deps, env, info, recipient, amount),
+        BurnFrom { owner, amount } => burn_from(deps, env, info, owner, amount),
+        BurnBaseFrom { owner, amount } => burn_base_from(deps, env, info, owner, amount),
         Rebase { ratio } => rebase(deps, info, ratio),
         Distribute { sender } => distribute(deps, env, info, sender),
-        WithdrawFunds {} => withdraw_funds(deps, info),
+        WithdrawFunds {} => withdraw_funds(deps, info.sender.clone(), info.sender),
+        WithdrawFundsFor { owner } => withdraw_funds_for(deps, info, owner),
+        AddDistributedToken { token } => add_distributed_token(deps, info, token),
+        UpdateMarketing {
+            project,
+            description,
+            marketing,
+        } => update_marketing(deps, info, project, description, marketing),
+        UploadLogo(logo) => upload_logo(deps, info, logo),
     }
 }
 
@@ -528,37 +974,143 @@ pub fn query_multiplier(deps: Deps) -> StdResult<MultiplierResponse> {
 
 /// Handler for `QueryMsg::DistributedFunds`
 pub fn query_distributed_funds(deps: Deps) -> StdResult<FundsResponse> {
-    let distribution = DISTRIBUTION.load(deps.storage)?;
-    Ok(FundsResponse {
-        funds: Coin::new(distribution.distributed_total.into(), distribution.denom),
-    })
+    let funds = DISTRIBUTION
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            item.map(|(token, distribution)| {
+                Coin::new(distribution.distributed_total.into(), token)
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(FundsResponse { funds })
 }
 
 /// Handler for `QueryMsg::UndistributedFunds`
 pub fn query_undistributed_funds(deps: Deps, env: Env) -> StdResult<FundsResponse> {
-    let distribution = DISTRIBUTION.load(deps.storage)?;
-    let balance = distribution
-        .denom
-        .query_balance(deps, env.contract.address)?;
-    Ok(FundsResponse {
-        funds: Coin::new(
-            balance - distribution.withdrawable_total.u128(),
-            distribution.denom,
-        ),
-    })
+    let funds = DISTRIBUTION
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (token, distribution) = item?;
+            let balance = token.query_balance(deps, env.contract.address.clone())?;
+            Ok(Coin::new(
+                balance - distribution.withdrawable_total.u128(),
+                token,
+            ))
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(FundsResponse { funds })
 }
 
 /// Handler for `QueryMsg::WithdrawableFunds`
 pub fn query_withdrawable_funds(deps: Deps, owner: String) -> StdResult<FundsResponse> {
     let owner = Addr::unchecked(owner);
-    let distribution = DISTRIBUTION.load(deps.storage)?;
-    let adjustment = WITHDRAW_ADJUSTMENT
-        .may_load(deps.storage, &owner)?
+    let funds = DISTRIBUTION
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (token, distribution) = item?;
+            let adjustment = WITHDRAW_ADJUSTMENT
+                .may_load(deps.storage, (&owner, &token))?
+                .unwrap_or_default();
+            withdrawable_funds(deps, &owner, &token, &distribution, &adjustment)
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(FundsResponse { funds })
+}
+
+/// Handler for `QueryMsg::Allowance`
+pub fn query_allowance(deps: Deps, owner: String, spender: String) -> StdResult<AllowanceResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let spender = deps.api.addr_validate(&spender)?;
+    let allowance = ALLOWANCES
+        .may_load(deps.storage, (&owner, &spender))?
         .unwrap_or_default();
+    Ok(allowance)
+}
 
-    Ok(FundsResponse {
-        funds: withdrawable_funds(deps, &owner, &distribution, &adjustment)?,
-    })
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// Handler for `QueryMsg::AllAllowances`
+pub fn query_all_allowances(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllAllowancesResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let allowances = ALLOWANCES
+        .prefix(&owner)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(spender, allowance)| AllowanceInfo {
+                spender: spender.into(),
+                allowance: allowance.allowance,
+                expires: allowance.expires,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(AllAllowancesResponse { allowances })
+}
+
+/// Handler for `QueryMsg::AllAccounts`
+pub fn query_all_accounts(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllAccountsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+    let multiplier = MULTIPLIER.load(deps.storage)?;
+
+    let accounts = BALANCES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(address, balance)| AccountBalance {
+                address: address.into(),
+                balance,
+                base_balance: token_to_base(balance, multiplier),
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(AllAccountsResponse { accounts })
+}
+
+/// Handler for `QueryMsg::MarketingInfo`
+pub fn query_marketing_info(deps: Deps) -> StdResult<MarketingInfoResponse> {
+    Ok(MARKETING_INFO.may_load(deps.storage)?.unwrap_or_default())
+}
+
+/// Handler for `QueryMsg::DownloadLogo`
+pub fn query_download_logo(deps: Deps) -> StdResult<DownloadLogoResponse> {
+    let logo = LOGO.load(deps.storage)?;
+    match logo {
+        Logo::Embedded(EmbeddedLogo::Svg(logo)) => Ok(DownloadLogoResponse {
+            mime_type: "image/svg+xml".to_owned(),
+            data: logo,
+        }),
+        Logo::Embedded(EmbeddedLogo::Png(logo)) => Ok(DownloadLogoResponse {
+            mime_type: "image/png".to_owned(),
+            data: logo,
+        }),
+        Logo::Url(_) => Err(StdError::not_found("logo")),
+    }
 }
 
 /// `QueryMsg` entry point
@@ -574,42 +1126,60 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         DistributedFunds {} => to_binary(&query_distributed_funds(deps)?),
         UndistributedFunds {} => to_binary(&query_undistributed_funds(deps, env)?),
         WithdrawableFunds { owner } => to_binary(&query_withdrawable_funds(deps, owner)?),
+        Allowance { owner, spender } => to_binary(&query_allowance(deps, owner, spender)?),
+        AllAllowances {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_all_allowances(deps, owner, start_after, limit)?),
+        AllAccounts { start_after, limit } => {
+            to_binary(&query_all_accounts(deps, start_after, limit)?)
+        }
+        MarketingInfo {} => to_binary(&query_marketing_info(deps)?),
+        DownloadLogo {} => to_binary(&query_download_logo(deps)?),
     }
 }
 
-/// Calculates withdrawable funds from distribution and adjustment info.
+/// Calculates withdrawable funds of `token` from distribution and adjustment info.
 pub fn withdrawable_funds(
     deps: Deps,
     owner: &Addr,
+    token: &Token,
     distribution: &Distribution,
     adjustment: &WithdrawAdjustment,
 ) -> StdResult<Coin> {
-    let ppt: u128 = distribution.points_per_token.into();
-    let tokens: u128 = BALANCES
-        .may_load(deps.storage, owner)?
-        .unwrap_or_default()
-        .into();
-    let correction: i128 = adjustment.points_correction.into();
-    let withdrawn: u128 = adjustment.withdrawn_funds.into();
-    let points = (ppt * tokens) as i128;
-    let points = points + correction;
-    let amount = points as u128 / POINTS_SCALE;
-    let amount = amount - withdrawn;
-
-    Ok(Coin::new(amount, distribution.denom.clone()))
-}
-
-/// Applies points correction for given address.
-/// `ppt` is current value from `POINTS_PER_TOKEN` - not loaded in function, to
+    let tokens = BALANCES.may_load(deps.storage, owner)?.unwrap_or_default();
+    let amount = distribution.withdrawable(tokens, adjustment)?;
+
+    Ok(Coin::new(amount.u128(), token.clone()))
+}
+
+/// Loads current points per token of every distributed token - loaded once to avoid multiple
+/// queries on bulk updates.
+pub fn points_per_token(storage: &dyn Storage) -> StdResult<Vec<(Token, Uint128)>> {
+    DISTRIBUTION
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(token, distribution)| (token, distribution.points_per_token)))
+        .collect()
+}
+
+/// Applies points correction for given address, for every distributed token.
+/// `ppt` is current value from `points_per_token` - not loaded in function, to
 /// avoid multiple queries on bulk updates.
 /// `diff` is the weight change
-pub fn apply_points_correction(deps: DepsMut, addr: &Addr, ppt: u128, diff: i128) -> StdResult<()> {
-    WITHDRAW_ADJUSTMENT.update(deps.storage, addr, |old| -> StdResult<_> {
-        let mut old = old.unwrap_or_default();
-        let points_correction: i128 = old.points_correction.into();
-        old.points_correction = (points_correction - ppt as i128 * diff).into();
-        Ok(old)
-    })?;
+pub fn apply_points_correction(
+    deps: DepsMut,
+    addr: &Addr,
+    ppt: &[(Token, Uint128)],
+    diff: i128,
+) -> StdResult<()> {
+    for (token, ppt) in ppt {
+        WITHDRAW_ADJUSTMENT.update(deps.storage, (addr, token), |old| -> StdResult<_> {
+            let mut old = old.unwrap_or_default();
+            old.apply_correction(*ppt, diff)?;
+            Ok(old)
+        })?;
+    }
     Ok(())
 }
 
@@ -618,7 +1188,6 @@ mod tests {
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
 
     use super::*;
-    use utils::token::Token;
 
     #[test]
     fn rebase_works() {
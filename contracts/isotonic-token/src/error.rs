@@ -23,6 +23,33 @@ pub enum ContractError {
 
     #[error("Cw20 tokens are not supported yet")]
     Cw20TokensNotSupported,
+
+    #[error("Token {0} is not distributed by this contract")]
+    TokenNotDistributed(String),
+
+    #[error("Token {0} is already distributed by this contract")]
+    TokenAlreadyDistributed(String),
+
+    #[error("Cannot set allowance to own account")]
+    CannotSetOwnAccount {},
+
+    #[error("Invalid expiration value")]
+    InvalidExpiration {},
+
+    #[error("No allowance for this account")]
+    NoAllowance {},
+
+    #[error("Allowance is expired")]
+    Expired {},
+
+    #[error("Logo binary data exceeds 5KB limit")]
+    LogoTooBig {},
+
+    #[error("Invalid xml preamble for SVG")]
+    InvalidXmlPreamble {},
+
+    #[error("Invalid png header")]
+    InvalidPngHeader {},
 }
 
 impl ContractError {
@@ -0,0 +1,184 @@
+use cosmwasm_std::{OverflowError, OverflowOperation, Uint256};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::de::Visitor;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::ops::Neg;
+use std::str::FromStr;
+
+/// Signed 256-bit integer, as `cosmwasm_std` is missing one. Stored as sign and magnitude, and
+/// (de)serialized as string - the same way `Int128` used to be, so values stored with 128 bits
+/// are still readable.
+///
+/// Only operations required for points calculations are provided.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct Int256 {
+    /// Never set for zero, so there is single representation of every value
+    negative: bool,
+    abs: Uint256,
+}
+
+impl Int256 {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn new(negative: bool, abs: Uint256) -> Self {
+        Self {
+            negative: negative && !abs.is_zero(),
+            abs,
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn checked_add(self, other: Self) -> Result<Self, OverflowError> {
+        let overflow = || OverflowError::new(OverflowOperation::Add, self, other);
+
+        if self.negative == other.negative {
+            let abs = self.abs.checked_add(other.abs).map_err(|_| overflow())?;
+            Ok(Self::new(self.negative, abs))
+        } else if self.abs >= other.abs {
+            Ok(Self::new(self.negative, self.abs - other.abs))
+        } else {
+            Ok(Self::new(other.negative, other.abs - self.abs))
+        }
+    }
+
+    pub fn checked_sub(self, other: Self) -> Result<Self, OverflowError> {
+        self.checked_add(-other)
+            .map_err(|_| OverflowError::new(OverflowOperation::Sub, self, other))
+    }
+
+    /// Converts to unsigned value, `None` if negative.
+    pub fn to_unsigned(self) -> Option<Uint256> {
+        if self.negative {
+            None
+        } else {
+            Some(self.abs)
+        }
+    }
+}
+
+impl Neg for Int256 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(!self.negative, self.abs)
+    }
+}
+
+impl From<Uint256> for Int256 {
+    fn from(val: Uint256) -> Self {
+        Self::new(false, val)
+    }
+}
+
+impl From<i128> for Int256 {
+    fn from(val: i128) -> Self {
+        Self::new(val < 0, val.unsigned_abs().into())
+    }
+}
+
+impl fmt::Display for Int256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", self.abs)
+    }
+}
+
+impl FromStr for Int256 {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, abs) = match s.strip_prefix('-') {
+            Some(abs) => (true, abs),
+            None => (false, s),
+        };
+        let abs = Uint256::from_str(abs).map_err(|e| format!("invalid Int256 '{}' - {}", s, e))?;
+        Ok(Self::new(negative, abs))
+    }
+}
+
+impl JsonSchema for Int256 {
+    fn schema_name() -> String {
+        "Int256".to_owned()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        String::json_schema(gen)
+    }
+}
+
+impl Serialize for Int256 {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ser.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Int256 {
+    fn deserialize<D>(de: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        de.deserialize_str(Int256Visitor)
+    }
+}
+
+struct Int256Visitor;
+
+impl<'de> Visitor<'de> for Int256Visitor {
+    type Value = Int256;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("string-encoded integer")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        v.parse().map_err(E::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_and_sub() {
+        let a = Int256::from(-5i128);
+        let b = Int256::from(3i128);
+        assert_eq!(a.checked_add(b).unwrap(), Int256::from(-2i128));
+        assert_eq!(b.checked_sub(a).unwrap(), Int256::from(8i128));
+        assert_eq!(a.checked_sub(a).unwrap(), Int256::zero());
+        assert!(!a.checked_sub(a).unwrap().is_negative());
+
+        let max = Int256::from(Uint256::MAX);
+        max.checked_add(b).unwrap_err();
+        assert_eq!(
+            max.checked_add(a).unwrap().to_unsigned(),
+            Some(Uint256::MAX - Uint256::from(5u8))
+        );
+    }
+
+    #[test]
+    fn serialized_as_string() {
+        let val = Int256::from(-1234i128);
+        assert_eq!(cosmwasm_std::to_vec(&val).unwrap(), br#""-1234""#);
+        let parsed: Int256 = cosmwasm_std::from_slice(br#""-1234""#).unwrap();
+        assert_eq!(parsed, val);
+        let parsed: Int256 = cosmwasm_std::from_slice(br#""0""#).unwrap();
+        assert_eq!(parsed, Int256::zero());
+    }
+}
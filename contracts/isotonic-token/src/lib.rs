@@ -1,6 +1,6 @@
 pub mod contract;
 pub mod error;
-pub mod i128;
+pub mod i256;
 pub mod msg;
 #[cfg(test)]
 mod multitest;
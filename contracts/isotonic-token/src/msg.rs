@@ -1,5 +1,6 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Binary, Decimal, Uint128};
+use cw20::{Expiration, Logo};
 
 use utils::{amount::token_to_base, coin::Coin, token::Token};
 
@@ -14,7 +15,8 @@ pub struct InstantiateMsg {
     /// Controller is contract allowed to ming, burn, rebase, and must be checked with to
     /// enable transfer. Usually it is an isotonic market contract.
     pub controller: String,
-    /// Token which will be distributed via this contract by cw2222 interface
+    /// Token which will be distributed via this contract by cw2222 interface. More tokens can be
+    /// added later with `ExecuteMsg::AddDistributedToken`.
     pub distributed_token: Token,
 }
 
@@ -25,11 +27,11 @@ pub enum ExecuteMsg {
     /// controller.
     Transfer { recipient: String, amount: Uint128 },
     /// TransferFrom allows to order transfer of tokens from source to destination.
-    /// Proper authentication is in place - can be called only be controller.
-    /// Requires check for transfer possibility by `ControllerQuery::CanTransfer` call to
-    /// controller.
+    /// Controller can always call it. Any other sender must have been given an allowance by
+    /// `owner` before, and then requires check for transfer possibility by
+    /// `ControllerQuery::CanTransfer` call to controller.
     TransferFrom {
-        sender: String,
+        owner: String,
         recipient: String,
         amount: Uint128,
     },
@@ -50,13 +52,38 @@ pub enum ExecuteMsg {
         amount: Uint128,
         msg: Binary,
     },
+    /// Like `Send`, but uses an allowance given by `owner` to the sender.
+    /// Requires check for transfer possibility by `ControllerQuery::CanTransfer` call to
+    /// controller.
+    SendFrom {
+        owner: String,
+        contract: String,
+        amount: Uint128,
+        msg: Binary,
+    },
+    /// Allows `spender` to access an additional `amount` tokens from the owner's (sender) account.
+    /// If `expires` is `Some()`, overwrites current allowance expiration with this one.
+    IncreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// Lowers the `spender`'s access of tokens from the owner's (sender) account by `amount`.
+    /// If `expires` is `Some()`, overwrites current allowance expiration with this one.
+    DecreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
     /// Reserved for controller
     Mint { recipient: String, amount: Uint128 },
     /// Like `Mint`, but the `amount` is specified in base token amount, not amount of this token.
     ///
     /// Reserved for controller
     MintBase { recipient: String, amount: Uint128 },
-    /// Reserved for controller
+    /// Burns tokens from `owner` account. Controller can always call it. Any other sender must
+    /// have been given an allowance by `owner` before, and then requires check for transfer
+    /// possibility by `ControllerQuery::CanTransfer` call to controller.
     BurnFrom { owner: String, amount: Uint128 },
     /// Like `BurnFrom`, but the `amount` is specified in base token amount, not amount of this token.
     ///
@@ -66,13 +93,40 @@ pub enum ExecuteMsg {
     /// multiplier *= ratio
     Rebase { ratio: Decimal },
     /// Distributed tokens using cw2222 mechanism. Tokens send with this message as distributed
-    /// alongside with all tokens send until now which are not yet distributed.
+    /// alongside with all tokens send until now which are not yet distributed. Only tokens
+    /// added as distributed tokens are accepted.
     Distribute {
         /// Just for informational purposes - would overwrite message sender in generated event.
         sender: Option<String>,
     },
-    /// Withdraw tokens distributed before
+    /// Withdraw all tokens distributed before
     WithdrawFunds {},
+    /// Like `WithdrawFunds`, but withdraws tokens distributed to `owner`. Funds are always sent
+    /// to the `owner`.
+    ///
+    /// Reserved for controller
+    WithdrawFundsFor { owner: String },
+    /// Adds another token to be distributed via cw2222 interface.
+    ///
+    /// Reserved for controller
+    AddDistributedToken { token: Token },
+    /// Updates the metadata displayed by explorers. Fields set to `Some("")` are cleared,
+    /// fields set to `None` are left untouched.
+    ///
+    /// Reserved for controller
+    UpdateMarketing {
+        /// A URL pointing to the project behind this token.
+        project: Option<String>,
+        /// A longer description of the token and it's utility. Designed for tooltips or such
+        description: Option<String>,
+        /// Address reported as the marketing contact in `MarketingInfo`. It is informational
+        /// only - updates are always reserved for the controller.
+        marketing: Option<String>,
+    },
+    /// Sets the logo of the token to the URL, SVG or PNG passed.
+    ///
+    /// Reserved for controller
+    UploadLogo(Logo),
 }
 
 #[cw_serde]
@@ -105,15 +159,39 @@ pub enum QueryMsg {
     /// Returns the global multiplier factor.
     #[returns(MultiplierResponse)]
     Multiplier {},
-    /// Funds distributed by this contract.
+    /// Funds distributed by this contract, one entry per distributed token.
     #[returns(FundsResponse)]
     DistributedFunds {},
-    /// Funds send to this contact but not yet distributed.
+    /// Funds send to this contact but not yet distributed, one entry per distributed token.
     #[returns(FundsResponse)]
     UndistributedFunds {},
-    /// Queries for funds distributed but not yet withdrawn by owner
-    #[returns(Coin)]
+    /// Queries for funds distributed but not yet withdrawn by owner, one entry per distributed
+    /// token.
+    #[returns(FundsResponse)]
     WithdrawableFunds { owner: String },
+    /// Returns how much spender can use from owner account, 0 if unset.
+    #[returns(cw20::AllowanceResponse)]
+    Allowance { owner: String, spender: String },
+    /// Returns all allowances this owner has approved. Supports pagination.
+    #[returns(cw20::AllAllowancesResponse)]
+    AllAllowances {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns all accounts holding the token together with their balances. Supports pagination.
+    #[returns(AllAccountsResponse)]
+    AllAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns marketing metadata of the token.
+    #[returns(cw20::MarketingInfoResponse)]
+    MarketingInfo {},
+    /// Downloads the embedded logo data (if stored on chain). Errors if no logo data is stored
+    /// for this contract.
+    #[returns(cw20::DownloadLogoResponse)]
+    DownloadLogo {},
 }
 
 #[cw_serde]
@@ -137,6 +215,20 @@ impl TokenInfoResponse {
     }
 }
 
+#[cw_serde]
+pub struct AccountBalance {
+    pub address: String,
+    /// Balance in this token
+    pub balance: Uint128,
+    /// Balance in base tokens
+    pub base_balance: Uint128,
+}
+
+#[cw_serde]
+pub struct AllAccountsResponse {
+    pub accounts: Vec<AccountBalance>,
+}
+
 #[cw_serde]
 pub struct MultiplierResponse {
     pub multiplier: Decimal,
@@ -144,5 +236,5 @@ pub struct MultiplierResponse {
 
 #[cw_serde]
 pub struct FundsResponse {
-    pub funds: Coin,
+    pub funds: Vec<Coin>,
 }
@@ -1,9 +1,14 @@
+pub mod allowances;
 pub mod controller;
+pub mod events;
+pub mod fair_distribution;
+pub mod marketing;
 pub mod rebasing;
 pub mod receiver;
 pub mod suite;
 
 use cosmwasm_std::{coins, Decimal, Event, Uint128};
+use utils::amount::token_to_base;
 use utils::coin::coin_native;
 
 use crate::msg::TokenInfoResponse;
@@ -37,11 +42,11 @@ fn fresh_queries() {
     assert_eq!(suite.query_balance(controller).unwrap(), Uint128::zero());
     assert_eq!(
         suite.query_distributed_funds().unwrap(),
-        coin_native(0, "Reward")
+        vec![coin_native(0, "Reward")]
     );
     assert_eq!(
         suite.query_undistributed_funds().unwrap(),
-        coin_native(0, "Reward")
+        vec![coin_native(0, "Reward")]
     );
 }
 
@@ -166,12 +171,67 @@ mod burning {
         // Actually burning
         let err = suite.burn(lender, lender, Uint128::new(150)).unwrap_err();
 
-        assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+        assert_eq!(ContractError::NoAllowance {}, err.downcast().unwrap());
         assert_eq!(suite.query_balance(lender).unwrap(), Uint128::new(100u128));
         assert_eq!(suite.query_balance(controller).unwrap(), Uint128::zero());
     }
 }
 
+mod all_accounts {
+    use super::*;
+    use crate::msg::AccountBalance;
+
+    #[test]
+    fn lists_holders_with_base_balances() {
+        let mut suite = Suite::new();
+        let controller = suite.controller();
+        let controller = controller.as_str();
+
+        suite.mint(controller, "alice", Uint128::new(100)).unwrap();
+        suite.mint(controller, "bob", Uint128::new(200)).unwrap();
+        suite.mint(controller, "carol", Uint128::new(300)).unwrap();
+
+        let multiplier = suite.query_multiplier().unwrap();
+        let accounts = suite.query_all_accounts(None, None).unwrap().accounts;
+        assert_eq!(
+            accounts,
+            vec![
+                AccountBalance {
+                    address: "alice".to_owned(),
+                    balance: Uint128::new(100),
+                    base_balance: token_to_base(Uint128::new(100), multiplier),
+                },
+                AccountBalance {
+                    address: "bob".to_owned(),
+                    balance: Uint128::new(200),
+                    base_balance: token_to_base(Uint128::new(200), multiplier),
+                },
+                AccountBalance {
+                    address: "carol".to_owned(),
+                    balance: Uint128::new(300),
+                    base_balance: token_to_base(Uint128::new(300), multiplier),
+                },
+            ]
+        );
+
+        let accounts = suite
+            .query_all_accounts("alice".to_owned(), 1)
+            .unwrap()
+            .accounts;
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].address, "bob");
+
+        // Base balances follow the rebase
+        suite.rebase(controller, Decimal::percent(200)).unwrap();
+        let accounts = suite.query_all_accounts(None, 1).unwrap().accounts;
+        assert_eq!(accounts[0].balance, Uint128::new(100));
+        assert_eq!(
+            accounts[0].base_balance,
+            token_to_base(Uint128::new(100), multiplier * Decimal::percent(200))
+        );
+    }
+}
+
 mod transfer {
     use super::*;
 
@@ -484,7 +544,9 @@ mod send {
 }
 
 mod distribution {
+    use cosmwasm_std::coin;
     use utils::coin::coin_native;
+    use utils::token::Token;
 
     use super::*;
 
@@ -530,24 +592,24 @@ mod distribution {
 
         assert_eq!(
             suite.query_withdrawable_funds(members[0]).unwrap(),
-            coin_native(50, reward)
+            vec![coin_native(50, reward)]
         );
         assert_eq!(
             suite.query_withdrawable_funds(members[1]).unwrap(),
-            coin_native(100, reward)
+            vec![coin_native(100, reward)]
         );
         assert_eq!(
             suite.query_withdrawable_funds(members[2]).unwrap(),
-            coin_native(250, reward)
+            vec![coin_native(250, reward)]
         );
 
         assert_eq!(
             suite.query_distributed_funds().unwrap(),
-            coin_native(400, reward)
+            vec![coin_native(400, reward)]
         );
         assert_eq!(
             suite.query_undistributed_funds().unwrap(),
-            coin_native(0, reward)
+            vec![coin_native(0, reward)]
         );
 
         // Funds withdrawal
@@ -588,11 +650,11 @@ mod distribution {
 
         assert_eq!(
             suite.query_distributed_funds().unwrap(),
-            coin_native(400, reward)
+            vec![coin_native(400, reward)]
         );
         assert_eq!(
             suite.query_undistributed_funds().unwrap(),
-            coin_native(0, reward)
+            vec![coin_native(0, reward)]
         );
 
         suite.withdraw_funds(members[0]).unwrap();
@@ -611,11 +673,11 @@ mod distribution {
 
         assert_eq!(
             suite.query_distributed_funds().unwrap(),
-            coin_native(1000, reward)
+            vec![coin_native(1000, reward)]
         );
         assert_eq!(
             suite.query_undistributed_funds().unwrap(),
-            coin_native(0, reward)
+            vec![coin_native(0, reward)]
         );
 
         suite.withdraw_funds(members[0]).unwrap();
@@ -655,11 +717,11 @@ mod distribution {
 
         assert_eq!(
             suite.query_distributed_funds().unwrap(),
-            coin_native(400, reward)
+            vec![coin_native(400, reward)]
         );
         assert_eq!(
             suite.query_undistributed_funds().unwrap(),
-            coin_native(0, reward)
+            vec![coin_native(0, reward)]
         );
 
         suite
@@ -668,11 +730,11 @@ mod distribution {
 
         assert_eq!(
             suite.query_distributed_funds().unwrap(),
-            coin_native(1000, reward)
+            vec![coin_native(1000, reward)]
         );
         assert_eq!(
             suite.query_undistributed_funds().unwrap(),
-            coin_native(0, reward)
+            vec![coin_native(0, reward)]
         );
 
         suite.withdraw_funds(members[0]).unwrap();
@@ -896,6 +958,104 @@ mod distribution {
         assert_eq!(suite.native_balance(members[2], reward).unwrap(), 1300);
     }
 
+    #[test]
+    fn multiple_tokens_distributed() {
+        let members = ["member1", "member2", "member3"];
+        let reward = "Reward";
+        let partner = "Partner";
+
+        let mut suite = SuiteBuilder::new()
+            .with_distributed_native_token(reward)
+            .with_funds(members[2], vec![coin(400, reward), coin(900, partner)])
+            .build();
+
+        let controller = suite.controller();
+        let controller = controller.as_str();
+
+        // Partner token is not distributed yet
+        let err = suite
+            .distribute(members[2], None, &coins(900, partner))
+            .unwrap_err();
+        assert_eq!(
+            ContractError::TokenNotDistributed(partner.to_owned()),
+            err.downcast().unwrap()
+        );
+
+        suite
+            .add_distributed_token(controller, Token::Native(partner.to_owned()))
+            .unwrap();
+        let err = suite
+            .add_distributed_token(controller, Token::Native(partner.to_owned()))
+            .unwrap_err();
+        assert_eq!(
+            ContractError::TokenAlreadyDistributed(partner.to_owned()),
+            err.downcast().unwrap()
+        );
+        let err = suite
+            .add_distributed_token(members[0], Token::Native("other".to_owned()))
+            .unwrap_err();
+        assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+        // member1 gets 1/3 and member2 2/3
+        suite.mint(controller, members[0], Uint128::new(1)).unwrap();
+        suite.mint(controller, members[1], Uint128::new(2)).unwrap();
+
+        suite
+            .distribute(members[2], None, &[coin(900, partner), coin(400, reward)])
+            .unwrap();
+
+        assert_eq!(
+            suite.query_distributed_funds().unwrap(),
+            vec![coin_native(900, partner), coin_native(400, reward)]
+        );
+        assert_eq!(
+            suite.query_withdrawable_funds(members[1]).unwrap(),
+            vec![coin_native(600, partner), coin_native(266, reward)]
+        );
+
+        // Single withdrawal pays out all the tokens
+        suite.withdraw_funds(members[0]).unwrap();
+        suite.withdraw_funds(members[1]).unwrap();
+
+        assert_eq!(suite.native_balance(members[0], reward).unwrap(), 133);
+        assert_eq!(suite.native_balance(members[0], partner).unwrap(), 300);
+        assert_eq!(suite.native_balance(members[1], reward).unwrap(), 266);
+        assert_eq!(suite.native_balance(members[1], partner).unwrap(), 600);
+        assert_eq!(
+            suite.query_withdrawable_funds(members[0]).unwrap(),
+            vec![coin_native(0, partner), coin_native(0, reward)]
+        );
+    }
+
+    #[test]
+    fn withdraw_funds_for() {
+        let members = ["member1", "member2"];
+        let reward = "Reward";
+
+        let mut suite = SuiteBuilder::new()
+            .with_distributed_native_token(reward)
+            .with_funds(members[1], coins(100, reward))
+            .build();
+
+        let controller = suite.controller();
+        let controller = controller.as_str();
+
+        suite.mint(controller, members[0], Uint128::new(1)).unwrap();
+        suite
+            .distribute(members[1], None, &coins(100, reward))
+            .unwrap();
+
+        let err = suite
+            .withdraw_funds_for(members[1], members[0])
+            .unwrap_err();
+        assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+        // Funds always go to the owner
+        suite.withdraw_funds_for(controller, members[0]).unwrap();
+        assert_eq!(suite.native_balance(members[0], reward).unwrap(), 100);
+        assert_eq!(suite.native_balance(controller, reward).unwrap(), 0);
+    }
+
     fn cw20_coin(address: &str, amount: u128) -> cw20::Cw20Coin {
         cw20::Cw20Coin {
             address: address.to_string(),
@@ -0,0 +1,277 @@
+use cosmwasm_std::{to_binary, Uint128};
+use cw20::{AllowanceInfo, Expiration};
+
+use super::receiver::Cw20ExecMsg;
+use super::suite::SuiteBuilder;
+use crate::ContractError;
+
+#[test]
+fn increase_and_decrease() {
+    let owner = "owner";
+    let spender = "spender";
+    let mut suite = SuiteBuilder::new().build();
+
+    suite
+        .increase_allowance(owner, spender, Uint128::new(100), None)
+        .unwrap();
+    suite
+        .increase_allowance(owner, spender, Uint128::new(50), None)
+        .unwrap();
+    let allowance = suite.query_allowance(owner, spender).unwrap();
+    assert_eq!(allowance.allowance, Uint128::new(150));
+    assert_eq!(allowance.expires, Expiration::Never {});
+
+    suite
+        .decrease_allowance(owner, spender, Uint128::new(30))
+        .unwrap();
+    assert_eq!(
+        suite.query_allowance(owner, spender).unwrap().allowance,
+        Uint128::new(120)
+    );
+
+    // Decreasing over the allowance removes it completely
+    suite
+        .decrease_allowance(owner, spender, Uint128::new(500))
+        .unwrap();
+    assert_eq!(
+        suite.query_allowance(owner, spender).unwrap().allowance,
+        Uint128::zero()
+    );
+    assert!(suite
+        .query_all_allowances(owner, None, None)
+        .unwrap()
+        .allowances
+        .is_empty());
+}
+
+#[test]
+fn cannot_set_own_account() {
+    let owner = "owner";
+    let mut suite = SuiteBuilder::new().build();
+
+    let err = suite
+        .increase_allowance(owner, owner, Uint128::new(100), None)
+        .unwrap_err();
+    assert_eq!(
+        ContractError::CannotSetOwnAccount {},
+        err.downcast().unwrap()
+    );
+}
+
+#[test]
+fn cannot_set_expired() {
+    let owner = "owner";
+    let spender = "spender";
+    let mut suite = SuiteBuilder::new().build();
+
+    let err = suite
+        .increase_allowance(owner, spender, Uint128::new(100), Expiration::AtHeight(1))
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidExpiration {}, err.downcast().unwrap());
+}
+
+#[test]
+fn transfer_from() {
+    let owner = "owner";
+    let spender = "spender";
+    let recipient = "recipient";
+    let mut suite = SuiteBuilder::new()
+        .with_transferable(owner, Uint128::new(100))
+        .build();
+    let controller = suite.controller();
+    let controller = controller.as_str();
+
+    suite.mint(controller, owner, Uint128::new(100)).unwrap();
+    suite
+        .increase_allowance(owner, spender, Uint128::new(60), None)
+        .unwrap();
+
+    suite
+        .transfer_from(spender, owner, recipient, Uint128::new(40))
+        .unwrap();
+    assert_eq!(suite.query_balance(owner).unwrap(), Uint128::new(60));
+    assert_eq!(suite.query_balance(recipient).unwrap(), Uint128::new(40));
+    assert_eq!(
+        suite.query_allowance(owner, spender).unwrap().allowance,
+        Uint128::new(20)
+    );
+
+    // Remaining allowance is not enough
+    let err = suite
+        .transfer_from(spender, owner, recipient, Uint128::new(30))
+        .unwrap_err();
+    assert_eq!(ContractError::NoAllowance {}, err.downcast().unwrap());
+    assert_eq!(suite.query_balance(owner).unwrap(), Uint128::new(60));
+}
+
+#[test]
+fn transfer_from_without_allowance() {
+    let owner = "owner";
+    let spender = "spender";
+    let mut suite = SuiteBuilder::new()
+        .with_transferable(owner, Uint128::new(100))
+        .build();
+    let controller = suite.controller();
+    let controller = controller.as_str();
+
+    suite.mint(controller, owner, Uint128::new(100)).unwrap();
+
+    let err = suite
+        .transfer_from(spender, owner, spender, Uint128::new(10))
+        .unwrap_err();
+    assert_eq!(ContractError::NoAllowance {}, err.downcast().unwrap());
+    assert_eq!(suite.query_balance(owner).unwrap(), Uint128::new(100));
+}
+
+#[test]
+fn transfer_from_respects_transferable_amount() {
+    let owner = "owner";
+    let spender = "spender";
+    let mut suite = SuiteBuilder::new()
+        .with_transferable(owner, Uint128::new(50))
+        .build();
+    let controller = suite.controller();
+    let controller = controller.as_str();
+
+    suite.mint(controller, owner, Uint128::new(100)).unwrap();
+    suite
+        .increase_allowance(owner, spender, Uint128::new(100), None)
+        .unwrap();
+
+    let err = suite
+        .transfer_from(spender, owner, spender, Uint128::new(80))
+        .unwrap_err();
+    assert_eq!(
+        ContractError::CannotTransfer {
+            max_transferable: Uint128::new(50)
+        },
+        err.downcast().unwrap()
+    );
+    assert_eq!(suite.query_balance(owner).unwrap(), Uint128::new(100));
+    assert_eq!(
+        suite.query_allowance(owner, spender).unwrap().allowance,
+        Uint128::new(100)
+    );
+}
+
+#[test]
+fn expired_allowance() {
+    let owner = "owner";
+    let spender = "spender";
+    let mut suite = SuiteBuilder::new()
+        .with_transferable(owner, Uint128::new(100))
+        .build();
+    let controller = suite.controller();
+    let controller = controller.as_str();
+
+    suite.mint(controller, owner, Uint128::new(100)).unwrap();
+    let height = suite.block_height();
+    suite
+        .increase_allowance(
+            owner,
+            spender,
+            Uint128::new(100),
+            Expiration::AtHeight(height + 10),
+        )
+        .unwrap();
+
+    suite.advance_blocks(10);
+
+    let err = suite
+        .transfer_from(spender, owner, spender, Uint128::new(10))
+        .unwrap_err();
+    assert_eq!(ContractError::Expired {}, err.downcast().unwrap());
+    assert_eq!(suite.query_balance(owner).unwrap(), Uint128::new(100));
+}
+
+#[test]
+fn send_from() {
+    let owner = "owner";
+    let spender = "spender";
+    let mut suite = SuiteBuilder::new()
+        .with_transferable(owner, Uint128::new(100))
+        .build();
+    let controller = suite.controller();
+    let controller = controller.as_str();
+    let receiver = suite.receiver();
+    let receiver = receiver.as_str();
+
+    suite.mint(controller, owner, Uint128::new(100)).unwrap();
+    suite
+        .increase_allowance(owner, spender, Uint128::new(40), None)
+        .unwrap();
+
+    let exec = to_binary(&Cw20ExecMsg::Valid {}).unwrap();
+    suite
+        .send_from(spender, owner, receiver, Uint128::new(40), exec)
+        .unwrap();
+
+    assert_eq!(suite.query_receiver().unwrap(), 1);
+    assert_eq!(suite.query_balance(owner).unwrap(), Uint128::new(60));
+    assert_eq!(suite.query_balance(receiver).unwrap(), Uint128::new(40));
+    assert_eq!(
+        suite.query_allowance(owner, spender).unwrap().allowance,
+        Uint128::zero()
+    );
+}
+
+#[test]
+fn burn_from() {
+    let owner = "owner";
+    let spender = "spender";
+    let mut suite = SuiteBuilder::new()
+        .with_transferable(owner, Uint128::new(100))
+        .build();
+    let controller = suite.controller();
+    let controller = controller.as_str();
+
+    suite.mint(controller, owner, Uint128::new(100)).unwrap();
+    suite
+        .increase_allowance(owner, spender, Uint128::new(30), None)
+        .unwrap();
+
+    suite.burn(spender, owner, Uint128::new(30)).unwrap();
+    assert_eq!(suite.query_balance(owner).unwrap(), Uint128::new(70));
+    assert_eq!(
+        suite.query_token_info().unwrap().total_supply,
+        Uint128::new(70)
+    );
+
+    let err = suite.burn(spender, owner, Uint128::new(1)).unwrap_err();
+    assert_eq!(ContractError::NoAllowance {}, err.downcast().unwrap());
+}
+
+#[test]
+fn all_allowances_pagination() {
+    let owner = "owner";
+    let mut suite = SuiteBuilder::new().build();
+
+    for spender in ["spender1", "spender2", "spender3"] {
+        suite
+            .increase_allowance(owner, spender, Uint128::new(10), None)
+            .unwrap();
+    }
+
+    let page = suite.query_all_allowances(owner, None, 2).unwrap();
+    assert_eq!(
+        page.allowances,
+        vec![
+            AllowanceInfo {
+                spender: "spender1".to_owned(),
+                allowance: Uint128::new(10),
+                expires: Expiration::Never {},
+            },
+            AllowanceInfo {
+                spender: "spender2".to_owned(),
+                allowance: Uint128::new(10),
+                expires: Expiration::Never {},
+            },
+        ]
+    );
+
+    let page = suite
+        .query_all_allowances(owner, "spender2".to_owned(), 2)
+        .unwrap();
+    assert_eq!(page.allowances.len(), 1);
+    assert_eq!(page.allowances[0].spender, "spender3");
+}
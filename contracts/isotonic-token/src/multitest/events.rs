@@ -0,0 +1,64 @@
+use cosmwasm_std::{Addr, Uint128};
+use utils::events::{IncreaseAllowance, Mint, Transfer};
+use utils::tests::assert_event;
+use utils::token::Token;
+
+use super::suite::SuiteBuilder;
+
+#[test]
+fn mint_and_transfer() {
+    let lender = "lender";
+    let receiver = "receiver";
+    let mut suite = SuiteBuilder::new()
+        .with_transferable(lender, Uint128::new(100))
+        .build();
+    let controller = suite.controller();
+    let ctoken = Token::Cw20(suite.token().to_string());
+
+    let response = suite
+        .mint(controller.as_str(), lender, Uint128::new(100))
+        .unwrap();
+    assert_event(
+        &response.events,
+        &Mint {
+            sender: controller,
+            recipient: Addr::unchecked(lender),
+            amount: ctoken.amount(100u128),
+        },
+    );
+
+    let response = suite.transfer(lender, receiver, Uint128::new(40)).unwrap();
+    assert_event(
+        &response.events,
+        &Transfer {
+            sender: Addr::unchecked(lender),
+            account: Addr::unchecked(lender),
+            recipient: Addr::unchecked(receiver),
+            amount: ctoken.amount(40u128),
+        },
+    );
+}
+
+#[test]
+fn increase_allowance() {
+    let owner = "owner";
+    let spender = "spender";
+    let mut suite = SuiteBuilder::new().build();
+    let ctoken = Token::Cw20(suite.token().to_string());
+
+    suite
+        .increase_allowance(owner, spender, Uint128::new(20), None)
+        .unwrap();
+    let response = suite
+        .increase_allowance(owner, spender, Uint128::new(30), None)
+        .unwrap();
+    assert_event(
+        &response.events,
+        &IncreaseAllowance {
+            sender: Addr::unchecked(owner),
+            spender: Addr::unchecked(spender),
+            amount: ctoken.amount(30u128),
+            allowance: Uint128::new(50),
+        },
+    );
+}
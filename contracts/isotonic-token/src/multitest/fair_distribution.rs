@@ -0,0 +1,236 @@
+//! Distribution is tracked on token balances, while base amounts they are worth change with
+//! every `Rebase`. Tests here ensure every holder gets rewards proportional to the base amount
+//! held at the time of distribution, whatever happened to balances before.
+
+use cosmwasm_std::{coin, Decimal, Uint128};
+use utils::coin::coin_native;
+
+use super::suite::SuiteBuilder;
+
+const REWARD: &str = "reward";
+
+#[test]
+fn rebase_keeps_distribution_proportional_to_value() {
+    let members = ["member1", "member2", "member3"];
+    let funder = "funder";
+
+    let mut suite = SuiteBuilder::new()
+        .with_distributed_native_token(REWARD)
+        .with_funds(funder, vec![coin(1600, REWARD)])
+        .build();
+    let controller = suite.controller();
+    let controller = controller.as_str();
+
+    suite
+        .mint_base(controller, members[0], Uint128::new(100))
+        .unwrap();
+    suite
+        .mint_base(controller, members[1], Uint128::new(300))
+        .unwrap();
+    suite
+        .distribute(funder, None, &[coin(400, REWARD)])
+        .unwrap();
+
+    // Interest accrued - every token is worth 1.5 base now, so 100 and 300 tokens are worth
+    // 150 and 450 base. 600 base deposited now is only 400 tokens.
+    suite.rebase(controller, Decimal::percent(150)).unwrap();
+    suite
+        .mint_base(controller, members[2], Uint128::new(600))
+        .unwrap();
+    assert_eq!(suite.query_balance(members[2]).unwrap(), Uint128::new(400));
+
+    suite
+        .distribute(funder, None, &[coin(1200, REWARD)])
+        .unwrap();
+
+    assert_eq!(
+        suite.query_withdrawable_funds(members[0]).unwrap(),
+        vec![coin_native(100 + 150, REWARD)]
+    );
+    assert_eq!(
+        suite.query_withdrawable_funds(members[1]).unwrap(),
+        vec![coin_native(300 + 450, REWARD)]
+    );
+    assert_eq!(
+        suite.query_withdrawable_funds(members[2]).unwrap(),
+        vec![coin_native(600, REWARD)]
+    );
+}
+
+#[test]
+fn burn_from_keeps_distributed_funds() {
+    let members = ["member1", "member2"];
+    let funder = "funder";
+
+    let mut suite = SuiteBuilder::new()
+        .with_distributed_native_token(REWARD)
+        .with_funds(funder, vec![coin(800, REWARD)])
+        .build();
+    let controller = suite.controller();
+    let controller = controller.as_str();
+
+    suite
+        .mint(controller, members[0], Uint128::new(100))
+        .unwrap();
+    suite
+        .mint(controller, members[1], Uint128::new(100))
+        .unwrap();
+    suite
+        .distribute(funder, None, &[coin(400, REWARD)])
+        .unwrap();
+
+    // Withdrawing the deposit doesn't take away funds distributed before
+    suite.rebase(controller, Decimal::percent(200)).unwrap();
+    suite
+        .burn_base(controller, members[0], Uint128::new(100))
+        .unwrap();
+    assert_eq!(suite.query_balance(members[0]).unwrap(), Uint128::new(50));
+    assert_eq!(
+        suite.query_withdrawable_funds(members[0]).unwrap(),
+        vec![coin_native(200, REWARD)]
+    );
+
+    // member2 has twice the value of member1 now
+    suite
+        .distribute(funder, None, &[coin(300, REWARD)])
+        .unwrap();
+    assert_eq!(
+        suite.query_withdrawable_funds(members[0]).unwrap(),
+        vec![coin_native(200 + 100, REWARD)]
+    );
+    assert_eq!(
+        suite.query_withdrawable_funds(members[1]).unwrap(),
+        vec![coin_native(200 + 200, REWARD)]
+    );
+
+    // Burning everything still leaves funds to withdraw
+    suite
+        .burn(controller, members[0], Uint128::new(50))
+        .unwrap();
+    suite.withdraw_funds(members[0]).unwrap();
+    assert_eq!(suite.native_balance(members[0], REWARD).unwrap(), 300);
+}
+
+#[test]
+fn transfers_move_future_rewards_only() {
+    let members = ["member1", "member2"];
+    let funder = "funder";
+
+    let mut suite = SuiteBuilder::new()
+        .with_distributed_native_token(REWARD)
+        .with_transferable(members[0], Uint128::new(100))
+        .with_funds(funder, vec![coin(1000, REWARD)])
+        .build();
+    let controller = suite.controller();
+    let controller = controller.as_str();
+
+    suite
+        .mint(controller, members[0], Uint128::new(100))
+        .unwrap();
+    suite
+        .distribute(funder, None, &[coin(400, REWARD)])
+        .unwrap();
+
+    suite.rebase(controller, Decimal::percent(120)).unwrap();
+    suite
+        .transfer(members[0], members[1], Uint128::new(75))
+        .unwrap();
+    assert_eq!(
+        suite.query_withdrawable_funds(members[0]).unwrap(),
+        vec![coin_native(400, REWARD)]
+    );
+    assert_eq!(
+        suite.query_withdrawable_funds(members[1]).unwrap(),
+        vec![coin_native(0, REWARD)]
+    );
+
+    suite
+        .distribute(funder, None, &[coin(600, REWARD)])
+        .unwrap();
+    assert_eq!(
+        suite.query_withdrawable_funds(members[0]).unwrap(),
+        vec![coin_native(400 + 150, REWARD)]
+    );
+    assert_eq!(
+        suite.query_withdrawable_funds(members[1]).unwrap(),
+        vec![coin_native(450, REWARD)]
+    );
+}
+
+#[test]
+fn cw20_rewards_after_rebase() {
+    let members = ["member1", "member2"];
+    let funder = "funder";
+
+    let mut suite = SuiteBuilder::new()
+        .with_distributed_cw20_token(
+            6,
+            vec![cw20::Cw20Coin {
+                address: funder.to_owned(),
+                amount: Uint128::new(900),
+            }],
+        )
+        .build();
+    let controller = suite.controller();
+    let controller = controller.as_str();
+    let reward = suite.distributed_token().cw20().unwrap();
+
+    suite
+        .mint_base(controller, members[0], Uint128::new(100))
+        .unwrap();
+    suite.rebase(controller, Decimal::percent(200)).unwrap();
+    suite
+        .mint_base(controller, members[1], Uint128::new(400))
+        .unwrap();
+
+    // member1 holds 200 base, member2 400 base
+    suite
+        .cw20_send_to_token_contract(&reward, funder, 900)
+        .unwrap();
+    suite.distribute(funder, None, &[]).unwrap();
+
+    suite.withdraw_funds(members[0]).unwrap();
+    suite.withdraw_funds(members[1]).unwrap();
+    assert_eq!(suite.cw20_balance(&reward, members[0]).unwrap(), 300);
+    assert_eq!(suite.cw20_balance(&reward, members[1]).unwrap(), 600);
+}
+
+#[test]
+fn amounts_over_96_bits() {
+    let members = ["member1", "member2"];
+    let funder = "funder";
+    // Distributed amount multiplied by `POINTS_SCALE` doesn't fit 128 bits
+    let amount = 1u128 << 100;
+
+    let mut suite = SuiteBuilder::new()
+        .with_distributed_native_token(REWARD)
+        .with_funds(funder, vec![coin(amount, REWARD)])
+        .build();
+    let controller = suite.controller();
+    let controller = controller.as_str();
+
+    suite
+        .mint(controller, members[0], Uint128::new(1 << 40))
+        .unwrap();
+    suite
+        .mint(controller, members[1], Uint128::new(3 << 40))
+        .unwrap();
+    suite
+        .distribute(funder, None, &[coin(amount, REWARD)])
+        .unwrap();
+
+    assert_eq!(
+        suite.query_withdrawable_funds(members[0]).unwrap(),
+        vec![coin_native(amount / 4, REWARD)]
+    );
+    assert_eq!(
+        suite.query_withdrawable_funds(members[1]).unwrap(),
+        vec![coin_native(amount / 4 * 3, REWARD)]
+    );
+
+    suite.withdraw_funds(members[1]).unwrap();
+    assert_eq!(
+        suite.native_balance(members[1], REWARD).unwrap(),
+        amount / 4 * 3
+    );
+}
@@ -0,0 +1,108 @@
+use cosmwasm_std::Binary;
+use cw20::{EmbeddedLogo, Logo, LogoInfo};
+
+use super::suite::Suite;
+use crate::ContractError;
+
+#[test]
+fn update_marketing() {
+    let mut suite = Suite::new();
+    let controller = suite.controller();
+    let controller = controller.as_str();
+
+    suite
+        .update_marketing(
+            controller,
+            "https://wynddao.com".to_owned(),
+            "Lent token".to_owned(),
+            "marketing".to_owned(),
+        )
+        .unwrap();
+
+    let info = suite.query_marketing_info().unwrap();
+    assert_eq!(info.project.as_deref(), Some("https://wynddao.com"));
+    assert_eq!(info.description.as_deref(), Some("Lent token"));
+    assert_eq!(info.marketing.unwrap().as_str(), "marketing");
+    assert_eq!(info.logo, None);
+
+    // Empty strings clear fields, `None` leaves them untouched
+    suite
+        .update_marketing(controller, None, "".to_owned(), "".to_owned())
+        .unwrap();
+
+    let info = suite.query_marketing_info().unwrap();
+    assert_eq!(info.project.as_deref(), Some("https://wynddao.com"));
+    assert_eq!(info.description, None);
+    assert_eq!(info.marketing, None);
+}
+
+#[test]
+fn update_marketing_by_non_controller() {
+    let mut suite = Suite::new();
+
+    let err = suite
+        .update_marketing("someone", "https://evil.com".to_owned(), None, None)
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+    assert_eq!(suite.query_marketing_info().unwrap().project, None);
+}
+
+#[test]
+fn upload_logo() {
+    let mut suite = Suite::new();
+    let controller = suite.controller();
+    let controller = controller.as_str();
+
+    let err = suite
+        .upload_logo("someone", Logo::Url("https://logo.png".to_owned()))
+        .unwrap_err();
+    assert_eq!(ContractError::Unauthorized {}, err.downcast().unwrap());
+
+    suite
+        .upload_logo(controller, Logo::Url("https://logo.png".to_owned()))
+        .unwrap();
+    assert_eq!(
+        suite.query_marketing_info().unwrap().logo,
+        Some(LogoInfo::Url("https://logo.png".to_owned()))
+    );
+    // Url logos can't be downloaded
+    suite.query_download_logo().unwrap_err();
+
+    let svg = Binary::from(b"<?xml version=\"1.0\"?><svg></svg>".as_slice());
+    suite
+        .upload_logo(controller, Logo::Embedded(EmbeddedLogo::Svg(svg.clone())))
+        .unwrap();
+    assert_eq!(
+        suite.query_marketing_info().unwrap().logo,
+        Some(LogoInfo::Embedded)
+    );
+    let logo = suite.query_download_logo().unwrap();
+    assert_eq!(logo.mime_type, "image/svg+xml");
+    assert_eq!(logo.data, svg);
+}
+
+#[test]
+fn upload_invalid_logo() {
+    let mut suite = Suite::new();
+    let controller = suite.controller();
+    let controller = controller.as_str();
+
+    let err = suite
+        .upload_logo(
+            controller,
+            Logo::Embedded(EmbeddedLogo::Png(Binary::from(b"not a png".as_slice()))),
+        )
+        .unwrap_err();
+    assert_eq!(ContractError::InvalidPngHeader {}, err.downcast().unwrap());
+
+    let err = suite
+        .upload_logo(
+            controller,
+            Logo::Embedded(EmbeddedLogo::Svg(Binary::from(b"<svg></svg>".as_slice()))),
+        )
+        .unwrap_err();
+    assert_eq!(
+        ContractError::InvalidXmlPreamble {},
+        err.downcast().unwrap()
+    );
+}
@@ -1,13 +1,17 @@
 use std::collections::HashMap;
 
 use crate::msg::{
-    BalanceResponse, ExecuteMsg, FundsResponse, InstantiateMsg, MultiplierResponse, QueryMsg,
-    TokenInfoResponse,
+    AllAccountsResponse, BalanceResponse, ExecuteMsg, FundsResponse, InstantiateMsg,
+    MultiplierResponse, QueryMsg, TokenInfoResponse,
 };
 use crate::multitest::controller::Controller;
 use crate::multitest::receiver::{QueryResp as ReceiverQueryResp, Receiver};
 use anyhow::{anyhow, Result as AnyResult};
 use cosmwasm_std::{Addr, Binary, Coin as StdCoin, Decimal, Empty, Uint128};
+use cw20::{
+    AllAllowancesResponse, AllowanceResponse, DownloadLogoResponse, Expiration, Logo,
+    MarketingInfoResponse,
+};
 use cw_multi_test::{App, AppResponse, BasicAppBuilder, Contract, ContractWrapper, Executor};
 
 use utils::{coin::Coin, token::Token};
@@ -290,6 +294,168 @@ impl Suite {
             .map_err(|err| anyhow!(err))
     }
 
+    /// Execute withdraw_funds_for on token contract
+    pub fn withdraw_funds_for(&mut self, executor: &str, owner: &str) -> AnyResult<AppResponse> {
+        self.app
+            .execute_contract(
+                Addr::unchecked(executor),
+                self.token.clone(),
+                &ExecuteMsg::WithdrawFundsFor {
+                    owner: owner.to_owned(),
+                },
+                &[],
+            )
+            .map_err(|err| anyhow!(err))
+    }
+
+    /// Executes add_distributed_token on token contract
+    pub fn add_distributed_token(&mut self, sender: &str, token: Token) -> AnyResult<AppResponse> {
+        self.app
+            .execute_contract(
+                Addr::unchecked(sender),
+                self.token.clone(),
+                &ExecuteMsg::AddDistributedToken { token },
+                &[],
+            )
+            .map_err(|err| anyhow!(err))
+    }
+
+    /// Executes increase_allowance on token contract
+    pub fn increase_allowance(
+        &mut self,
+        owner: &str,
+        spender: &str,
+        amount: Uint128,
+        expires: impl Into<Option<Expiration>>,
+    ) -> AnyResult<AppResponse> {
+        self.app
+            .execute_contract(
+                Addr::unchecked(owner),
+                self.token.clone(),
+                &ExecuteMsg::IncreaseAllowance {
+                    spender: spender.to_owned(),
+                    amount,
+                    expires: expires.into(),
+                },
+                &[],
+            )
+            .map_err(|err| anyhow!(err))
+    }
+
+    /// Executes decrease_allowance on token contract
+    pub fn decrease_allowance(
+        &mut self,
+        owner: &str,
+        spender: &str,
+        amount: Uint128,
+    ) -> AnyResult<AppResponse> {
+        self.app
+            .execute_contract(
+                Addr::unchecked(owner),
+                self.token.clone(),
+                &ExecuteMsg::DecreaseAllowance {
+                    spender: spender.to_owned(),
+                    amount,
+                    expires: None,
+                },
+                &[],
+            )
+            .map_err(|err| anyhow!(err))
+    }
+
+    /// Executes transfer_from on token contract
+    pub fn transfer_from(
+        &mut self,
+        sender: &str,
+        owner: &str,
+        recipient: &str,
+        amount: Uint128,
+    ) -> AnyResult<AppResponse> {
+        self.app
+            .execute_contract(
+                Addr::unchecked(sender),
+                self.token.clone(),
+                &ExecuteMsg::TransferFrom {
+                    owner: owner.to_owned(),
+                    recipient: recipient.to_owned(),
+                    amount,
+                },
+                &[],
+            )
+            .map_err(|err| anyhow!(err))
+    }
+
+    /// Executes send_from on token contract
+    pub fn send_from(
+        &mut self,
+        sender: &str,
+        owner: &str,
+        recipient: &str,
+        amount: Uint128,
+        msg: Binary,
+    ) -> AnyResult<AppResponse> {
+        self.app
+            .execute_contract(
+                Addr::unchecked(sender),
+                self.token.clone(),
+                &ExecuteMsg::SendFrom {
+                    owner: owner.to_owned(),
+                    contract: recipient.to_owned(),
+                    amount,
+                    msg,
+                },
+                &[],
+            )
+            .map_err(|err| anyhow!(err))
+    }
+
+    /// Executes update_marketing on token contract
+    pub fn update_marketing(
+        &mut self,
+        sender: &str,
+        project: impl Into<Option<String>>,
+        description: impl Into<Option<String>>,
+        marketing: impl Into<Option<String>>,
+    ) -> AnyResult<AppResponse> {
+        self.app
+            .execute_contract(
+                Addr::unchecked(sender),
+                self.token.clone(),
+                &ExecuteMsg::UpdateMarketing {
+                    project: project.into(),
+                    description: description.into(),
+                    marketing: marketing.into(),
+                },
+                &[],
+            )
+            .map_err(|err| anyhow!(err))
+    }
+
+    /// Executes upload_logo on token contract
+    pub fn upload_logo(&mut self, sender: &str, logo: Logo) -> AnyResult<AppResponse> {
+        self.app
+            .execute_contract(
+                Addr::unchecked(sender),
+                self.token.clone(),
+                &ExecuteMsg::UploadLogo(logo),
+                &[],
+            )
+            .map_err(|err| anyhow!(err))
+    }
+
+    /// Gives current block height
+    pub fn block_height(&self) -> u64 {
+        self.app.block_info().height
+    }
+
+    /// Moves the chain forward by given number of blocks
+    pub fn advance_blocks(&mut self, blocks: u64) {
+        self.app.update_block(|block| {
+            block.height += blocks;
+            block.time = block.time.plus_seconds(5 * blocks);
+        });
+    }
+
     /// Executes mint on token contract
     pub fn mint(
         &mut self,
@@ -460,7 +626,7 @@ impl Suite {
     }
 
     /// Queries distributed funds
-    pub fn query_distributed_funds(&self) -> AnyResult<Coin> {
+    pub fn query_distributed_funds(&self) -> AnyResult<Vec<Coin>> {
         let resp: FundsResponse = self
             .app
             .wrap()
@@ -471,7 +637,7 @@ impl Suite {
     }
 
     /// Queries undistributed funds
-    pub fn query_undistributed_funds(&self) -> AnyResult<Coin> {
+    pub fn query_undistributed_funds(&self) -> AnyResult<Vec<Coin>> {
         let resp: FundsResponse = self
             .app
             .wrap()
@@ -482,7 +648,7 @@ impl Suite {
     }
 
     /// Queries withdrawable funds
-    pub fn query_withdrawable_funds(&self, addr: &str) -> AnyResult<Coin> {
+    pub fn query_withdrawable_funds(&self, addr: &str) -> AnyResult<Vec<Coin>> {
         let resp: FundsResponse = self
             .app
             .wrap()
@@ -497,6 +663,74 @@ impl Suite {
         Ok(resp.funds)
     }
 
+    /// Queries allowance given by `owner` to `spender`
+    pub fn query_allowance(&self, owner: &str, spender: &str) -> AnyResult<AllowanceResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.token.clone(),
+                &QueryMsg::Allowance {
+                    owner: owner.to_owned(),
+                    spender: spender.to_owned(),
+                },
+            )
+            .map_err(|err| anyhow!(err))
+    }
+
+    /// Queries all allowances given by `owner`
+    pub fn query_all_allowances(
+        &self,
+        owner: &str,
+        start_after: impl Into<Option<String>>,
+        limit: impl Into<Option<u32>>,
+    ) -> AnyResult<AllAllowancesResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.token.clone(),
+                &QueryMsg::AllAllowances {
+                    owner: owner.to_owned(),
+                    start_after: start_after.into(),
+                    limit: limit.into(),
+                },
+            )
+            .map_err(|err| anyhow!(err))
+    }
+
+    /// Queries all accounts holding the token
+    pub fn query_all_accounts(
+        &self,
+        start_after: impl Into<Option<String>>,
+        limit: impl Into<Option<u32>>,
+    ) -> AnyResult<AllAccountsResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.token.clone(),
+                &QueryMsg::AllAccounts {
+                    start_after: start_after.into(),
+                    limit: limit.into(),
+                },
+            )
+            .map_err(|err| anyhow!(err))
+    }
+
+    /// Queries marketing info
+    pub fn query_marketing_info(&self) -> AnyResult<MarketingInfoResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.token.clone(), &QueryMsg::MarketingInfo {})
+            .map_err(|err| anyhow!(err))
+    }
+
+    /// Downloads embedded logo
+    pub fn query_download_logo(&self) -> AnyResult<DownloadLogoResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.token.clone(), &QueryMsg::DownloadLogo {})
+            .map_err(|err| anyhow!(err))
+    }
+
     /// Queries for balance of native token
     pub fn native_balance(&self, addr: &str, token: &str) -> AnyResult<u128> {
         let amount = self
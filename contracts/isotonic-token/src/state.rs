@@ -1,11 +1,12 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Decimal, Uint128};
+use cosmwasm_std::{Addr, Decimal, StdError, StdResult, Uint128, Uint256};
+use cw20::{AllowanceResponse, Logo, MarketingInfoResponse};
 use cw_storage_plus::{Item, Map};
 use utils::token::Token;
 
-use crate::i128::Int128;
+use crate::i256::Int256;
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -15,10 +16,14 @@ pub struct TokenInfo {
     pub decimals: u8,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, JsonSchema)]
+/// Distribution of a single token, following the cw2222 mechanism.
+///
+/// Points are tracked on token balances, not on base amounts. It is still fair under rebases:
+/// `Rebase` changes the value of every token by the same ratio, so token balances are always
+/// proportional to the underlying base amounts, and so is the share in every distribution.
+/// Balances changed by `Mint`, `BurnFrom` and transfers are handled by points corrections.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, JsonSchema, Default)]
 pub struct Distribution {
-    /// Token distributed by this contract.
-    pub denom: Token,
     /// How much points is single division of isotonic worth at this point.
     pub points_per_token: Uint128,
     /// Points which were not fully distributed on previous distribution, and should be
@@ -33,7 +38,7 @@ pub struct Distribution {
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, JsonSchema, Default)]
 pub struct WithdrawAdjustment {
     /// How much points should be added/removed from calculated funds while withdrawal.
-    pub points_correction: Int128,
+    pub points_correction: Int256,
     /// How much funds was already withdrawn.
     pub withdrawn_funds: Uint128,
 }
@@ -41,15 +46,68 @@ pub struct WithdrawAdjustment {
 /// How much points is the worth of single token in token distribution.
 ///
 /// 4_000_000_000 is choosen as the closest to reasonable 32bits shift,
-/// still being decimal-friendly. It reduces how much tokens may be handled by this contract
-/// (it is now ~96-bit integer instead of 128). In original ERC2222 it is handled by 256-bit
-/// calculations, but I256 is missing and it is required for this.
+/// still being decimal-friendly. All calculations on points are performed on 256 bits, as in
+/// original ERC2222, so it doesn't limit how much tokens may be handled by this contract.
 pub const POINTS_SCALE: u128 = 4_000_000_000;
 
+impl Distribution {
+    /// Distributes `amount` between holders of `total_weight` tokens.
+    ///
+    /// It is very much possible, that non-whole points should be paid for single token. To
+    /// overcome this, as much points as possible are distributed without non-whole division, and
+    /// the leftover is stored for the next distribution. Full amount is added to withdrawable
+    /// total anyway - the error is handled by the leftover.
+    pub fn distribute(&mut self, amount: Uint128, total_weight: Uint128) -> StdResult<()> {
+        let points = amount.full_mul(POINTS_SCALE) + Uint256::from(self.points_leftover);
+        let total_weight = Uint256::from(total_weight);
+
+        let points_per_token: Uint128 = (points / total_weight).try_into()?;
+        // Remainder is lower than `total_weight`, so always fits 128 bits
+        self.points_leftover = (points % total_weight).try_into()?;
+        self.points_per_token = self.points_per_token.checked_add(points_per_token)?;
+        self.distributed_total = self.distributed_total.checked_add(amount)?;
+        self.withdrawable_total = self.withdrawable_total.checked_add(amount)?;
+
+        Ok(())
+    }
+
+    /// Funds not yet withdrawn by the owner of `weight` tokens.
+    pub fn withdrawable(
+        &self,
+        weight: Uint128,
+        adjustment: &WithdrawAdjustment,
+    ) -> StdResult<Uint128> {
+        let points = Int256::from(self.points_per_token.full_mul(weight))
+            .checked_add(adjustment.points_correction)?
+            .to_unsigned()
+            .ok_or_else(|| StdError::generic_err("Negative points while calculating funds"))?;
+        let amount: Uint128 = (points / Uint256::from(POINTS_SCALE)).try_into()?;
+        Ok(amount.checked_sub(adjustment.withdrawn_funds)?)
+    }
+}
+
+impl WithdrawAdjustment {
+    /// Corrects points after the weight of the owner changed by `diff` tokens, so funds
+    /// distributed before the change are not affected by it.
+    pub fn apply_correction(&mut self, points_per_token: Uint128, diff: i128) -> StdResult<()> {
+        let points = Int256::new(diff < 0, points_per_token.full_mul(diff.unsigned_abs()));
+        self.points_correction = self.points_correction.checked_sub(points)?;
+        Ok(())
+    }
+}
+
 pub const TOKEN_INFO: Item<TokenInfo> = Item::new("token_info");
 pub const TOTAL_SUPPLY: Item<Uint128> = Item::new("total_supply");
 pub const CONTROLLER: Item<Addr> = Item::new("controller");
 pub const BALANCES: Map<&Addr, Uint128> = Map::new("balance");
+/// Allowances given by (owner, spender)
+pub const ALLOWANCES: Map<(&Addr, &Addr), AllowanceResponse> = Map::new("allowance");
 pub const MULTIPLIER: Item<Decimal> = Item::new("multiplier");
-pub const DISTRIBUTION: Item<Distribution> = Item::new("distribution");
-pub const WITHDRAW_ADJUSTMENT: Map<&Addr, WithdrawAdjustment> = Map::new("withdraw_adjustment");
+pub const MARKETING_INFO: Item<MarketingInfoResponse> = Item::new("marketing_info");
+pub const LOGO: Item<Logo> = Item::new("logo");
+/// Distribution state for every token distributed by this contract. Only tokens in this map are
+/// accepted by `ExecuteMsg::Distribute`.
+pub const DISTRIBUTION: Map<&Token, Distribution> = Map::new("distributions");
+/// Withdraw adjustments by (owner, distributed token)
+pub const WITHDRAW_ADJUSTMENT: Map<(&Addr, &Token), WithdrawAdjustment> =
+    Map::new("withdraw_adjustments");
@@ -66,6 +66,8 @@ pub fn execute(
 mod execute {
     use cosmwasm_std::ensure_eq;
 
+    use utils::events;
+
     use crate::state::POOLS;
 
     use super::*;
@@ -87,7 +89,15 @@ mod execute {
             &pair_address,
         )?;
 
-        Ok(Response::new().add_attribute("action", "register_pool"))
+        let event = events::RegisterPool {
+            sender: info.sender,
+            pair: pair_address,
+            first_asset: denom1.into(),
+            second_asset: denom2.into(),
+        };
+        Ok(Response::new()
+            .add_attribute("action", "register_pool")
+            .add_event(event.into()))
     }
 }
 
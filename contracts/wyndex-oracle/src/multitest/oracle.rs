@@ -1,6 +1,6 @@
 use super::suite::SuiteBuilder;
 
-use cosmwasm_std::{assert_approx_eq, coin, Decimal, Fraction, Uint128};
+use cosmwasm_std::{assert_approx_eq, coin, Addr, Decimal, Fraction, Uint128};
 use wyndex::{
     asset::{Asset, AssetInfo},
     factory::PairType,
@@ -10,6 +10,9 @@ use wyndex::{
 use std::str::FromStr;
 
 use crate::ContractError;
+use utils::events::RegisterPool;
+use utils::tests::assert_event;
+use utils::token::Token;
 use utils::wyndex::SwapOperation;
 
 pub const TWAP_INTERVAL: u64 = 30 * 60; // 30 minutes
@@ -72,9 +75,18 @@ fn register_pool() {
     );
 
     // "controller" here represents an address that is allowed to register pools
-    suite
+    let response = suite
         .register_pool("controller", juno_atom.as_str(), &juno_info, &atom_info)
         .unwrap();
+    assert_event(
+        &response.events,
+        &RegisterPool {
+            sender: Addr::unchecked("controller"),
+            pair: juno_atom.clone(),
+            first_asset: Token::Native("juno".to_owned()),
+            second_asset: Token::Native("ibc".to_owned()),
+        },
+    );
 
     // Querying for pool address works both ways
     let result = suite.query_pool_address(&juno_info, &atom_info).unwrap();
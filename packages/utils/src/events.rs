@@ -0,0 +1,576 @@
+//! Typed events of all isotonic contracts.
+//!
+//! Every state-changing action emits one event of type `isotonic-<action>`, reported by the chain
+//! as `wasm-isotonic-<action>`. Fields are named the same way in all contracts:
+//!
+//! * `sender` - caller of the message,
+//! * `account` - account whose position is changed,
+//! * `recipient` - receiver of the tokens,
+//! * `market` - market contract the action is executed on,
+//! * `amount` - amount of tokens, in the market token for market actions,
+//! * `amount_common` - value of `amount` in the common token,
+//! * `ctoken_ratio`, `debt_ratio` - interest ratios applied by the action, one if no interest
+//!   was charged.
+//!
+//! Coins are formatted as `<amount><denom>`, and empty fields are skipped.
+
+use cosmwasm_std::{attr, to_vec, Addr, Attribute, Decimal, Event, StdResult, Timestamp, Uint128};
+use serde::Serialize;
+
+use crate::coin::Coin;
+use crate::token::Token;
+
+/// Prefix of types of all isotonic events
+pub const EVENT_PREFIX: &str = "isotonic-";
+
+/// Event of a single action
+pub trait IsotonicEvent {
+    /// Name of the action, the type of the event is `isotonic-<ACTION>`
+    const ACTION: &'static str;
+
+    fn attributes(&self) -> Vec<Attribute>;
+
+    fn event_type() -> String {
+        format!("{}{}", EVENT_PREFIX, Self::ACTION)
+    }
+
+    fn to_event(&self) -> Event {
+        Event::new(Self::event_type()).add_attributes(self.attributes())
+    }
+}
+
+/// Value of the event attribute, `None` if the attribute is skipped
+pub trait EventValue {
+    fn event_value(&self) -> Option<String>;
+}
+
+macro_rules! display_value {
+    ($($ty:ty),*) => {
+        $(
+            impl EventValue for $ty {
+                fn event_value(&self) -> Option<String> {
+                    Some(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+display_value!(
+    Addr,
+    String,
+    &'static str,
+    Uint128,
+    Decimal,
+    Token,
+    u32,
+    u64,
+    bool
+);
+
+impl EventValue for Timestamp {
+    fn event_value(&self) -> Option<String> {
+        Some(self.seconds().to_string())
+    }
+}
+
+impl EventValue for Coin {
+    fn event_value(&self) -> Option<String> {
+        Some(format!("{}{}", self.amount, self.denom))
+    }
+}
+
+impl<T: EventValue> EventValue for Option<T> {
+    fn event_value(&self) -> Option<String> {
+        self.as_ref().and_then(EventValue::event_value)
+    }
+}
+
+impl<T: EventValue> EventValue for Vec<T> {
+    fn event_value(&self) -> Option<String> {
+        let values: Vec<_> = self.iter().filter_map(EventValue::event_value).collect();
+        if values.is_empty() {
+            None
+        } else {
+            Some(values.join(","))
+        }
+    }
+}
+
+/// Serializes the value to JSON, for events carrying whole messages like parameter changes
+pub fn json<T: Serialize>(value: &T) -> StdResult<String> {
+    Ok(String::from_utf8_lossy(&to_vec(value)?).into_owned())
+}
+
+macro_rules! isotonic_events {
+    ($(
+        $(#[$meta:meta])*
+        $name:ident = $action:literal {
+            $($(#[$field_meta:meta])* $field:ident: $ty:ty),* $(,)?
+        }
+    )*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Clone, Debug, PartialEq, Eq)]
+            pub struct $name {
+                $($(#[$field_meta])* pub $field: $ty,)*
+            }
+
+            impl IsotonicEvent for $name {
+                const ACTION: &'static str = $action;
+
+                fn attributes(&self) -> Vec<Attribute> {
+                    let mut attributes = vec![];
+                    $(
+                        if let Some(value) = self.$field.event_value() {
+                            attributes.push(attr(stringify!($field), value));
+                        }
+                    )*
+                    attributes
+                }
+            }
+
+            impl From<$name> for Event {
+                fn from(event: $name) -> Self {
+                    event.to_event()
+                }
+            }
+        )*
+    };
+}
+
+// Market events
+isotonic_events! {
+    /// Tokens deposited on the market, `account` receives cTokens
+    Deposit = "deposit" {
+        sender: Addr,
+        account: Addr,
+        amount: Coin,
+        amount_common: Option<Coin>,
+        ctoken_ratio: Decimal,
+        debt_ratio: Decimal,
+    }
+
+    /// Tokens withdrawn from the market, cTokens of `account` are burned
+    Withdraw = "withdraw" {
+        sender: Addr,
+        account: Addr,
+        recipient: Addr,
+        amount: Coin,
+        amount_common: Option<Coin>,
+        ctoken_ratio: Decimal,
+        debt_ratio: Decimal,
+    }
+
+    /// Tokens borrowed from the market, debt of `account` is increased
+    Borrow = "borrow" {
+        sender: Addr,
+        account: Addr,
+        recipient: Addr,
+        amount: Coin,
+        amount_common: Option<Coin>,
+        ctoken_ratio: Decimal,
+        debt_ratio: Decimal,
+    }
+
+    /// Debt of `account` repaid, `amount` doesn't include the surplus sent back
+    Repay = "repay" {
+        sender: Addr,
+        account: Addr,
+        amount: Coin,
+        amount_common: Option<Coin>,
+        ctoken_ratio: Decimal,
+        debt_ratio: Decimal,
+    }
+
+    /// Collateral of `account` seized during the liquidation. `amount` is the seized collateral,
+    /// `amount_common` the repaid debt it covers.
+    SeizeCollateral = "seize-collateral" {
+        sender: Addr,
+        account: Addr,
+        recipient: Addr,
+        amount: Coin,
+        amount_common: Coin,
+        liquidation_price: Decimal,
+        ctoken_ratio: Decimal,
+        debt_ratio: Decimal,
+    }
+
+    /// Collateral of `account` sold for `bought` tokens, which are sent to the credit agency
+    SwapWithdraw = "swap-withdraw" {
+        sender: Addr,
+        account: Addr,
+        amount: Coin,
+        bought: Coin,
+    }
+
+    /// Common token of the market changed by the credit agency
+    AdjustCommonToken = "adjust-common-token" {
+        sender: Addr,
+        common_token: Token,
+    }
+
+    /// The market is winding down
+    WindDown = "wind-down" {
+        sender: Addr,
+        duration: u64,
+        force_liquidation: bool,
+        collateral_ratio: Decimal,
+    }
+
+    /// Governance of the market changed the cToken: its `marketing`, `logo` or
+    /// `distributed_token`
+    AdjustCToken = "adjust-ctoken" {
+        sender: Addr,
+        ctoken: Addr,
+        update: &'static str,
+        token: Option<Token>,
+    }
+}
+
+// Events shared by the market, the credit agency and the token
+isotonic_events! {
+    /// Parameter change with `status` being `proposed`, `applied` or `cancelled`. `change` is
+    /// the JSON of the change, not known when cancelling.
+    ParamChange = "param-change" {
+        sender: Addr,
+        status: &'static str,
+        id: Option<u64>,
+        executable_at: Option<Timestamp>,
+        change: Option<String>,
+    }
+
+    /// Governance transfer with `status` being `proposed` or `accepted`
+    GovernanceTransfer = "governance-transfer" {
+        sender: Addr,
+        status: &'static str,
+        governance: Addr,
+    }
+
+    /// Rewards distributed between cToken holders or borrowers
+    DistributeRewards = "distribute-rewards" {
+        sender: Addr,
+        rewards: Vec<Coin>,
+    }
+
+    /// Rewards claimed by `account`. The credit agency claims on `markets`, which report the
+    /// claimed `rewards`.
+    ClaimRewards = "claim-rewards" {
+        sender: Addr,
+        account: Addr,
+        markets: Vec<Addr>,
+        rewards: Vec<Coin>,
+    }
+}
+
+// Credit agency events
+isotonic_events! {
+    /// New market created, from the `template` if given
+    CreateMarket = "create-market" {
+        sender: Addr,
+        market: Addr,
+        market_token: Token,
+        market_id: u32,
+        collateral_ratio: Decimal,
+        template: Option<String>,
+    }
+
+    /// Market removed after winding down
+    RemoveMarket = "remove-market" {
+        sender: Addr,
+        market: Addr,
+        market_token: Token,
+        market_id: u32,
+    }
+
+    /// Market migrated to the new code
+    MigrateMarket = "migrate-market" {
+        sender: Addr,
+        market: Addr,
+        code_id: u64,
+    }
+
+    /// Debt of `account` repaid by the liquidator in exchange for its collateral
+    Liquidate = "liquidate" {
+        sender: Addr,
+        account: Addr,
+        debt_market: Addr,
+        collateral_market: Addr,
+        amount: Coin,
+        amount_common: Coin,
+        liquidation_price: Decimal,
+    }
+
+    /// Liquidation selling the seized collateral for the repaid debt
+    LiquidateWithSwap = "liquidate-with-swap" {
+        sender: Addr,
+        account: Addr,
+        debt_market: Addr,
+        collateral_market: Addr,
+        amount: Coin,
+        amount_common: Coin,
+        liquidation_price: Decimal,
+        min_profit: Uint128,
+    }
+
+    /// Collateral left after the swap sent to the liquidator
+    SettleLiquidation = "settle-liquidation" {
+        sender: Addr,
+        recipient: Addr,
+        amount: Coin,
+    }
+
+    /// Liquidation auction of `account` started or stopped
+    LiquidationAuction = "liquidation-auction" {
+        sender: Addr,
+        account: Addr,
+        liquidatable: bool,
+    }
+
+    EnterMarket = "enter-market" {
+        sender: Addr,
+        account: Addr,
+        market: Addr,
+    }
+
+    ExitMarket = "exit-market" {
+        sender: Addr,
+        account: Addr,
+        market: Addr,
+    }
+
+    /// Debt repaid by selling at most `collateral`
+    RepayWithCollateral = "repay-with-collateral" {
+        sender: Addr,
+        account: Addr,
+        collateral_market: Addr,
+        debt_market: Addr,
+        amount: Coin,
+        amount_common: Coin,
+        collateral: Coin,
+        collateral_common: Coin,
+    }
+
+    /// Debt repaid by selling at most `collateral`, `fee_common` is paid to the governance
+    Deleverage = "deleverage" {
+        sender: Addr,
+        account: Addr,
+        collateral_market: Addr,
+        debt_market: Addr,
+        amount: Coin,
+        amount_common: Coin,
+        collateral: Coin,
+        fee_common: Coin,
+    }
+
+    /// `amount` of collateral leveraged with `borrowed` debt
+    OpenLeveragedPosition = "open-leveraged-position" {
+        sender: Addr,
+        account: Addr,
+        collateral_market: Addr,
+        debt_market: Addr,
+        amount: Coin,
+        borrowed: Coin,
+        leverage: Decimal,
+    }
+
+    /// Whole debt repaid by selling the collateral
+    CloseLeveragedPosition = "close-leveraged-position" {
+        sender: Addr,
+        account: Addr,
+        collateral_market: Addr,
+        debt_market: Addr,
+        amount: Coin,
+        amount_common: Coin,
+    }
+
+    /// Bought collateral deposited on the `market` at the end of the leveraged position opening
+    /// or the collateral swap
+    SettleDeposit = "settle-deposit" {
+        sender: Addr,
+        account: Addr,
+        market: Addr,
+        amount: Coin,
+        amount_common: Coin,
+    }
+
+    /// `amount` of collateral sold for at least `min_receive` of `to_denom`
+    SwapCollateral = "swap-collateral" {
+        sender: Addr,
+        account: Addr,
+        amount: Coin,
+        to_denom: Token,
+        min_receive: Uint128,
+    }
+
+    /// `amount` of debt repaid with `borrowed` debt in other token
+    SwapDebt = "swap-debt" {
+        sender: Addr,
+        account: Addr,
+        amount: Coin,
+        borrowed: Coin,
+    }
+
+    /// Account verified at the end of the debt swap
+    SettleDebtSwap = "settle-debt-swap" {
+        sender: Addr,
+        account: Addr,
+        debt: Coin,
+        borrow_limit: Coin,
+    }
+
+    /// Rewards released on the market
+    EmitRewards = "emit-rewards" {
+        sender: Addr,
+        market: Addr,
+        supplier_rewards: Coin,
+        borrower_rewards: Coin,
+    }
+
+    /// Rewards of the market funded until `end`
+    FundEmission = "fund-emission" {
+        sender: Addr,
+        market: Addr,
+        amount: Coin,
+        end: Timestamp,
+        supplier_share: Decimal,
+    }
+
+    /// Market parameter changed by the risk steward
+    StewardAdjust = "steward-adjust" {
+        sender: Addr,
+        market: Addr,
+        id: u64,
+        change: String,
+    }
+}
+
+// Token events, amounts are in cTokens
+isotonic_events! {
+    Transfer = "transfer" {
+        sender: Addr,
+        account: Addr,
+        recipient: Addr,
+        amount: Coin,
+    }
+
+    Send = "send" {
+        sender: Addr,
+        account: Addr,
+        recipient: Addr,
+        amount: Coin,
+    }
+
+    /// Allowance of `spender` on `sender` tokens increased, up to `allowance`
+    IncreaseAllowance = "increase-allowance" {
+        sender: Addr,
+        spender: Addr,
+        amount: Coin,
+        allowance: Uint128,
+    }
+
+    /// Allowance of `spender` on `sender` tokens decreased, down to `allowance`
+    DecreaseAllowance = "decrease-allowance" {
+        sender: Addr,
+        spender: Addr,
+        amount: Coin,
+        allowance: Uint128,
+    }
+
+    Mint = "mint" {
+        sender: Addr,
+        recipient: Addr,
+        amount: Coin,
+    }
+
+    Burn = "burn" {
+        sender: Addr,
+        account: Addr,
+        amount: Coin,
+    }
+
+    /// Token multiplier multiplied by `ratio` to `multiplier`
+    Rebase = "rebase" {
+        sender: Addr,
+        ratio: Decimal,
+        multiplier: Decimal,
+    }
+
+    AddDistributedToken = "add-distributed-token" {
+        sender: Addr,
+        token: Token,
+    }
+
+    UpdateMarketing = "update-marketing" {
+        sender: Addr,
+    }
+
+    UploadLogo = "upload-logo" {
+        sender: Addr,
+    }
+}
+
+// Oracle events
+isotonic_events! {
+    /// Pair contract registered as the price source of the pair of tokens
+    RegisterPool = "register-pool" {
+        sender: Addr,
+        pair: Addr,
+        first_asset: Token,
+        second_asset: Token,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_attributes() {
+        let event: Event = Repay {
+            sender: Addr::unchecked("sender"),
+            account: Addr::unchecked("account"),
+            amount: Token::Native("juno".to_owned()).amount(100u128),
+            amount_common: None,
+            ctoken_ratio: Decimal::one(),
+            debt_ratio: Decimal::percent(101),
+        }
+        .into();
+
+        assert_eq!(event.ty, "isotonic-repay");
+        assert_eq!(
+            event.attributes,
+            vec![
+                attr("sender", "sender"),
+                attr("account", "account"),
+                attr("amount", "100juno"),
+                attr("ctoken_ratio", "1"),
+                attr("debt_ratio", "1.01"),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_lists_skipped() {
+        let rewards = vec![
+            Token::Native("juno".to_owned()).amount(10u128),
+            Token::Cw20("cw20".to_owned()).amount(20u128),
+        ];
+        let event = ClaimRewards {
+            sender: Addr::unchecked("market"),
+            account: Addr::unchecked("account"),
+            markets: vec![],
+            rewards,
+        }
+        .to_event();
+
+        assert_eq!(
+            event.attributes,
+            vec![
+                attr("sender", "market"),
+                attr("account", "account"),
+                attr("rewards", "10juno,20cw20"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use cosmwasm_std::{instantiate2_address, Addr, Binary, Deps, StdError, StdResult};
+use cw_storage_plus::PrimaryKey;
+use sha2::{Digest, Sha256};
+
+use crate::token::Token;
+
+/// Salt of the contract created for `token` with `WasmMsg::Instantiate2`, `seed` distinguishes
+/// contracts of the same token. Salt is limited to 64 bytes, so it is hashed.
+pub fn token_salt(token: &Token, seed: &[u8]) -> Binary {
+    let mut hasher = Sha256::new();
+    hasher.update(token.joined_key());
+    hasher.update(seed);
+    Binary::from(hasher.finalize().to_vec())
+}
+
+/// Address of the contract `creator` instantiates from `code_id` with `salt`
+pub fn predict_address(deps: Deps, code_id: u64, creator: &Addr, salt: &Binary) -> StdResult<Addr> {
+    let checksum = deps.querier.query_wasm_code_info(code_id)?.checksum;
+    let creator = deps.api.addr_canonicalize(creator.as_str())?;
+    let address = instantiate2_address(&checksum, &creator, salt)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    deps.api.addr_humanize(&address)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_salt_distinguishes_tokens_and_seeds() {
+        let native = token_salt(&Token::Native("juno".to_owned()), b"");
+        assert_eq!(native.len(), 32);
+        assert_ne!(native, token_salt(&Token::Cw20("juno".to_owned()), b""));
+        assert_ne!(native, token_salt(&Token::Native("juno".to_owned()), b"1"));
+    }
+}
@@ -82,6 +82,17 @@ impl ValidatedInterest {
         }
     }
 
+    /// Breakpoint of the piecewise linear rates, `None` for linear ones
+    pub fn optimal_utilisation(&self) -> Option<Decimal> {
+        match self.inner {
+            Interest::Linear { .. } => None,
+            Interest::PiecewiseLinear {
+                optimal_utilisation,
+                ..
+            } => Some(optimal_utilisation),
+        }
+    }
+
     /// Bypasses the validation, building a `ValidatedInterest` out of the raw data.
     /// If you're using this, you're guaranteeing the data is valid.
     pub fn unchecked(interest: Interest) -> Self {
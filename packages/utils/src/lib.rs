@@ -1,9 +1,12 @@
 pub mod amount;
 pub mod coin;
 pub mod credit_line;
+pub mod events;
+pub mod instantiate2;
 pub mod interest;
 pub mod price;
 pub mod tests;
 pub mod time;
+pub mod timelock;
 pub mod token;
 pub mod wyndex;
@@ -1,4 +1,6 @@
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_std::{Decimal, Event, Uint128};
+
+use crate::events::IsotonicEvent;
 
 /// Performs "almost exact" comparison between numbers.
 ///
@@ -48,3 +50,42 @@ pub fn check_approx_eq_impl(
         Err((diff, rel))
     }
 }
+
+/// Events of type `E` as reported by the chain, which prefixes contract events with `wasm-`
+pub fn find_events<E: IsotonicEvent>(events: &[Event]) -> Vec<&Event> {
+    let ty = format!("wasm-{}", E::event_type());
+    events.iter().filter(|event| event.ty == ty).collect()
+}
+
+/// Value of the `key` attribute of the first event of type `E`
+pub fn event_attribute<E: IsotonicEvent>(events: &[Event], key: &str) -> Option<String> {
+    find_events::<E>(events)
+        .first()?
+        .attributes
+        .iter()
+        .find(|attr| attr.key == key)
+        .map(|attr| attr.value.clone())
+}
+
+/// Asserts that the `expected` event was emitted, by any contract
+pub fn assert_event<E: IsotonicEvent>(events: &[Event], expected: &E) {
+    let expected = expected.attributes();
+    let emitted: Vec<_> = find_events::<E>(events)
+        .into_iter()
+        .map(|event| {
+            event
+                .attributes
+                .iter()
+                .filter(|attr| attr.key != "_contract_address")
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    assert!(
+        emitted.contains(&expected),
+        "Event {} with attributes {:?} not emitted, emitted ones: {:?}",
+        E::event_type(),
+        expected,
+        emitted
+    );
+}
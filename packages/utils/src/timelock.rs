@@ -0,0 +1,175 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{BlockInfo, Order, StdError, StdResult, Storage, Timestamp};
+use cw_storage_plus::{Bound, Item, Map};
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error;
+
+/// Parameter change proposed by the governance, waiting for its delay to pass
+#[cw_serde]
+pub struct PendingChange<T> {
+    pub change: T,
+    /// Time from which the change can be executed
+    pub executable_at: Timestamp,
+}
+
+/// Queue of pending parameter changes, identified by consecutive ids
+pub struct Timelock<T> {
+    changes: Map<u64, PendingChange<T>>,
+    next_id: Item<u64>,
+}
+
+impl<T> Timelock<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    pub const fn new(changes_namespace: &'static str, next_id_namespace: &'static str) -> Self {
+        Self {
+            changes: Map::new(changes_namespace),
+            next_id: Item::new(next_id_namespace),
+        }
+    }
+
+    /// Stores the change and returns its id
+    pub fn propose(
+        &self,
+        storage: &mut dyn Storage,
+        change: T,
+        executable_at: Timestamp,
+    ) -> StdResult<u64> {
+        let id = self.next_id.may_load(storage)?.unwrap_or_default();
+        self.next_id.save(storage, &(id + 1))?;
+        self.changes.save(
+            storage,
+            id,
+            &PendingChange {
+                change,
+                executable_at,
+            },
+        )?;
+        Ok(id)
+    }
+
+    /// Removes the change from the queue, failing if its delay hasn't passed yet
+    pub fn take_ready(
+        &self,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        id: u64,
+    ) -> Result<T, TimelockError> {
+        let pending = self.load(storage, id)?;
+        if block.time < pending.executable_at {
+            return Err(TimelockError::NotReady {
+                id,
+                executable_at: pending.executable_at,
+            });
+        }
+        self.changes.remove(storage, id);
+        Ok(pending.change)
+    }
+
+    /// Removes the change from the queue regardless of its delay
+    pub fn cancel(&self, storage: &mut dyn Storage, id: u64) -> Result<T, TimelockError> {
+        let pending = self.load(storage, id)?;
+        self.changes.remove(storage, id);
+        Ok(pending.change)
+    }
+
+    pub fn load(&self, storage: &dyn Storage, id: u64) -> Result<PendingChange<T>, TimelockError> {
+        self.changes
+            .may_load(storage, id)?
+            .ok_or(TimelockError::NotFound(id))
+    }
+
+    /// Pending changes ordered by id
+    pub fn range(
+        &self,
+        storage: &dyn Storage,
+        start_after: Option<u64>,
+        limit: usize,
+    ) -> StdResult<Vec<(u64, PendingChange<T>)>> {
+        self.changes
+            .range(
+                storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .collect()
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum TimelockError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("No pending parameter change with id {0}")]
+    NotFound(u64),
+
+    #[error("Parameter change {id} cannot be executed before {executable_at}")]
+    NotReady { id: u64, executable_at: Timestamp },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use cosmwasm_std::testing::{mock_env, MockStorage};
+
+    const TIMELOCK: Timelock<String> = Timelock::new("changes", "next_change_id");
+
+    #[test]
+    fn change_executable_after_delay() {
+        let mut storage = MockStorage::new();
+        let mut block = mock_env().block;
+        let executable_at = block.time.plus_seconds(100);
+
+        let first = TIMELOCK
+            .propose(&mut storage, "first".to_owned(), executable_at)
+            .unwrap();
+        let second = TIMELOCK
+            .propose(&mut storage, "second".to_owned(), executable_at)
+            .unwrap();
+        assert_eq!((first, second), (0, 1));
+
+        assert_eq!(
+            TIMELOCK.take_ready(&mut storage, &block, first),
+            Err(TimelockError::NotReady {
+                id: first,
+                executable_at
+            })
+        );
+
+        block.time = executable_at;
+        assert_eq!(
+            TIMELOCK.take_ready(&mut storage, &block, first).unwrap(),
+            "first"
+        );
+        assert_eq!(
+            TIMELOCK.take_ready(&mut storage, &block, first),
+            Err(TimelockError::NotFound(first))
+        );
+
+        assert_eq!(TIMELOCK.cancel(&mut storage, second).unwrap(), "second");
+        assert_eq!(TIMELOCK.range(&storage, None, 10).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn range_pages() {
+        let mut storage = MockStorage::new();
+        let executable_at = mock_env().block.time;
+
+        for change in ["a", "b", "c"] {
+            TIMELOCK
+                .propose(&mut storage, change.to_owned(), executable_at)
+                .unwrap();
+        }
+
+        let ids = |changes: Vec<(u64, PendingChange<String>)>| {
+            changes.into_iter().map(|(id, _)| id).collect::<Vec<_>>()
+        };
+        assert_eq!(ids(TIMELOCK.range(&storage, None, 2).unwrap()), vec![0, 1]);
+        assert_eq!(ids(TIMELOCK.range(&storage, Some(1), 2).unwrap()), vec![2]);
+    }
+}
@@ -167,6 +167,28 @@ impl Token {
             Self::Cw20(address) => unimplemented!()
         })
     }
+
+    /// Wyndex route for selling this token for `ask`. Every pool pairs a token with the common
+    /// token, so swap between two other tokens goes through the common token.
+    pub fn swap_operations(&self, ask: &Token, common: &Token) -> Vec<SwapOperation> {
+        if self == common || ask == common {
+            vec![SwapOperation::WyndexSwap {
+                offer_asset_info: self.clone().into(),
+                ask_asset_info: ask.clone().into(),
+            }]
+        } else {
+            vec![
+                SwapOperation::WyndexSwap {
+                    offer_asset_info: self.clone().into(),
+                    ask_asset_info: common.clone().into(),
+                },
+                SwapOperation::WyndexSwap {
+                    offer_asset_info: common.clone().into(),
+                    ask_asset_info: ask.clone().into(),
+                },
+            ]
+        }
+    }
 }
 
 impl fmt::Display for Token {
@@ -234,3 +256,27 @@ impl<'a> PrimaryKey<'a> for &Token {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swap_operations() {
+        let common = Token::Native("common".to_owned());
+        let juno = Token::Native("juno".to_owned());
+        let atom = Token::Native("atom".to_owned());
+
+        let swap = |offer: &Token, ask: &Token| SwapOperation::WyndexSwap {
+            offer_asset_info: offer.clone().into(),
+            ask_asset_info: ask.clone().into(),
+        };
+
+        assert_eq!(common.swap_operations(&juno, &common), vec![swap(&common, &juno)]);
+        assert_eq!(juno.swap_operations(&common, &common), vec![swap(&juno, &common)]);
+        assert_eq!(
+            juno.swap_operations(&atom, &common),
+            vec![swap(&juno, &common), swap(&common, &atom)]
+        );
+    }
+}
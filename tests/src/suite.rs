@@ -15,7 +15,7 @@ use wyndex_oracle::msg::{ExecuteMsg as OracleExecuteMsg, InstantiateMsg as Oracl
 
 use isotonic_credit_agency::msg::{
     ExecuteMsg as CAExecuteMsg, InstantiateMsg, IsOnMarketResponse, ListEnteredMarketsResponse,
-    ListMarketsResponse, MarketResponse, QueryMsg,
+    ListMarketsResponse, MarketResponse, ParamChange, QueryMsg,
 };
 use isotonic_credit_agency::state::Config;
 
@@ -179,6 +179,9 @@ impl SuiteBuilder {
                     common_token: Token::Native(common_token.clone()),
                     liquidation_price: self.liquidation_price,
                     borrow_limit_ratio: Decimal::one(),
+                    liquidation_auction: None,
+                    deleverage_fee: Decimal::zero(),
+                    param_change_delay: 0,
                 },
                 &[],
                 "credit-agency",
@@ -680,21 +683,33 @@ impl Suite {
     pub fn sudo_adjust_market_id(&mut self, new_market_id: u64) -> AnyResult<AppResponse> {
         let contract = self.credit_agency.clone();
         self.app
-            .wasm_sudo(contract, &CAExecuteMsg::AdjustMarketId { new_market_id })
+            .wasm_sudo(
+                contract,
+                &CAExecuteMsg::ProposeParamChange {
+                    change: ParamChange::MarketId { new_market_id },
+                },
+            )
     }
 
     pub fn sudo_adjust_token_id(&mut self, new_token_id: u64) -> AnyResult<AppResponse> {
         let contract = self.credit_agency.clone();
         self.app
-            .wasm_sudo(contract, &CAExecuteMsg::AdjustTokenId { new_token_id })
+            .wasm_sudo(
+                contract,
+                &CAExecuteMsg::ProposeParamChange {
+                    change: ParamChange::TokenId { new_token_id },
+                },
+            )
     }
 
     pub fn sudo_adjust_common_token(&mut self, new_common_token: &str) -> AnyResult<AppResponse> {
         let contract = self.credit_agency.clone();
         self.app.wasm_sudo(
             contract,
-            &CAExecuteMsg::AdjustCommonToken {
-                new_common_token: Token::Native(new_common_token.to_owned()),
+            &CAExecuteMsg::ProposeParamChange {
+                change: ParamChange::CommonToken {
+                    new_common_token: Token::Native(new_common_token.to_owned()),
+                },
             },
         )
     }